@@ -0,0 +1,625 @@
+//! FlowNode CLI - run saved graphs without the GUI
+//!
+//! Loads a graph saved by the canvas (native JSON or React Flow JSON), binds
+//! input files to its Image and Bucket nodes, runs it and writes every Output
+//! node's result. Meant for build servers reusing graphs designers made.
+//!
+//! ```text
+//! flownode-cli grade.json -i "shots/*.jpg" -o "out/{stem}.{ext}"
+//! flownode-cli grade.json -i photo=in.png -i overlay=logo.png -o result.png
+//! ```
+
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+
+use uuid::Uuid;
+
+use flownode_core::color::WorkingSpace;
+use flownode_core::compat::ReactFlowWorkflow;
+use flownode_core::executor::Executor;
+use flownode_core::graph::NodeGraph;
+use flownode_core::image_data::{self, ImageData, ImageLimits};
+use flownode_core::nodes::{ExportDepth, ExportFormat, Node, NodeProperties, NodeType};
+
+const USAGE: &str = "\
+Usage: flownode-cli GRAPH [OPTIONS]
+
+Runs a saved FlowNode graph (native or React Flow JSON) and writes its outputs.
+
+Options:
+  -i, --input [NODE=]PATTERN  Bind files to an Image or Bucket node. NODE is a
+                              node label, ID or number from --list; without it
+                              the next unbound input node is used. PATTERN may
+                              use * and ? wildcards. Repeatable.
+  -o, --output TEMPLATE       Output path (default: {stem}-{output}.{ext})
+                              {stem}   file name of the run's first input
+                              {output} Output node name
+                              {index}  run number, from 1
+                              {item}   batch item number, from 001
+                              {ext}    extension of the output's format
+      --working-space SPACE   Override the graph's working space
+                              (linear-srgb, linear-display-p3, srgb)
+      --gpu                   Run node operations on the GPU (built with the
+                              `gpu` feature); falls back to the CPU without one
+      --list                  List the graph's input and output nodes and exit
+  -h, --help                  Show this help
+
+An Image node bound to several files runs the graph once per file; a Bucket
+node takes all of its files in a single run.
+
+Exit codes: 0 success, 1 processing error, 2 bad arguments";
+
+const DEFAULT_TEMPLATE: &str = "{stem}-{output}.{ext}";
+
+/// A failure and the exit code it maps to
+#[derive(Debug)]
+enum CliError {
+    Usage(String),
+    Failed(String),
+}
+
+impl CliError {
+    fn exit_code(&self) -> u8 {
+        match self {
+            CliError::Usage(_) => 2,
+            CliError::Failed(_) => 1,
+        }
+    }
+}
+
+/// Parsed command line
+struct Args {
+    graph: PathBuf,
+    inputs: Vec<(Option<String>, String)>,
+    template: String,
+    working_space: Option<WorkingSpace>,
+    gpu: bool,
+    list: bool,
+}
+
+/// Files bound to one input node
+struct Binding {
+    node_id: Uuid,
+    bucket: bool,
+    files: Vec<PathBuf>,
+}
+
+fn main() -> ExitCode {
+    env_logger::init();
+
+    let result = parse_args(std::env::args().skip(1)).and_then(|args| match args {
+        Some(args) => run(&args),
+        None => {
+            println!("{}", USAGE);
+            Ok(())
+        }
+    });
+
+    match result {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(error) => {
+            match &error {
+                CliError::Usage(message) => eprintln!("error: {}\n\n{}", message, USAGE),
+                CliError::Failed(message) => eprintln!("error: {}", message),
+            }
+            ExitCode::from(error.exit_code())
+        }
+    }
+}
+
+/// Switch the executor to the GPU backend if one can be opened
+#[cfg(feature = "gpu")]
+fn use_gpu(executor: &mut Executor) {
+    match flownode_core::gpu::GpuContext::new() {
+        Ok(gpu) => {
+            log::info!("Using {}", flownode_core::backend::ProcessingBackend::name(&gpu));
+            executor.set_backend(std::sync::Arc::new(gpu));
+        }
+        Err(e) => log::warn!("{}; running on the CPU", e),
+    }
+}
+
+#[cfg(not(feature = "gpu"))]
+fn use_gpu(_executor: &mut Executor) {
+    log::warn!("Built without the `gpu` feature; running on the CPU");
+}
+
+/// Parse the arguments; `None` when help was asked for
+fn parse_args(mut args: impl Iterator<Item = String>) -> Result<Option<Args>, CliError> {
+    let mut graph = None;
+    let mut inputs = Vec::new();
+    let mut template = DEFAULT_TEMPLATE.to_string();
+    let mut working_space = None;
+    let mut gpu = false;
+    let mut list = false;
+
+    while let Some(arg) = args.next() {
+        let mut value = |name: &str| args.next().ok_or_else(|| CliError::Usage(format!("{} needs a value", name)));
+        match arg.as_str() {
+            "-h" | "--help" => return Ok(None),
+            "-i" | "--input" => {
+                let spec = value(&arg)?;
+                inputs.push(match spec.split_once('=') {
+                    Some((node, pattern)) => (Some(node.to_string()), pattern.to_string()),
+                    None => (None, spec),
+                });
+            }
+            "-o" | "--output" => template = value(&arg)?,
+            "--working-space" => {
+                let name = value(&arg)?;
+                working_space = Some(WorkingSpace::parse(&name)
+                    .ok_or_else(|| CliError::Usage(format!("Unknown working space: {}", name)))?);
+            }
+            "--gpu" => gpu = true,
+            "--list" => list = true,
+            _ if arg.starts_with('-') => return Err(CliError::Usage(format!("Unknown option: {}", arg))),
+            _ if graph.is_none() => graph = Some(PathBuf::from(arg)),
+            _ => return Err(CliError::Usage(format!("Unexpected argument: {}", arg))),
+        }
+    }
+
+    let graph = graph.ok_or_else(|| CliError::Usage("No graph file given".to_string()))?;
+    Ok(Some(Args { graph, inputs, template, working_space, gpu, list }))
+}
+
+fn run(args: &Args) -> Result<(), CliError> {
+    let mut graph = load_graph(&args.graph)?;
+    if let Some(space) = args.working_space {
+        graph.working_space = space;
+    }
+
+    let input_nodes = sorted_nodes(&graph, |t| matches!(t, NodeType::Image | NodeType::Bucket));
+    let output_nodes = sorted_nodes(&graph, |t| t == NodeType::Output);
+
+    if args.list {
+        println!("Inputs:");
+        for (i, node) in input_nodes.iter().enumerate() {
+            println!("  {}  {:<7} {}  {}", i + 1, node.node_type.name(), node.id, node.label.as_deref().unwrap_or(""));
+        }
+        println!("Outputs:");
+        for node in &output_nodes {
+            println!("     {:<7} {}  {}", node.node_type.name(), node.id, output_settings(node).0);
+        }
+        return Ok(());
+    }
+
+    if output_nodes.is_empty() {
+        return Err(CliError::Failed("The graph has no Output nodes".to_string()));
+    }
+    let bindings = bind_inputs(&args.inputs, &input_nodes)?;
+
+    // Image nodes bound to several files drive one run per file
+    let runs = bindings.iter()
+        .filter(|b| !b.bucket)
+        .map(|b| b.files.len())
+        .max()
+        .unwrap_or(1);
+    if let Some(b) = bindings.iter().find(|b| !b.bucket && b.files.len() != 1 && b.files.len() != runs) {
+        return Err(CliError::Usage(format!(
+            "Inputs bound to Image nodes must match 1 or {} files, but one matched {}",
+            runs, b.files.len()
+        )));
+    }
+    for node in &input_nodes {
+        if node.node_type == NodeType::Image && !bindings.iter().any(|b| b.node_id == node.id) {
+            log::warn!("Image node {} has no input bound", node_name(node));
+        }
+    }
+
+    let limits = ImageLimits::default();
+    let mut executor = Executor::new();
+    executor.set_limits(limits);
+    if args.gpu {
+        use_gpu(&mut executor);
+    }
+    let mut images: HashMap<u64, ImageData> = HashMap::new();
+    let mut next_image_id = 0;
+
+    // Bucket files are the same for every run, so load them once
+    for binding in bindings.iter().filter(|b| b.bucket) {
+        for file in &binding.files {
+            images.insert(next_image_id, load_image(file, &limits)?);
+            graph.set_node_image(binding.node_id, next_image_id);
+            next_image_id += 1;
+        }
+    }
+
+    let mut written = HashSet::new();
+    for run_index in 0..runs {
+        let mut stem = None;
+        for binding in bindings.iter().filter(|b| !b.bucket) {
+            let file = &binding.files[run_index.min(binding.files.len() - 1)];
+            stem.get_or_insert_with(|| file_stem(file));
+
+            // A single file stays bound for every run, keeping its nodes cached
+            if binding.files.len() == 1 && run_index > 0 {
+                continue;
+            }
+
+            // Fresh IDs so only the nodes whose file changed are recomputed
+            if let Some(NodeProperties::Image { texture_id: Some(old), .. }) = graph.nodes.get(&binding.node_id).map(|n| &n.properties) {
+                images.remove(old);
+            }
+            images.insert(next_image_id, load_image(file, &limits)?);
+            graph.set_node_image(binding.node_id, next_image_id);
+            next_image_id += 1;
+        }
+        let stem = stem
+            .or_else(|| bindings.first().and_then(|b| b.files.first()).map(|f| file_stem(f)))
+            .unwrap_or_else(|| "output".to_string());
+
+        let results = executor.execute(&graph, &images).map_err(CliError::Failed)?;
+        if results.is_empty() {
+            return Err(CliError::Failed("No Output node produced an image (are the inputs bound?)".to_string()));
+        }
+
+        for node in &output_nodes {
+            let Some(items) = results.get(&node.id) else {
+                log::warn!("Output {} produced nothing", node_name(node));
+                continue;
+            };
+            let (name, format, depth) = output_settings(node);
+            let template = complete_template(&args.template, runs > 1, output_nodes.len() > 1, items.len() > 1);
+
+            for (item, image) in items.iter().enumerate() {
+                let path = PathBuf::from(template
+                    .replace("{stem}", &stem)
+                    .replace("{output}", &name)
+                    .replace("{index}", &(run_index + 1).to_string())
+                    .replace("{item}", &format!("{:03}", item + 1))
+                    .replace("{ext}", format.extension()));
+                if !written.insert(path.clone()) {
+                    return Err(CliError::Usage(format!(
+                        "Output template writes {} more than once; add {{stem}}, {{index}}, {{output}} or {{item}}",
+                        path.display()
+                    )));
+                }
+                write_output(&path, image, format, depth)?;
+                println!("{}", path.display());
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Load a native graph, falling back to React Flow JSON
+fn load_graph(path: &Path) -> Result<NodeGraph, CliError> {
+    let json = std::fs::read_to_string(path)
+        .map_err(|e| CliError::Failed(format!("Failed to read {}: {}", path.display(), e)))?;
+
+    match NodeGraph::from_json(&json) {
+        Ok(graph) => Ok(graph),
+        Err(native_error) => serde_json::from_str::<ReactFlowWorkflow>(&json)
+            .map_err(|e| e.to_string())
+            .and_then(|workflow| workflow.to_graph())
+            .map(|(graph, _, _)| graph)
+            .map_err(|e| CliError::Failed(format!(
+                "{} is not a FlowNode graph ({}) or React Flow workflow ({})",
+                path.display(), native_error, e
+            ))),
+    }
+}
+
+/// Nodes of the given types in canvas order: top to bottom, then left to right
+fn sorted_nodes(graph: &NodeGraph, filter: impl Fn(NodeType) -> bool) -> Vec<Node> {
+    let mut nodes: Vec<Node> = graph.nodes.values().filter(|n| filter(n.node_type)).cloned().collect();
+    nodes.sort_by(|a, b| {
+        a.position.y.total_cmp(&b.position.y)
+            .then(a.position.x.total_cmp(&b.position.x))
+            .then(a.id.cmp(&b.id))
+    });
+    nodes
+}
+
+/// Resolve each `[NODE=]PATTERN` to an input node and the files it matches
+fn bind_inputs(inputs: &[(Option<String>, String)], nodes: &[Node]) -> Result<Vec<Binding>, CliError> {
+    let mut bindings: Vec<Binding> = Vec::new();
+
+    for (node_ref, pattern) in inputs {
+        let bound = |id: Uuid| bindings.iter().any(|b| b.node_id == id);
+        let node = match node_ref {
+            Some(name) => find_node(nodes, name)
+                .ok_or_else(|| CliError::Usage(format!("No Image or Bucket node called {} (see --list)", name)))?,
+            None => nodes.iter()
+                .find(|n| !bound(n.id))
+                .ok_or_else(|| CliError::Usage(format!("No unbound input node left for {}", pattern)))?,
+        };
+        if bound(node.id) {
+            return Err(CliError::Usage(format!("{} is bound twice", node_name(node))));
+        }
+
+        let files = glob(pattern).map_err(CliError::Failed)?;
+        if files.is_empty() {
+            return Err(CliError::Failed(format!("No files match {}", pattern)));
+        }
+        bindings.push(Binding {
+            node_id: node.id,
+            bucket: node.node_type == NodeType::Bucket,
+            files,
+        });
+    }
+
+    Ok(bindings)
+}
+
+/// Find an input node by label, ID or 1-based number in canvas order
+fn find_node<'a>(nodes: &'a [Node], name: &str) -> Option<&'a Node> {
+    if let Ok(number) = name.parse::<usize>() {
+        return number.checked_sub(1).and_then(|i| nodes.get(i));
+    }
+    nodes.iter().find(|n| n.label.as_deref() == Some(name) || n.id.to_string() == name)
+}
+
+fn node_name(node: &Node) -> String {
+    match &node.label {
+        Some(label) => format!("{} \"{}\"", node.node_type.name(), label),
+        None => format!("{} {}", node.node_type.name(), node.id),
+    }
+}
+
+/// Name, format and bit depth of an Output node
+fn output_settings(node: &Node) -> (String, ExportFormat, ExportDepth) {
+    match &node.properties {
+        NodeProperties::Output { name, format, bit_depth, .. } => {
+            let name = if name.trim().is_empty() { "output" } else { name.trim() };
+            (name.to_string(), *format, *bit_depth)
+        }
+        _ => ("output".to_string(), ExportFormat::Png, ExportDepth::Eight),
+    }
+}
+
+/// Add the placeholders a template needs so no two files share a path
+fn complete_template(template: &str, many_runs: bool, many_outputs: bool, many_items: bool) -> String {
+    let mut suffix = String::new();
+    if many_runs && !template.contains("{stem}") && !template.contains("{index}") {
+        suffix.push_str("-{index}");
+    }
+    if many_outputs && !template.contains("{output}") {
+        suffix.push_str("-{output}");
+    }
+    if many_items && !template.contains("{item}") {
+        suffix.push_str("-{item}");
+    }
+
+    match template.rfind('.') {
+        Some(dot) if !template[dot..].contains('/') => format!("{}{}{}", &template[..dot], suffix, &template[dot..]),
+        _ => format!("{}{}", template, suffix),
+    }
+}
+
+fn file_stem(path: &Path) -> String {
+    path.file_stem().map(|s| s.to_string_lossy().to_string()).unwrap_or_default()
+}
+
+fn load_image(path: &Path, limits: &ImageLimits) -> Result<ImageData, CliError> {
+    let bytes = std::fs::read(path)
+        .map_err(|e| CliError::Failed(format!("Failed to read {}: {}", path.display(), e)))?;
+    image_data::decode_image(&bytes, limits)
+        .map_err(|e| CliError::Failed(format!("{}: {}", path.display(), e)))
+}
+
+fn write_output(path: &Path, image: &ImageData, format: ExportFormat, depth: ExportDepth) -> Result<(), CliError> {
+    let bytes = image_data::encode_image(image, format, depth)
+        .map_err(|e| CliError::Failed(format!("Failed to encode {}: {}", path.display(), e)))?;
+    if let Some(dir) = path.parent().filter(|d| !d.as_os_str().is_empty()) {
+        std::fs::create_dir_all(dir)
+            .map_err(|e| CliError::Failed(format!("Failed to create {}: {}", dir.display(), e)))?;
+    }
+    std::fs::write(path, bytes)
+        .map_err(|e| CliError::Failed(format!("Failed to write {}: {}", path.display(), e)))
+}
+
+/// Expand `*` and `?` wildcards in any path component, sorted. A pattern
+/// without wildcards is returned as is if the file exists.
+fn glob(pattern: &str) -> Result<Vec<PathBuf>, String> {
+    let path = Path::new(pattern);
+    if !pattern.contains(['*', '?']) {
+        return if path.is_file() {
+            Ok(vec![path.to_path_buf()])
+        } else {
+            Err(format!("{} is not a file", pattern))
+        };
+    }
+
+    let mut matches = vec![PathBuf::new()];
+    for component in path.components() {
+        let part = component.as_os_str().to_string_lossy();
+        if !part.contains(['*', '?']) {
+            for m in &mut matches {
+                m.push(component);
+            }
+            continue;
+        }
+
+        let mut next = Vec::new();
+        for dir in &matches {
+            let read_from = if dir.as_os_str().is_empty() { Path::new(".") } else { dir.as_path() };
+            let Ok(entries) = std::fs::read_dir(read_from) else {
+                continue;
+            };
+            for entry in entries.flatten() {
+                let name = entry.file_name().to_string_lossy().to_string();
+                // Like shells, only match hidden files when asked to
+                if name.starts_with('.') && !part.starts_with('.') {
+                    continue;
+                }
+                if wildcard_match(&part, &name) {
+                    next.push(dir.join(&name));
+                }
+            }
+        }
+        matches = next;
+    }
+
+    matches.retain(|p| p.is_file());
+    matches.sort();
+    Ok(matches)
+}
+
+/// Match a file name against a pattern with `*` (any run) and `?` (one character)
+fn wildcard_match(pattern: &str, name: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let name: Vec<char> = name.chars().collect();
+    let (mut p, mut n) = (0, 0);
+    let mut backtrack: Option<(usize, usize)> = None;
+
+    while n < name.len() {
+        if p < pattern.len() && (pattern[p] == '?' || pattern[p] == name[n]) {
+            p += 1;
+            n += 1;
+        } else if p < pattern.len() && pattern[p] == '*' {
+            backtrack = Some((p, n));
+            p += 1;
+        } else if let Some((star, matched)) = backtrack {
+            // Let the last * swallow one more character
+            p = star + 1;
+            n = matched + 1;
+            backtrack = Some((star, n));
+        } else {
+            return false;
+        }
+    }
+    pattern[p..].iter().all(|&c| c == '*')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use flownode_core::nodes::Vec2;
+
+    /// Fresh directory under the system temp dir holding empty `files`
+    fn temp_dir(name: &str, files: &[&str]) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("flownode-cli-{}-{}", name, std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        for file in files {
+            let path = dir.join(file);
+            std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+            std::fs::write(path, []).unwrap();
+        }
+        dir
+    }
+
+    fn names(paths: &[PathBuf], dir: &Path) -> Vec<String> {
+        paths.iter().map(|p| p.strip_prefix(dir).unwrap().to_string_lossy().to_string()).collect()
+    }
+
+    fn input_node(node_type: NodeType, label: &str) -> Node {
+        let mut node = Node::new(node_type, Vec2::ZERO);
+        node.label = Some(label.to_string());
+        node
+    }
+
+    #[test]
+    fn wildcards() {
+        assert!(wildcard_match("*.png", "a.png"));
+        assert!(wildcard_match("*.png", ".png"));
+        assert!(!wildcard_match("*.png", "a.jpg"));
+        assert!(wildcard_match("shot-??.jpg", "shot-01.jpg"));
+        assert!(!wildcard_match("shot-??.jpg", "shot-1.jpg"));
+        assert!(wildcard_match("*", ""));
+        assert!(!wildcard_match("?", ""));
+    }
+
+    #[test]
+    fn wildcards_backtrack() {
+        // The first * has to give back what it matched for the rest to fit
+        assert!(wildcard_match("*a*b", "xaxaxb"));
+        assert!(wildcard_match("*.tar.gz", "a.tar.tar.gz"));
+        assert!(wildcard_match("a*b*c", "abcbc"));
+        assert!(!wildcard_match("a*b*c", "abcb"));
+        assert!(wildcard_match("**x", "yx"));
+    }
+
+    #[test]
+    fn glob_expands_sorted_and_skips_hidden_files() {
+        let dir = temp_dir("glob", &["b.png", "a.png", ".hidden.png", "c.jpg", "sub/d.png", "sub/e.jpg"]);
+        let pattern = |p: &str| dir.join(p).to_string_lossy().to_string();
+
+        assert_eq!(names(&glob(&pattern("*.png")).unwrap(), &dir), ["a.png", "b.png"]);
+        assert_eq!(names(&glob(&pattern(".*")).unwrap(), &dir), [".hidden.png"]);
+        assert_eq!(names(&glob(&pattern("?.jpg")).unwrap(), &dir), ["c.jpg"]);
+        assert_eq!(names(&glob(&pattern("s*/*.png")).unwrap(), &dir), ["sub/d.png"]);
+        // Directories aren't inputs
+        assert!(glob(&pattern("s*")).unwrap().is_empty());
+        // Without wildcards the file has to exist
+        assert_eq!(glob(&pattern("a.png")).unwrap().len(), 1);
+        assert!(glob(&pattern("missing.png")).is_err());
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn templates_get_the_placeholders_they_lack() {
+        assert_eq!(complete_template("out.png", false, false, false), "out.png");
+        assert_eq!(complete_template("out.png", true, false, false), "out-{index}.png");
+        assert_eq!(complete_template("out.png", false, true, false), "out-{output}.png");
+        assert_eq!(complete_template("out.png", false, false, true), "out-{item}.png");
+        assert_eq!(complete_template("out.png", true, true, true), "out-{index}-{output}-{item}.png");
+        // Placeholders already there aren't added again
+        assert_eq!(complete_template("{stem}.{ext}", true, false, false), "{stem}.{ext}");
+        assert_eq!(complete_template("{index}-{output}-{item}.png", true, true, true), "{index}-{output}-{item}.png");
+        // No extension, or a dot only in a directory name
+        assert_eq!(complete_template("out", true, false, false), "out-{index}");
+        assert_eq!(complete_template("v1.2/out", true, false, false), "v1.2/out-{index}");
+    }
+
+    #[test]
+    fn inputs_bind_by_name_number_or_order() {
+        let dir = temp_dir("bind", &["a.png", "b.png", "c.png"]);
+        let pattern = |p: &str| dir.join(p).to_string_lossy().to_string();
+        let nodes = [
+            input_node(NodeType::Image, "photo"),
+            input_node(NodeType::Bucket, "set"),
+            input_node(NodeType::Image, "overlay"),
+        ];
+
+        // Named, then the next unbound in order, then by number
+        let inputs = [
+            (Some("set".to_string()), pattern("*.png")),
+            (None, pattern("a.png")),
+            (Some("3".to_string()), pattern("b.png")),
+        ];
+        let Ok(bindings) = bind_inputs(&inputs, &nodes) else { panic!("inputs didn't bind") };
+        let bound: Vec<_> = bindings.iter().map(|b| (b.node_id, b.bucket, b.files.len())).collect();
+        assert_eq!(bound, [(nodes[1].id, true, 3), (nodes[0].id, false, 1), (nodes[2].id, false, 1)]);
+
+        // By ID
+        let inputs = [(Some(nodes[2].id.to_string()), pattern("c.png"))];
+        assert!(matches!(bind_inputs(&inputs, &nodes), Ok(b) if b[0].node_id == nodes[2].id));
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn bad_bindings_are_errors() {
+        let dir = temp_dir("bind-errors", &["a.png"]);
+        let pattern = |p: &str| dir.join(p).to_string_lossy().to_string();
+        let nodes = [input_node(NodeType::Image, "photo")];
+        let error = |inputs: &[(Option<String>, String)]| bind_inputs(inputs, &nodes).err().map(|e| e.exit_code());
+
+        assert_eq!(error(&[(Some("nope".to_string()), pattern("a.png"))]), Some(2));
+        assert_eq!(error(&[(Some("2".to_string()), pattern("a.png"))]), Some(2));
+        assert_eq!(error(&[(None, pattern("a.png")), (Some("photo".to_string()), pattern("a.png"))]), Some(2));
+        assert_eq!(error(&[(None, pattern("a.png")), (None, pattern("a.png"))]), Some(2));
+        assert_eq!(error(&[(None, pattern("*.jpg"))]), Some(1));
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn usage_errors_exit_2_and_failures_1() {
+        assert_eq!(CliError::Usage(String::new()).exit_code(), 2);
+        assert_eq!(CliError::Failed(String::new()).exit_code(), 1);
+
+        let args = |list: &[&str]| parse_args(list.iter().map(|s| s.to_string()));
+        assert_eq!(args(&[]).err().map(|e| e.exit_code()), Some(2));
+        assert_eq!(args(&["g.json", "--bogus"]).err().map(|e| e.exit_code()), Some(2));
+        assert_eq!(args(&["g.json", "-i"]).err().map(|e| e.exit_code()), Some(2));
+        assert!(matches!(args(&["--help"]), Ok(None)));
+
+        let run_missing_graph = |graph: &str| args(&[graph]).and_then(|a| run(&a.unwrap()));
+        assert_eq!(run_missing_graph("/nonexistent/graph.json").err().map(|e| e.exit_code()), Some(1));
+    }
+}
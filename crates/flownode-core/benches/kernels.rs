@@ -0,0 +1,148 @@
+//! Pixel kernel benchmarks: one single-node graph per effect, run from scratch
+//!
+//! `cargo bench -p flownode-core` runs them on all cores; add
+//! `--no-default-features` to measure the single-threaded fallback.
+
+use std::collections::HashMap;
+
+use criterion::{criterion_group, criterion_main, Criterion};
+use flownode_core::executor::Executor;
+use flownode_core::graph::NodeGraph;
+use flownode_core::image_data::ImageData;
+use flownode_core::lut::Lut;
+use flownode_core::nodes::{BlendMode, ColorWheel, CompareMode, CompositionLayer, Node, NodeProperties, NodeType, Vec2};
+
+const WIDTH: u32 = 1920;
+const HEIGHT: u32 = 1080;
+const IMAGE_ID: u64 = 1;
+
+/// Gradients and noise, half transparent at the bottom
+fn test_image() -> ImageData {
+    let mut seed = 0x9e37_79b9_u32;
+    let mut pixels = Vec::with_capacity((WIDTH * HEIGHT * 4) as usize);
+    for y in 0..HEIGHT {
+        for x in 0..WIDTH {
+            seed ^= seed << 13;
+            seed ^= seed >> 17;
+            seed ^= seed << 5;
+            let noise = (seed % 1000) as f32 / 1000.0;
+            let alpha = if y > HEIGHT / 2 { 0.5 } else { 1.0 };
+            pixels.extend_from_slice(&[x as f32 / WIDTH as f32, y as f32 / HEIGHT as f32, noise, alpha]);
+        }
+    }
+    ImageData::from_f32(pixels, WIDTH, HEIGHT)
+}
+
+/// Image -> node -> Output, with the image on every input of the node
+fn graph(node_type: NodeType, inputs: usize, setup: impl FnOnce(&mut NodeProperties)) -> NodeGraph {
+    let mut graph = NodeGraph::new();
+    let mut image = Node::new(NodeType::Image, Vec2::new(0.0, 0.0));
+    if let NodeProperties::Image { texture_id, .. } = &mut image.properties {
+        *texture_id = Some(IMAGE_ID);
+    }
+    let mut node = Node::new(node_type, Vec2::new(200.0, 0.0));
+    setup(&mut node.properties);
+    let output = Node::new(NodeType::Output, Vec2::new(400.0, 0.0));
+
+    let (image_id, node_id, output_id) = (image.id, node.id, output.id);
+    graph.insert_node(image);
+    graph.insert_node(node);
+    graph.insert_node(output);
+    for slot in 0..inputs {
+        graph.add_connection(image_id, 0, node_id, slot).expect("connect input");
+    }
+    graph.add_connection(node_id, 0, output_id, 0).expect("connect output");
+    graph
+}
+
+fn effect(setup: impl FnOnce(&mut NodeProperties)) -> NodeGraph {
+    graph(NodeType::Effects, 1, setup)
+}
+
+fn adjust(setup: impl FnOnce(&mut NodeProperties)) -> NodeGraph {
+    graph(NodeType::Adjust, 1, setup)
+}
+
+/// 17³ table that swaps red and blue
+fn swap_lut() -> Lut {
+    let size = 17;
+    let mut text = format!("LUT_3D_SIZE {}\n", size);
+    for b in 0..size {
+        for g in 0..size {
+            for r in 0..size {
+                let v = |i: usize| i as f32 / (size - 1) as f32;
+                text += &format!("{} {} {}\n", v(b), v(g), v(r));
+            }
+        }
+    }
+    Lut::parse_cube(&text).expect("valid cube")
+}
+
+/// LUT node with [`swap_lut`] loaded into the graph's store
+fn lut_graph() -> NodeGraph {
+    let mut graph = graph(NodeType::Lut, 1, |_| {});
+    let lut_id = graph.nodes.values().find(|n| n.node_type == NodeType::Lut).expect("LUT node").id;
+    graph.set_node_lut(lut_id, "swap.cube", swap_lut());
+    graph
+}
+
+fn benches() -> Vec<(&'static str, NodeGraph)> {
+    vec![
+        ("passthrough", effect(|_| {})),
+        ("gaussian_blur", effect(|p| if let NodeProperties::Effects { gaussian_blur, .. } = p { *gaussian_blur = 20.0 })),
+        ("directional_blur", effect(|p| if let NodeProperties::Effects { directional_blur, .. } = p { *directional_blur = 30.0 })),
+        ("progressive_blur", effect(|p| if let NodeProperties::Effects { progressive_blur, .. } = p { *progressive_blur = 50.0 })),
+        ("glass_blinds", effect(|p| if let NodeProperties::Effects { glass_blinds, .. } = p { *glass_blinds = 50.0 })),
+        ("sharpen", effect(|p| if let NodeProperties::Effects { sharpen, .. } = p { *sharpen = 50.0 })),
+        ("grain", effect(|p| if let NodeProperties::Effects { grain, .. } = p { *grain = 50.0 })),
+        ("vignette", effect(|p| if let NodeProperties::Effects { vignette, .. } = p { *vignette = 50.0 })),
+        ("adjust", adjust(|p| if let NodeProperties::Adjust { brightness, contrast, saturation, hue_rotation, .. } = p {
+            *brightness = 10.0;
+            *contrast = 20.0;
+            *saturation = -15.0;
+            *hue_rotation = 30.0;
+        })),
+        ("color_wheels", adjust(|p| if let NodeProperties::Adjust { lift, gain, .. } = p {
+            *lift = ColorWheel { x: 0.3, y: -0.2, luminance: 0.0 };
+            *gain = ColorWheel { x: -0.1, y: 0.4, luminance: 10.0 };
+        })),
+        ("curves", adjust(|p| if let NodeProperties::Adjust { curves_enabled, curves, .. } = p {
+            *curves_enabled = true;
+            curves.master.points = vec![[0.0, 0.0], [0.25, 0.3], [0.75, 0.7], [1.0, 1.0]];
+        })),
+        ("luminance_mix", adjust(|p| if let NodeProperties::Adjust { saturation, luminance_mix, .. } = p {
+            *saturation = 40.0;
+            *luminance_mix = 50.0;
+        })),
+        ("lut", lut_graph()),
+        ("compare_difference", graph(NodeType::Compare, 2, |p| if let NodeProperties::Compare { mode, .. } = p {
+            *mode = CompareMode::Difference;
+        })),
+        ("compare_onion_skin", graph(NodeType::Compare, 2, |p| if let NodeProperties::Compare { mode, .. } = p {
+            *mode = CompareMode::OnionSkin;
+        })),
+        ("composition", graph(NodeType::Composition, 2, |p| if let NodeProperties::Composition { layers } = p {
+            *layers = vec![
+                CompositionLayer::default(),
+                CompositionLayer { opacity: 0.6, blend_mode: BlendMode::Screen, position: (100.0, 50.0), scale: 0.75, ..Default::default() },
+            ];
+        })),
+    ]
+}
+
+fn kernels(c: &mut Criterion) {
+    let images = HashMap::from([(IMAGE_ID, test_image())]);
+    let mut group = c.benchmark_group("kernels");
+    group.sample_size(10);
+
+    for (name, graph) in benches() {
+        group.bench_function(name, |b| {
+            // A fresh executor each time so nothing is served from the cache
+            b.iter(|| Executor::new().execute(&graph, &images).expect("graph runs"));
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches_group, kernels);
+criterion_main!(benches_group);
@@ -0,0 +1,90 @@
+//! Where node operations run
+//!
+//! The executor hands every image operation to a [`ProcessingBackend`]. It
+//! decides what to compute (node properties, tiling, colour spaces); the
+//! backend only computes it. [`CpuBackend`](crate::cpu::CpuBackend) runs the
+//! kernels on the CPU; with the `gpu` feature
+//! [`GpuContext`](crate::gpu::GpuContext) runs them as wgpu compute shaders.
+//!
+//! Images are straight-alpha RGBA in the working space, 0-1 nominally.
+
+use crate::image_data::ImageData;
+use crate::lut::Lut;
+use crate::mask::Mask;
+use crate::nodes::{BlurDirection, ColorWheel, CompareMode, CompositionLayer, Curves, EdgeMode, LutInterpolation};
+
+/// Blur used by Sharpen's unsharp mask, in pixels
+pub const SHARPEN_SIGMA: f32 = 0.8;
+
+/// Basic Adjust node settings, as on the node (mostly -100 to 100)
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct Adjustments {
+    pub brightness: f32,
+    pub contrast: f32,
+    pub saturation: f32,
+    pub exposure: f32,
+    pub highlights: f32,
+    pub shadows: f32,
+    pub temperature: f32,
+    pub tint: f32,
+    pub vibrance: f32,
+    pub gamma: f32,
+    pub color_boost: f32,
+    /// Degrees
+    pub hue_rotation: f32,
+}
+
+/// Image operations the executor needs. Every backend must give the same
+/// results as the CPU one, within floating point error.
+pub trait ProcessingBackend: Send + Sync {
+    /// Short name for logs, e.g. "CPU" or the GPU adapter
+    fn name(&self) -> String;
+
+    /// Exposure, contrast, saturation and the other basic adjustments
+    fn adjust(&self, img: &ImageData, params: &Adjustments) -> ImageData;
+
+    /// Lift/gamma/gain/offset colour grading
+    fn color_wheels(&self, img: &ImageData, lift: &ColorWheel, gamma: &ColorWheel, gain: &ColorWheel, offset: &ColorWheel) -> ImageData;
+
+    /// Each channel through its own curve, then the master curve
+    fn curves(&self, img: &ImageData, curves: &Curves) -> ImageData;
+
+    /// Map colours through a lookup table, mixed with the original by `intensity` (0-1)
+    fn lut(&self, img: &ImageData, lut: &Lut, interpolation: LutInterpolation, intensity: f32) -> ImageData;
+
+    /// Blend the luminance of `adjusted` back towards `original`, keeping the
+    /// adjusted colour. 100 keeps the adjusted luminance.
+    fn luminance_mix(&self, original: &ImageData, adjusted: &ImageData, mix: f32) -> ImageData;
+
+    /// Gaussian blur with standard deviation `sigma` pixels
+    fn gaussian_blur(&self, img: &ImageData, sigma: f32, edge: EdgeMode) -> ImageData;
+
+    /// Motion blur along `angle` degrees; `amount` 0-1
+    fn directional_blur(&self, img: &ImageData, amount: f32, angle: f32, edge: EdgeMode) -> ImageData;
+
+    /// Blur growing towards one side; `amount` and `falloff` 0-1
+    fn progressive_blur(&self, img: &ImageData, amount: f32, direction: &BlurDirection, falloff: f32, edge: EdgeMode) -> ImageData;
+
+    /// Wave distortion; `intensity` and `phase` 0-1
+    fn glass_blinds(&self, img: &ImageData, intensity: f32, frequency: f32, angle: f32, phase: f32) -> ImageData;
+
+    /// Unsharp mask with a [`SHARPEN_SIGMA`] blur; `amount` 0-1
+    fn sharpen(&self, img: &ImageData, amount: f32, edge: EdgeMode) -> ImageData;
+
+    /// Film grain in blocks of `size` pixels, reproducible from `seed`
+    fn grain(&self, img: &ImageData, amount: f32, size: f32, monochrome: bool, seed: u32) -> ImageData;
+
+    /// Darken towards the corners; all settings 0-1
+    fn vignette(&self, img: &ImageData, intensity: f32, roundness: f32, smoothness: f32) -> ImageData;
+
+    /// Composite layers bottom to top. `inputs` are (slot, image) pairs; the
+    /// slot picks the layer settings. The canvas takes the size of the lowest layer.
+    fn composition(&self, inputs: &[(usize, ImageData)], layers: &[CompositionLayer]) -> ImageData;
+
+    /// Visualise two same-sized images for comparison
+    fn compare(&self, a: &ImageData, b: &ImageData, mode: CompareMode, split: f32) -> ImageData;
+
+    /// Mix from `original` (mask 0) to `processed` (mask 1), all channels.
+    /// The images and the mask are the same size.
+    fn mask_mix(&self, original: &ImageData, processed: &ImageData, mask: &Mask) -> ImageData;
+}
@@ -0,0 +1,466 @@
+//! Colour management
+//!
+//! Images are tagged with the colour space their pixels are encoded in.
+//! Loaded images are converted into the project's working space (linear by
+//! default) before processing, and Output nodes convert back to an encoded
+//! space whose ICC profile is embedded on export.
+
+use serde::{Deserialize, Serialize};
+
+use crate::image_data::ImageData;
+use crate::kernel;
+
+/// Colour space of an image's pixel values
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, Serialize, Deserialize)]
+pub enum ColorSpace {
+    #[default]
+    Srgb,
+    DisplayP3,
+    AdobeRgb,
+    LinearSrgb,
+    LinearDisplayP3,
+}
+
+impl ColorSpace {
+    pub const ALL: [ColorSpace; 5] = [
+        Self::Srgb,
+        Self::DisplayP3,
+        Self::AdobeRgb,
+        Self::LinearSrgb,
+        Self::LinearDisplayP3,
+    ];
+
+    /// Encoded spaces that can be written to files
+    pub const OUTPUT: [ColorSpace; 3] = [Self::Srgb, Self::DisplayP3, Self::AdobeRgb];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Srgb => "sRGB",
+            Self::DisplayP3 => "Display P3",
+            Self::AdobeRgb => "Adobe RGB",
+            Self::LinearSrgb => "Linear sRGB",
+            Self::LinearDisplayP3 => "Linear Display P3",
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Srgb => "srgb",
+            Self::DisplayP3 => "display-p3",
+            Self::AdobeRgb => "adobe-rgb",
+            Self::LinearSrgb => "linear-srgb",
+            Self::LinearDisplayP3 => "linear-display-p3",
+        }
+    }
+
+    pub fn parse(s: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|c| c.as_str() == s)
+    }
+
+    /// The same primaries with the space's usual transfer curve
+    pub fn encoded(&self) -> ColorSpace {
+        match self {
+            Self::LinearSrgb => Self::Srgb,
+            Self::LinearDisplayP3 => Self::DisplayP3,
+            other => *other,
+        }
+    }
+
+    fn gamut(&self) -> Gamut {
+        match self {
+            Self::Srgb | Self::LinearSrgb => Gamut::Srgb,
+            Self::DisplayP3 | Self::LinearDisplayP3 => Gamut::DisplayP3,
+            Self::AdobeRgb => Gamut::AdobeRgb,
+        }
+    }
+
+    fn transfer(&self) -> Transfer {
+        match self {
+            Self::Srgb | Self::DisplayP3 => Transfer::Srgb,
+            Self::AdobeRgb => Transfer::Gamma(ADOBE_GAMMA),
+            Self::LinearSrgb | Self::LinearDisplayP3 => Transfer::Linear,
+        }
+    }
+
+    /// A minimal ICC v2 display profile describing this space
+    pub fn icc_profile(&self) -> Vec<u8> {
+        build_icc(*self)
+    }
+}
+
+/// Colour space the graph is processed in, set per project
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, Serialize, Deserialize)]
+pub enum WorkingSpace {
+    #[default]
+    LinearSrgb,
+    LinearDisplayP3,
+    /// Process sRGB-encoded values directly, as older projects did
+    Srgb,
+}
+
+impl WorkingSpace {
+    pub const ALL: [WorkingSpace; 3] = [Self::LinearSrgb, Self::LinearDisplayP3, Self::Srgb];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::LinearSrgb => "Linear sRGB",
+            Self::LinearDisplayP3 => "Linear Display P3",
+            Self::Srgb => "sRGB (non-linear)",
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        self.color_space().as_str()
+    }
+
+    pub fn parse(s: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|w| w.as_str() == s)
+    }
+
+    pub fn color_space(&self) -> ColorSpace {
+        match self {
+            Self::LinearSrgb => ColorSpace::LinearSrgb,
+            Self::LinearDisplayP3 => ColorSpace::LinearDisplayP3,
+            Self::Srgb => ColorSpace::Srgb,
+        }
+    }
+}
+
+/// Adobe RGB (1998) gamma, 563/256 as stored in its profile
+const ADOBE_GAMMA: f32 = 2.199_218_8;
+
+/// Profile connection space white (D50)
+const D50: [f32; 3] = [0.9642, 1.0, 0.8249];
+
+/// Bradford chromatic adaptation from D65 to D50
+const BRADFORD_D65_TO_D50: [[f32; 3]; 3] = [
+    [1.047_811_2, 0.022_886_6, -0.050_127],
+    [0.029_542_4, 0.990_484_4, -0.017_049_1],
+    [-0.009_234_5, 0.015_043_6, 0.752_131_6],
+];
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Gamut {
+    Srgb,
+    DisplayP3,
+    AdobeRgb,
+}
+
+impl Gamut {
+    const ALL: [Gamut; 3] = [Self::Srgb, Self::DisplayP3, Self::AdobeRgb];
+
+    /// xy chromaticities of the red, green and blue primaries (all D65 white)
+    fn primaries(&self) -> [[f32; 2]; 3] {
+        match self {
+            Self::Srgb => [[0.64, 0.33], [0.30, 0.60], [0.15, 0.06]],
+            Self::DisplayP3 => [[0.680, 0.320], [0.265, 0.690], [0.150, 0.060]],
+            Self::AdobeRgb => [[0.64, 0.33], [0.21, 0.71], [0.15, 0.06]],
+        }
+    }
+
+    /// Linear RGB to XYZ (D65)
+    fn to_xyz(self) -> [[f32; 3]; 3] {
+        let white = xy_to_xyz([0.3127, 0.3290]);
+        let [r, g, b] = self.primaries().map(xy_to_xyz);
+        let m = [
+            [r[0], g[0], b[0]],
+            [r[1], g[1], b[1]],
+            [r[2], g[2], b[2]],
+        ];
+        // Scale each primary so RGB(1, 1, 1) lands on the white point
+        let s = mat_vec(&invert(&m), white);
+        [
+            [m[0][0] * s[0], m[0][1] * s[1], m[0][2] * s[2]],
+            [m[1][0] * s[0], m[1][1] * s[1], m[1][2] * s[2]],
+            [m[2][0] * s[0], m[2][1] * s[1], m[2][2] * s[2]],
+        ]
+    }
+
+    /// Linear RGB to the D50 profile connection space, as stored in ICC colorant tags
+    fn to_pcs(self) -> [[f32; 3]; 3] {
+        mat_mul(&BRADFORD_D65_TO_D50, &self.to_xyz())
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Transfer {
+    Linear,
+    Srgb,
+    Gamma(f32),
+}
+
+impl Transfer {
+    /// Encoded value to linear light
+    fn decode(&self, v: f32) -> f32 {
+        let a = v.abs();
+        let linear = match self {
+            Self::Linear => a,
+            Self::Srgb if a <= 0.04045 => a / 12.92,
+            Self::Srgb => ((a + 0.055) / 1.055).powf(2.4),
+            Self::Gamma(g) => a.powf(*g),
+        };
+        linear.copysign(v)
+    }
+
+    /// Linear light to encoded value
+    fn encode(&self, v: f32) -> f32 {
+        let a = v.abs();
+        let encoded = match self {
+            Self::Linear => a,
+            Self::Srgb if a <= 0.003_130_8 => a * 12.92,
+            Self::Srgb => 1.055 * a.powf(1.0 / 2.4) - 0.055,
+            Self::Gamma(g) => a.powf(1.0 / g),
+        };
+        encoded.copysign(v)
+    }
+}
+
+/// Convert an image to another colour space. Alpha is left untouched and
+/// out-of-gamut values are kept (negative or above 1) until export.
+pub fn convert(data: &ImageData, to: ColorSpace) -> ImageData {
+    let from = data.color_space;
+    if from == to {
+        return data.clone();
+    }
+
+    let (decode, encode) = (from.transfer(), to.transfer());
+    let matrix = (from.gamut() != to.gamut())
+        .then(|| mat_mul(&invert(&to.gamut().to_xyz()), &from.gamut().to_xyz()));
+
+    let mut pixels = data.to_f32();
+    kernel::for_each_pixel(&mut pixels, |pixel| {
+        let mut rgb = [decode.decode(pixel[0]), decode.decode(pixel[1]), decode.decode(pixel[2])];
+        if let Some(m) = &matrix {
+            rgb = mat_vec(m, rgb);
+        }
+        for c in 0..3 {
+            pixel[c] = encode.encode(rgb[c]);
+        }
+    });
+
+    ImageData::from_f32(pixels, data.width, data.height).with_color_space(to)
+}
+
+/// Identify the colour space an ICC profile describes from its colorants and
+/// tone curves. Returns `None` for profiles that aren't one of the known RGB spaces.
+pub fn detect_icc(profile: &[u8]) -> Option<ColorSpace> {
+    if profile.len() < 132 || &profile[36..40] != b"acsp" || &profile[16..20] != b"RGB " {
+        return None;
+    }
+
+    let tag_count = read_u32(profile, 128)? as usize;
+    let find_tag = |sig: &[u8; 4]| -> Option<&[u8]> {
+        (0..tag_count.min(256)).find_map(|i| {
+            let entry = 132 + i * 12;
+            if profile.get(entry..entry + 4)? != sig {
+                return None;
+            }
+            let offset = read_u32(profile, entry + 4)? as usize;
+            let size = read_u32(profile, entry + 8)? as usize;
+            profile.get(offset..offset.checked_add(size)?)
+        })
+    };
+
+    let colorant = |sig: &[u8; 4]| -> Option<[f32; 3]> {
+        let tag = find_tag(sig)?;
+        if tag.get(0..4)? != b"XYZ " {
+            return None;
+        }
+        Some([read_s15f16(tag, 8)?, read_s15f16(tag, 12)?, read_s15f16(tag, 16)?])
+    };
+    let colorants = [colorant(b"rXYZ")?, colorant(b"gXYZ")?, colorant(b"bXYZ")?];
+
+    let gamut = Gamut::ALL.into_iter().find(|gamut| {
+        let pcs = gamut.to_pcs();
+        colorants.iter().enumerate().all(|(c, xyz)| {
+            (0..3).all(|i| (xyz[i] - pcs[i][c]).abs() < 0.01)
+        })
+    })?;
+
+    let linear = find_tag(b"rTRC").map(curve_is_linear).unwrap_or(false);
+    Some(match (gamut, linear) {
+        (Gamut::Srgb, false) => ColorSpace::Srgb,
+        (Gamut::Srgb, true) => ColorSpace::LinearSrgb,
+        (Gamut::DisplayP3, false) => ColorSpace::DisplayP3,
+        (Gamut::DisplayP3, true) => ColorSpace::LinearDisplayP3,
+        (Gamut::AdobeRgb, _) => ColorSpace::AdobeRgb,
+    })
+}
+
+/// Whether a `curv` or `para` tone curve is the identity
+fn curve_is_linear(tag: &[u8]) -> bool {
+    match tag.get(0..4) {
+        Some(b"curv") => match read_u32(tag, 8) {
+            Some(0) => true,
+            // Single entry: gamma as u8Fixed8
+            Some(1) => read_u16(tag, 12) == Some(0x0100),
+            _ => false,
+        },
+        // Function type 0 is a plain gamma
+        Some(b"para") => read_u16(tag, 8) == Some(0) && read_s15f16(tag, 12).is_some_and(|g| (g - 1.0).abs() < 0.01),
+        _ => false,
+    }
+}
+
+fn build_icc(space: ColorSpace) -> Vec<u8> {
+    let pcs = space.gamut().to_pcs();
+
+    let mut desc = Vec::new();
+    let text = format!("{}\0", space.name());
+    desc.extend_from_slice(b"desc\0\0\0\0");
+    desc.extend_from_slice(&(text.len() as u32).to_be_bytes());
+    desc.extend_from_slice(text.as_bytes());
+    // Empty Unicode and ScriptCode descriptions
+    desc.extend_from_slice(&[0; 8]);
+    desc.extend_from_slice(&[0; 3]);
+    desc.extend_from_slice(&[0; 67]);
+
+    let mut cprt = b"text\0\0\0\0".to_vec();
+    cprt.extend_from_slice(b"No copyright, use freely\0");
+
+    let xyz = |v: [f32; 3]| {
+        let mut tag = b"XYZ \0\0\0\0".to_vec();
+        for c in v {
+            tag.extend_from_slice(&s15f16(c).to_be_bytes());
+        }
+        tag
+    };
+
+    let mut trc = b"curv\0\0\0\0".to_vec();
+    match space.transfer() {
+        Transfer::Linear => trc.extend_from_slice(&0u32.to_be_bytes()),
+        Transfer::Gamma(g) => {
+            trc.extend_from_slice(&1u32.to_be_bytes());
+            trc.extend_from_slice(&((g * 256.0).round() as u16).to_be_bytes());
+        }
+        transfer @ Transfer::Srgb => {
+            const ENTRIES: usize = 1024;
+            trc.extend_from_slice(&(ENTRIES as u32).to_be_bytes());
+            for i in 0..ENTRIES {
+                let v = transfer.decode(i as f32 / (ENTRIES - 1) as f32);
+                trc.extend_from_slice(&((v * 65535.0).round() as u16).to_be_bytes());
+            }
+        }
+    }
+
+    let column = |c: usize| [pcs[0][c], pcs[1][c], pcs[2][c]];
+    let tags: [(&[u8; 4], Vec<u8>); 7] = [
+        (b"desc", desc),
+        (b"cprt", cprt),
+        (b"wtpt", xyz(D50)),
+        (b"rXYZ", xyz(column(0))),
+        (b"gXYZ", xyz(column(1))),
+        (b"bXYZ", xyz(column(2))),
+        (b"rTRC", trc),
+    ];
+
+    // The three tone curves share one block of data
+    let entry_count = tags.len() + 2;
+    let mut offset = 128 + 4 + entry_count * 12;
+    let mut table = Vec::new();
+    let mut data = Vec::new();
+    for (sig, tag) in &tags {
+        let entry = |sig: &[u8; 4]| {
+            let mut e = sig.to_vec();
+            e.extend_from_slice(&(offset as u32).to_be_bytes());
+            e.extend_from_slice(&(tag.len() as u32).to_be_bytes());
+            e
+        };
+        table.extend(entry(sig));
+        if *sig == b"rTRC" {
+            table.extend(entry(b"gTRC"));
+            table.extend(entry(b"bTRC"));
+        }
+        data.extend_from_slice(tag);
+        while data.len() % 4 != 0 {
+            data.push(0);
+        }
+        offset = 128 + 4 + entry_count * 12 + data.len();
+    }
+
+    let size = 128 + 4 + table.len() + data.len();
+    let mut profile = Vec::with_capacity(size);
+    profile.extend_from_slice(&(size as u32).to_be_bytes());
+    profile.extend_from_slice(&[0; 4]); // CMM
+    profile.extend_from_slice(&[2, 0x10, 0, 0]); // Version 2.1
+    profile.extend_from_slice(b"mntrRGB XYZ ");
+    for v in [2024u16, 1, 1, 0, 0, 0] {
+        profile.extend_from_slice(&v.to_be_bytes());
+    }
+    profile.extend_from_slice(b"acsp");
+    profile.extend_from_slice(&[0; 24]); // Platform, flags, manufacturer, model, attributes
+    profile.extend_from_slice(&0u32.to_be_bytes()); // Perceptual intent
+    for c in D50 {
+        profile.extend_from_slice(&s15f16(c).to_be_bytes());
+    }
+    profile.extend_from_slice(&[0; 4]); // Creator
+    profile.extend_from_slice(&[0; 44]); // Profile ID and reserved
+    debug_assert_eq!(profile.len(), 128);
+
+    profile.extend_from_slice(&(entry_count as u32).to_be_bytes());
+    profile.extend(table);
+    profile.extend(data);
+    profile
+}
+
+fn s15f16(v: f32) -> i32 {
+    (v * 65536.0).round() as i32
+}
+
+fn read_u32(bytes: &[u8], at: usize) -> Option<u32> {
+    Some(u32::from_be_bytes(bytes.get(at..at + 4)?.try_into().ok()?))
+}
+
+fn read_u16(bytes: &[u8], at: usize) -> Option<u16> {
+    Some(u16::from_be_bytes(bytes.get(at..at + 2)?.try_into().ok()?))
+}
+
+fn read_s15f16(bytes: &[u8], at: usize) -> Option<f32> {
+    Some(read_u32(bytes, at)? as i32 as f32 / 65536.0)
+}
+
+fn xy_to_xyz([x, y]: [f32; 2]) -> [f32; 3] {
+    [x / y, 1.0, (1.0 - x - y) / y]
+}
+
+fn mat_vec(m: &[[f32; 3]; 3], v: [f32; 3]) -> [f32; 3] {
+    [
+        m[0][0] * v[0] + m[0][1] * v[1] + m[0][2] * v[2],
+        m[1][0] * v[0] + m[1][1] * v[1] + m[1][2] * v[2],
+        m[2][0] * v[0] + m[2][1] * v[1] + m[2][2] * v[2],
+    ]
+}
+
+fn mat_mul(a: &[[f32; 3]; 3], b: &[[f32; 3]; 3]) -> [[f32; 3]; 3] {
+    let mut out = [[0.0; 3]; 3];
+    for (i, row) in out.iter_mut().enumerate() {
+        for (j, v) in row.iter_mut().enumerate() {
+            *v = (0..3).map(|k| a[i][k] * b[k][j]).sum();
+        }
+    }
+    out
+}
+
+fn invert(m: &[[f32; 3]; 3]) -> [[f32; 3]; 3] {
+    let det = m[0][0] * (m[1][1] * m[2][2] - m[1][2] * m[2][1])
+        - m[0][1] * (m[1][0] * m[2][2] - m[1][2] * m[2][0])
+        + m[0][2] * (m[1][0] * m[2][1] - m[1][1] * m[2][0]);
+    let inv = 1.0 / det;
+    [
+        [
+            (m[1][1] * m[2][2] - m[1][2] * m[2][1]) * inv,
+            (m[0][2] * m[2][1] - m[0][1] * m[2][2]) * inv,
+            (m[0][1] * m[1][2] - m[0][2] * m[1][1]) * inv,
+        ],
+        [
+            (m[1][2] * m[2][0] - m[1][0] * m[2][2]) * inv,
+            (m[0][0] * m[2][2] - m[0][2] * m[2][0]) * inv,
+            (m[0][2] * m[1][0] - m[0][0] * m[1][2]) * inv,
+        ],
+        [
+            (m[1][0] * m[2][1] - m[1][1] * m[2][0]) * inv,
+            (m[0][1] * m[2][0] - m[0][0] * m[2][1]) * inv,
+            (m[0][0] * m[1][1] - m[0][1] * m[1][0]) * inv,
+        ],
+    ]
+}
@@ -0,0 +1,712 @@
+//! Compatibility layer for FlowNode.io React Flow format
+//! 
+//! This module handles conversion between WASM internal format
+//! and the React Flow JSON format used by FlowNode.io
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use uuid::Uuid;
+
+use crate::color::{ColorSpace, WorkingSpace};
+use crate::lut::{Lut, LutStore};
+use crate::nodes::{Node, NodeType, NodeProperties, BlendMode, BlurDirection, ColorWheel, CompareMode, CompositionLayer, Curve, Curves, EdgeMode, ExportDepth, ExportFormat, LutInterpolation, MaskSource, Vec2};
+use crate::graph::{NodeGraph, Connection, Subgraph};
+
+/// React Flow compatible node format
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReactFlowNode {
+    pub id: String,
+    #[serde(rename = "type")]
+    pub node_type: String,
+    pub position: Position,
+    pub data: serde_json::Value,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Position {
+    pub x: f32,
+    pub y: f32,
+}
+
+/// React Flow compatible edge format
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReactFlowEdge {
+    pub id: String,
+    pub source: String,
+    pub target: String,
+    #[serde(rename = "sourceHandle")]
+    pub source_handle: Option<String>,
+    #[serde(rename = "targetHandle")]
+    pub target_handle: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Viewport {
+    pub x: f32,
+    pub y: f32,
+    pub zoom: f32,
+}
+
+impl Default for Viewport {
+    fn default() -> Self {
+        Self { x: 0.0, y: 0.0, zoom: 1.0 }
+    }
+}
+
+/// Complete React Flow workflow format
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReactFlowWorkflow {
+    pub nodes: Vec<ReactFlowNode>,
+    #[serde(default)]
+    pub edges: Vec<ReactFlowEdge>,
+    #[serde(default)]
+    pub viewport: Viewport,
+    #[serde(rename = "workingSpace", default, skip_serializing_if = "Option::is_none")]
+    pub working_space: Option<String>,
+}
+
+impl ReactFlowWorkflow {
+    /// Convert from internal NodeGraph format to React Flow format
+    pub fn from_graph(graph: &NodeGraph, pan: Vec2, zoom: f32) -> Self {
+        let (nodes, edges) = to_react_flow(graph.nodes_iter().map(|(_, node)| node), graph.connections_iter(), &graph.luts);
+        
+        Self {
+            nodes,
+            edges,
+            viewport: Viewport {
+                x: pan.x,
+                y: pan.y,
+                zoom,
+            },
+            working_space: Some(graph.working_space.as_str().to_string()),
+        }
+    }
+    
+    /// Convert copied nodes to React Flow format (for the clipboard)
+    pub fn from_subgraph(subgraph: &Subgraph) -> Self {
+        let (nodes, edges) = to_react_flow(subgraph.nodes.iter(), subgraph.connections.iter(), &subgraph.luts);
+        
+        Self {
+            nodes,
+            edges,
+            viewport: Viewport::default(),
+            working_space: None,
+        }
+    }
+    
+    /// Convert React Flow format to internal NodeGraph
+    pub fn to_graph(&self) -> Result<(NodeGraph, Vec2, f32), String> {
+        let (nodes, id_map, luts) = self.create_nodes()?;
+        let nodes = nodes.into_iter().map(|node| (node.id, node)).collect();
+        let mut graph = NodeGraph::from_parts(nodes, Vec::new());
+        graph.luts = luts;
+        
+        for edge in &self.edges {
+            let from_id = id_map.get(&edge.source)
+                .ok_or_else(|| format!("Unknown source node: {}", edge.source))?;
+            let to_id = id_map.get(&edge.target)
+                .ok_or_else(|| format!("Unknown target node: {}", edge.target))?;
+            add_edge(&mut graph, edge, *from_id, *to_id);
+        }
+        
+        let pan = Vec2::new(self.viewport.x, self.viewport.y);
+        let zoom = self.viewport.zoom;
+        
+        graph.working_space = self.working_space
+            .as_deref()
+            .and_then(WorkingSpace::parse)
+            // Workflows without one come from React, which works in sRGB
+            .unwrap_or(WorkingSpace::Srgb);
+        // React has no Output node type
+        graph.add_missing_outputs();
+        
+        Ok((graph, pan, zoom))
+    }
+    
+    /// Convert pasted React Flow nodes to a subgraph with fresh IDs.
+    /// Edges to nodes that weren't copied are dropped.
+    pub fn to_subgraph(&self) -> Result<Subgraph, String> {
+        let (nodes, id_map, luts) = self.create_nodes()?;
+        
+        // Wire the nodes up in a scratch graph so edges are checked as on load
+        let mut graph = NodeGraph::from_parts(nodes.iter().map(|n| (n.id, n.clone())).collect(), Vec::new());
+        for edge in &self.edges {
+            if let (Some(from_id), Some(to_id)) = (id_map.get(&edge.source), id_map.get(&edge.target)) {
+                add_edge(&mut graph, edge, *from_id, *to_id);
+            }
+        }
+        
+        Ok(Subgraph { nodes, connections: graph.connections, luts })
+    }
+    
+    /// Create internal nodes with new IDs
+    fn create_nodes(&self) -> Result<CreatedNodes, String> {
+        let mut nodes = Vec::new();
+        let mut id_map: HashMap<String, Uuid> = HashMap::new();
+        let mut luts = LutStore::default();
+        
+        for rf_node in &self.nodes {
+            let node_type = string_to_node_type(&rf_node.node_type)?;
+            let mut node = Node::new(node_type, Vec2::new(rf_node.position.x, rf_node.position.y));
+            node.properties = data_to_properties(&rf_node.data, &node_type, &mut luts);
+            
+            id_map.insert(rf_node.id.clone(), node.id);
+            nodes.push(node);
+        }
+        
+        Ok((nodes, id_map, luts))
+    }
+}
+
+/// Nodes made from a workflow, the map from React Flow IDs to theirs, and
+/// the tables their LUT nodes load
+type CreatedNodes = (Vec<Node>, HashMap<String, Uuid>, LutStore);
+
+/// Convert nodes and connections to React Flow nodes and edges
+fn to_react_flow<'a>(
+    nodes: impl Iterator<Item = &'a Node>,
+    connections: impl Iterator<Item = &'a Connection>,
+    luts: &LutStore,
+) -> (Vec<ReactFlowNode>, Vec<ReactFlowEdge>) {
+    let nodes = nodes
+        .map(|node| {
+            ReactFlowNode {
+                id: node.id.to_string(),
+                node_type: node_type_to_string(&node.node_type),
+                position: Position {
+                    x: node.position.x,
+                    y: node.position.y,
+                },
+                data: node_to_data(node, luts),
+            }
+        })
+        .collect();
+    
+    let edges = connections
+        .map(|conn| {
+            ReactFlowEdge {
+                id: format!("e-{}-{}-{}-{}", 
+                    conn.from_node, conn.from_slot, 
+                    conn.to_node, conn.to_slot),
+                source: conn.from_node.to_string(),
+                target: conn.to_node.to_string(),
+                source_handle: Some(format!("output-{}", conn.from_slot)),
+                target_handle: Some(format!("input-{}", conn.to_slot)),
+            }
+        })
+        .collect();
+    
+    (nodes, edges)
+}
+
+/// Connect an edge's nodes through [`NodeGraph::add_connection`], logging and
+/// skipping edges with unknown handles or that the graph rejects
+fn add_edge(graph: &mut NodeGraph, edge: &ReactFlowEdge, from_id: Uuid, to_id: Uuid) {
+    let (from_type, to_type) = (graph.nodes[&from_id].node_type, graph.nodes[&to_id].node_type);
+    let Some((from_slot, to_slot)) = edge_slots(edge, from_type, to_type) else {
+        log::warn!("Skipped edge {}: unknown handle", edge.id);
+        return;
+    };
+    if let Err(e) = graph.add_connection(from_id, from_slot, to_id, to_slot) {
+        log::warn!("Skipped edge {}: {}", edge.id, e);
+    }
+}
+
+/// Output and input slot indices an edge connects, or `None` if either
+/// handle isn't one the node has
+fn edge_slots(edge: &ReactFlowEdge, from: NodeType, to: NodeType) -> Option<(usize, usize)> {
+    Some((
+        output_slot(from, edge.source_handle.as_deref())?,
+        input_slot(to, edge.target_handle.as_deref())?,
+    ))
+}
+
+/// Output slot a React Flow source handle refers to. Accepts the slot names
+/// from [`NodeType::outputs`], FlowNode.io's handle IDs (docs/REACT_SPEC.md,
+/// "Output Handle Mapping") and our own "output-N". No handle means the
+/// first slot.
+pub fn output_slot(node_type: NodeType, handle: Option<&str>) -> Option<usize> {
+    let outputs = node_type.outputs();
+    let Some(handle) = handle else {
+        return (!outputs.is_empty()).then_some(0);
+    };
+    let slot = outputs.iter().position(|s| s.name == handle)
+        .or_else(|| handle.strip_prefix("output-").and_then(|n| n.parse().ok()))
+        .or_else(|| match (node_type, handle) {
+            (NodeType::Router, _) => handle.strip_prefix("out-").and_then(|n| n.parse().ok()),
+            // Each line is its own handle in React; here the lines travel together
+            (NodeType::Splitter, _) if handle.starts_with("line-") => Some(0),
+            (NodeType::Bucket, "content-out-0") |
+            (NodeType::Omni, "multi-content-out") |
+            (NodeType::Llm, "llm-out") |
+            (NodeType::Concat, "concat-out") => Some(0),
+            _ => None,
+        })?;
+    (slot < outputs.len()).then_some(slot)
+}
+
+/// Input slot a React Flow target handle refers to. Accepts the slot names
+/// from [`NodeType::inputs`], FlowNode.io's handle IDs (docs/REACT_SPEC.md,
+/// "Input Handle Mapping") and our own "input-N". No handle means the first
+/// slot.
+pub fn input_slot(node_type: NodeType, handle: Option<&str>) -> Option<usize> {
+    let inputs = node_type.inputs();
+    let Some(handle) = handle else {
+        return (!inputs.is_empty()).then_some(0);
+    };
+    let named = |name: &str| inputs.iter().position(|s| s.name == name);
+    let slot = named(handle)
+        .or_else(|| handle.strip_prefix("input-").and_then(|n| n.parse().ok()))
+        .or_else(|| match (node_type, handle) {
+            (NodeType::Compare, "content-1-in") => Some(0),
+            (NodeType::Compare, "content-2-in") => Some(1),
+            (NodeType::Concat, "text-1") => Some(0),
+            (NodeType::Concat, "text-2") => Some(1),
+            (_, "prompt-in") => named("prompts-in"),
+            // Image inputs of the generation nodes
+            (NodeType::Omni | NodeType::Video | NodeType::Rodin3d, "content-in") => named("images-in"),
+            _ => None,
+        })?;
+    (slot < inputs.len()).then_some(slot)
+}
+
+/// Convert node type enum to React Flow string
+fn node_type_to_string(node_type: &NodeType) -> String {
+    match node_type {
+        NodeType::Image => "image".to_string(),
+        NodeType::Content => "content".to_string(),
+        NodeType::Bucket => "bucket".to_string(),
+        NodeType::Adjust => "adjust".to_string(),
+        NodeType::Effects => "effects".to_string(),
+        NodeType::Lut => "lut".to_string(),
+        NodeType::Mask => "mask".to_string(),
+        NodeType::Text => "text".to_string(),
+        NodeType::Concat => "concat".to_string(),
+        NodeType::Splitter => "splitter".to_string(),
+        NodeType::Postit => "postit".to_string(),
+        NodeType::Compare => "compare".to_string(),
+        NodeType::Composition => "composition".to_string(),
+        NodeType::Router => "router".to_string(),
+        NodeType::Batch => "batch".to_string(),
+        NodeType::Title => "title".to_string(),
+        NodeType::Group => "group".to_string(),
+        NodeType::Folder => "folder".to_string(),
+        NodeType::Convertor => "convertor".to_string(),
+        NodeType::Output => "output".to_string(),
+        NodeType::Omni => "omni".to_string(),
+        NodeType::Llm => "llm".to_string(),
+        NodeType::Video => "video".to_string(),
+        NodeType::Upscaler => "upscaler".to_string(),
+        NodeType::Vector => "vector".to_string(),
+        NodeType::Rodin3d => "rodin3d".to_string(),
+        NodeType::MindMap => "mind-map".to_string(),
+    }
+}
+
+/// Convert React Flow type string to node type enum
+fn string_to_node_type(type_str: &str) -> Result<NodeType, String> {
+    match type_str {
+        "image" => Ok(NodeType::Image),
+        "content" => Ok(NodeType::Content),
+        "bucket" => Ok(NodeType::Bucket),
+        "adjust" => Ok(NodeType::Adjust),
+        "effects" => Ok(NodeType::Effects),
+        "lut" => Ok(NodeType::Lut),
+        "mask" => Ok(NodeType::Mask),
+        "text" => Ok(NodeType::Text),
+        "concat" => Ok(NodeType::Concat),
+        "splitter" => Ok(NodeType::Splitter),
+        "postit" => Ok(NodeType::Postit),
+        "compare" => Ok(NodeType::Compare),
+        "composition" => Ok(NodeType::Composition),
+        "router" => Ok(NodeType::Router),
+        "batch" => Ok(NodeType::Batch),
+        "title" => Ok(NodeType::Title),
+        "group" => Ok(NodeType::Group),
+        "folder" => Ok(NodeType::Folder),
+        "convertor" => Ok(NodeType::Convertor),
+        "output" => Ok(NodeType::Output),
+        "omni" => Ok(NodeType::Omni),
+        "llm" => Ok(NodeType::Llm),
+        "video" => Ok(NodeType::Video),
+        "upscaler" => Ok(NodeType::Upscaler),
+        "vector" => Ok(NodeType::Vector),
+        "rodin3d" => Ok(NodeType::Rodin3d),
+        "mind-map" => Ok(NodeType::MindMap),
+        _ => Err(format!("Unknown node type: {}", type_str)),
+    }
+}
+
+/// Convert internal node to React Flow data field
+fn node_to_data(node: &Node, luts: &LutStore) -> serde_json::Value {
+    match &node.properties {
+        NodeProperties::Image { image, .. } => {
+            serde_json::json!({
+                "image": image,
+                "label": node.label.as_deref().unwrap_or("Image")
+            })
+        }
+        
+        NodeProperties::Adjust { 
+            brightness, contrast, saturation, exposure,
+            highlights, shadows, temperature, tint,
+            vibrance, gamma, color_boost, hue_rotation, luminance_mix,
+            curves_enabled, curves, ..
+        } => {
+            serde_json::json!({
+                "label": "Adjust",
+                "settings": {
+                    "brightness": brightness,
+                    "contrast": contrast,
+                    "saturation": saturation,
+                    "exposure": exposure,
+                    "highlights": highlights,
+                    "shadows": shadows,
+                    "temperature": temperature,
+                    "tint": tint,
+                    "vibrance": vibrance,
+                    "gamma": gamma,
+                    "colorBoost": color_boost,
+                    "hueRotation": hue_rotation,
+                    "luminanceMix": luminance_mix,
+                    "curvesEnabled": curves_enabled,
+                    "curves": {
+                        "master": curves.master.points,
+                        "red": curves.red.points,
+                        "green": curves.green.points,
+                        "blue": curves.blue.points
+                    }
+                }
+            })
+        }
+        
+        NodeProperties::Effects {
+            gaussian_blur, blur_edge_mode, directional_blur, directional_blur_angle,
+            progressive_blur, progressive_blur_direction, progressive_blur_falloff,
+            glass_blinds, glass_blinds_frequency, glass_blinds_angle, glass_blinds_phase,
+            grain, grain_size, grain_monochrome, grain_seed,
+            sharpen, vignette, vignette_roundness, vignette_smoothness
+        } => {
+            serde_json::json!({
+                "label": "Effects",
+                "settings": {
+                    "gaussianBlur": gaussian_blur,
+                    "blurEdgeMode": blur_edge_mode.as_str(),
+                    "directionalBlur": directional_blur,
+                    "directionalBlurAngle": directional_blur_angle,
+                    "progressiveBlur": progressive_blur,
+                    "progressiveBlurDirection": format!("{:?}", progressive_blur_direction).to_lowercase(),
+                    "progressiveBlurFalloff": progressive_blur_falloff,
+                    "glassBlinds": glass_blinds,
+                    "glassBlindsFrequency": glass_blinds_frequency,
+                    "glassBlindsAngle": glass_blinds_angle,
+                    "glassBlindsPhase": glass_blinds_phase,
+                    "grain": grain,
+                    "grainSize": grain_size,
+                    "grainMonochrome": grain_monochrome,
+                    "grainSeed": grain_seed,
+                    "sharpen": sharpen,
+                    "vignette": vignette,
+                    "vignetteRoundness": vignette_roundness,
+                    "vignetteSmoothness": vignette_smoothness
+                }
+            })
+        }
+        
+        NodeProperties::Lut { file_name, lut, interpolation, intensity } => {
+            serde_json::json!({
+                "label": "LUT",
+                "fileName": file_name,
+                "cube": lut.as_ref().and_then(|r| luts.get(r.hash)).map(|l| l.to_cube()),
+                "interpolation": interpolation.as_str(),
+                "intensity": intensity
+            })
+        }
+        
+        NodeProperties::Mask { source, center, size, angle, invert, feather, density } => {
+            serde_json::json!({
+                "label": "Mask",
+                "source": source.as_str(),
+                "center": center,
+                "size": size,
+                "angle": angle,
+                "invert": invert,
+                "feather": feather,
+                "density": density
+            })
+        }
+        
+        NodeProperties::Text { text } => {
+            serde_json::json!({ "text": text })
+        }
+        
+        NodeProperties::Bucket { images, .. } => {
+            serde_json::json!({ "label": "Bucket", "images": images })
+        }
+        
+        NodeProperties::Batch { items } => {
+            serde_json::json!({ "label": "Batch", "items": items })
+        }
+        
+        NodeProperties::Compare { mode, split, .. } => {
+            serde_json::json!({
+                "label": "Compare",
+                "mode": mode.as_str(),
+                "split": split
+            })
+        }
+        
+        NodeProperties::Composition { layers } => {
+            let layers: Vec<serde_json::Value> = layers.iter()
+                .map(|layer| serde_json::json!({
+                    "image": layer.image,
+                    "opacity": layer.opacity,
+                    "blendMode": layer.blend_mode.as_str(),
+                    "x": layer.position.0,
+                    "y": layer.position.1,
+                    "scale": layer.scale
+                }))
+                .collect();
+            serde_json::json!({
+                "label": "Composition",
+                "layers": layers
+            })
+        }
+        
+        NodeProperties::Output { name, format, max_size, bit_depth, color_space } => {
+            serde_json::json!({
+                "label": "Output",
+                "name": name,
+                "output_format": format.as_str(),
+                "maxSize": max_size,
+                "bitDepth": bit_depth.bits(),
+                "colorSpace": color_space.as_str()
+            })
+        }
+        
+        NodeProperties::Omni { model, prompt, negative_prompt, seed } => {
+            serde_json::json!({
+                "model": model,
+                "prompt": prompt,
+                "negativePrompt": negative_prompt,
+                "seed": seed
+            })
+        }
+        
+        // Default for other types
+        _ => {
+            serde_json::json!({
+                "label": node.node_type.name()
+            })
+        }
+    }
+}
+
+/// Convert React Flow data field to internal properties
+fn data_to_properties(data: &serde_json::Value, node_type: &NodeType, luts: &mut LutStore) -> NodeProperties {
+    match node_type {
+        NodeType::Image => {
+            NodeProperties::Image {
+                image: data.get("image").and_then(|v| v.as_str()).map(String::from),
+                thumbnail: None,
+                history: Vec::new(),
+                texture_id: None,
+            }
+        }
+        
+        NodeType::Adjust => {
+            let settings = data.get("settings").unwrap_or(data);
+            NodeProperties::Adjust {
+                brightness: settings.get("brightness").and_then(|v| v.as_f64()).unwrap_or(0.0) as f32,
+                contrast: settings.get("contrast").and_then(|v| v.as_f64()).unwrap_or(0.0) as f32,
+                saturation: settings.get("saturation").and_then(|v| v.as_f64()).unwrap_or(0.0) as f32,
+                exposure: settings.get("exposure").and_then(|v| v.as_f64()).unwrap_or(0.0) as f32,
+                highlights: settings.get("highlights").and_then(|v| v.as_f64()).unwrap_or(0.0) as f32,
+                shadows: settings.get("shadows").and_then(|v| v.as_f64()).unwrap_or(0.0) as f32,
+                temperature: settings.get("temperature").and_then(|v| v.as_f64()).unwrap_or(0.0) as f32,
+                tint: settings.get("tint").and_then(|v| v.as_f64()).unwrap_or(0.0) as f32,
+                vibrance: settings.get("vibrance").and_then(|v| v.as_f64()).unwrap_or(0.0) as f32,
+                gamma: settings.get("gamma").and_then(|v| v.as_f64()).unwrap_or(0.0) as f32,
+                lift: ColorWheel::default(),
+                gamma_wheel: ColorWheel::default(),
+                gain: ColorWheel::default(),
+                offset: ColorWheel::default(),
+                color_boost: settings.get("colorBoost").and_then(|v| v.as_f64()).unwrap_or(0.0) as f32,
+                hue_rotation: settings.get("hueRotation").and_then(|v| v.as_f64()).unwrap_or(0.0) as f32,
+                luminance_mix: settings.get("luminanceMix").and_then(|v| v.as_f64()).unwrap_or(100.0) as f32,
+                curves_enabled: settings.get("curvesEnabled").and_then(|v| v.as_bool()).unwrap_or(false),
+                curves: settings.get("curves").map(parse_curves).unwrap_or_default(),
+            }
+        }
+        
+        NodeType::Effects => {
+            let settings = data.get("settings").unwrap_or(data);
+            NodeProperties::Effects {
+                gaussian_blur: settings.get("gaussianBlur").and_then(|v| v.as_f64()).unwrap_or(0.0) as f32,
+                blur_edge_mode: settings.get("blurEdgeMode").and_then(|v| v.as_str()).and_then(EdgeMode::parse).unwrap_or_default(),
+                directional_blur: settings.get("directionalBlur").and_then(|v| v.as_f64()).unwrap_or(0.0) as f32,
+                directional_blur_angle: settings.get("directionalBlurAngle").and_then(|v| v.as_f64()).unwrap_or(0.0) as f32,
+                progressive_blur: settings.get("progressiveBlur").and_then(|v| v.as_f64()).unwrap_or(0.0) as f32,
+                progressive_blur_direction: BlurDirection::Bottom, // TODO: Parse from string
+                progressive_blur_falloff: settings.get("progressiveBlurFalloff").and_then(|v| v.as_f64()).unwrap_or(50.0) as f32,
+                glass_blinds: settings.get("glassBlinds").and_then(|v| v.as_f64()).unwrap_or(0.0) as f32,
+                glass_blinds_frequency: settings.get("glassBlindsFrequency").and_then(|v| v.as_f64()).unwrap_or(10.0) as f32,
+                glass_blinds_angle: settings.get("glassBlindsAngle").and_then(|v| v.as_f64()).unwrap_or(0.0) as f32,
+                glass_blinds_phase: settings.get("glassBlindsPhase").and_then(|v| v.as_f64()).unwrap_or(0.0) as f32,
+                grain: settings.get("grain").and_then(|v| v.as_f64()).unwrap_or(0.0) as f32,
+                grain_size: settings.get("grainSize").and_then(|v| v.as_f64()).unwrap_or(2.0) as f32,
+                grain_monochrome: settings.get("grainMonochrome").and_then(|v| v.as_bool()).unwrap_or(true),
+                grain_seed: settings.get("grainSeed").and_then(|v| v.as_u64()).unwrap_or(0) as u32,
+                sharpen: settings.get("sharpen").and_then(|v| v.as_f64()).unwrap_or(0.0) as f32,
+                vignette: settings.get("vignette").and_then(|v| v.as_f64()).unwrap_or(0.0) as f32,
+                vignette_roundness: settings.get("vignetteRoundness").and_then(|v| v.as_f64()).unwrap_or(50.0) as f32,
+                vignette_smoothness: settings.get("vignetteSmoothness").and_then(|v| v.as_f64()).unwrap_or(50.0) as f32,
+            }
+        }
+        
+        NodeType::Lut => {
+            NodeProperties::Lut {
+                file_name: data.get("fileName").and_then(|v| v.as_str()).unwrap_or("").to_string(),
+                lut: data.get("cube")
+                    .and_then(|v| v.as_str())
+                    .and_then(|text| Lut::parse_cube(text).ok())
+                    .map(|lut| luts.insert(lut)),
+                interpolation: data.get("interpolation")
+                    .and_then(|v| v.as_str())
+                    .and_then(LutInterpolation::parse)
+                    .unwrap_or_default(),
+                intensity: data.get("intensity").and_then(|v| v.as_f64()).unwrap_or(100.0) as f32,
+            }
+        }
+        
+        NodeType::Mask => {
+            let pair = |key: &str, default: [f32; 2]| -> [f32; 2] {
+                data.get(key)
+                    .and_then(|v| v.as_array())
+                    .and_then(|v| Some([v.first()?.as_f64()? as f32, v.get(1)?.as_f64()? as f32]))
+                    .unwrap_or(default)
+            };
+            NodeProperties::Mask {
+                source: data.get("source")
+                    .and_then(|v| v.as_str())
+                    .and_then(MaskSource::parse)
+                    .unwrap_or_default(),
+                center: pair("center", [0.5, 0.5]),
+                size: pair("size", [0.5, 0.5]),
+                angle: data.get("angle").and_then(|v| v.as_f64()).unwrap_or(0.0) as f32,
+                invert: data.get("invert").and_then(|v| v.as_bool()).unwrap_or(false),
+                feather: data.get("feather").and_then(|v| v.as_f64()).unwrap_or(0.0) as f32,
+                density: data.get("density").and_then(|v| v.as_f64()).unwrap_or(100.0) as f32,
+            }
+        }
+        
+        NodeType::Text => {
+            NodeProperties::Text {
+                text: data.get("text").and_then(|v| v.as_str()).unwrap_or("").to_string(),
+            }
+        }
+        
+        NodeType::Bucket => {
+            NodeProperties::Bucket {
+                images: string_list(data.get("images")),
+                image_ids: Vec::new(),
+            }
+        }
+        
+        NodeType::Batch => {
+            NodeProperties::Batch {
+                items: string_list(data.get("items")),
+            }
+        }
+        
+        NodeType::Compare => {
+            NodeProperties::Compare {
+                mode: data.get("mode")
+                    .and_then(|v| v.as_str())
+                    .and_then(CompareMode::parse)
+                    .unwrap_or_default(),
+                split: data.get("split").and_then(|v| v.as_f64()).unwrap_or(0.5) as f32,
+                metrics: None,
+            }
+        }
+        
+        NodeType::Composition => {
+            let layers = data.get("layers")
+                .and_then(|v| v.as_array())
+                .map(|layers| layers.iter().map(|layer| CompositionLayer {
+                    image: layer.get("image").and_then(|v| v.as_str()).unwrap_or("").to_string(),
+                    opacity: layer.get("opacity").and_then(|v| v.as_f64()).unwrap_or(1.0) as f32,
+                    blend_mode: layer.get("blendMode")
+                        .and_then(|v| v.as_str())
+                        .and_then(BlendMode::parse)
+                        .unwrap_or_default(),
+                    position: (
+                        layer.get("x").and_then(|v| v.as_f64()).unwrap_or(0.0) as f32,
+                        layer.get("y").and_then(|v| v.as_f64()).unwrap_or(0.0) as f32,
+                    ),
+                    scale: layer.get("scale").and_then(|v| v.as_f64()).unwrap_or(1.0) as f32,
+                }).collect())
+                .unwrap_or_default();
+            NodeProperties::Composition { layers }
+        }
+        
+        NodeType::Output => {
+            NodeProperties::Output {
+                name: data.get("name").and_then(|v| v.as_str()).unwrap_or("output").to_string(),
+                format: data.get("output_format")
+                    .and_then(|v| v.as_str())
+                    .and_then(ExportFormat::parse)
+                    .unwrap_or_default(),
+                max_size: data.get("maxSize").and_then(|v| v.as_u64()).unwrap_or(0) as u32,
+                bit_depth: data.get("bitDepth")
+                    .and_then(|v| v.as_u64())
+                    .and_then(ExportDepth::from_bits)
+                    .unwrap_or_default(),
+                color_space: data.get("colorSpace")
+                    .and_then(|v| v.as_str())
+                    .and_then(ColorSpace::parse)
+                    .unwrap_or_default(),
+            }
+        }
+        
+        // Default: use for_type
+        _ => NodeProperties::for_type(*node_type),
+    }
+}
+
+/// Parse `{ master, red, green, blue }` control point arrays
+fn parse_curves(data: &serde_json::Value) -> Curves {
+    let curve = |key: &str| -> Curve {
+        let points: Vec<[f32; 2]> = data.get(key)
+            .and_then(|v| v.as_array())
+            .map(|points| points.iter()
+                .filter_map(|p| {
+                    let x = p.get(0)?.as_f64()? as f32;
+                    let y = p.get(1)?.as_f64()? as f32;
+                    Some([x.clamp(0.0, 1.0), y.clamp(0.0, 1.0)])
+                })
+                .collect())
+            .unwrap_or_default();
+        
+        if points.len() < 2 {
+            return Curve::default();
+        }
+        let mut curve = Curve { points };
+        curve.points.sort_by(|a, b| a[0].total_cmp(&b[0]));
+        curve
+    };
+    
+    Curves {
+        master: curve("master"),
+        red: curve("red"),
+        green: curve("green"),
+        blue: curve("blue"),
+    }
+}
+
+/// Parse an array of strings, skipping anything else
+fn string_list(value: Option<&serde_json::Value>) -> Vec<String> {
+    value.and_then(|v| v.as_array())
+        .map(|items| items.iter().filter_map(|v| v.as_str()).map(String::from).collect())
+        .unwrap_or_default()
+}
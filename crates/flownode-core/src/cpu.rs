@@ -0,0 +1,813 @@
+//! The reference backend: every operation on the CPU
+//!
+//! Kernels run in row bands through [`kernel`], in parallel with the
+//! `parallel` feature. Other backends are checked against this one.
+
+use crate::backend::{Adjustments, ProcessingBackend, SHARPEN_SIGMA};
+use crate::blur::{self, edge_index};
+use crate::image_data::ImageData;
+use crate::kernel::{self, f32x4};
+use crate::lut::Lut;
+use crate::mask::Mask;
+use crate::nodes::{BlendMode, BlurDirection, ColorWheel, CompareMode, CompositionLayer, Curves, EdgeMode, LutInterpolation};
+
+/// Progressive blur strength at 100%, in pixels
+const PROGRESSIVE_MAX_SIGMA: f32 = 15.0;
+
+/// Blur strengths blended by progressive blur
+const PROGRESSIVE_LEVELS: usize = 8;
+
+/// Runs everything on the CPU
+#[derive(Debug, Default, Clone, Copy)]
+pub struct CpuBackend;
+
+impl ProcessingBackend for CpuBackend {
+    fn name(&self) -> String {
+        "CPU".to_string()
+    }
+    
+    fn gaussian_blur(&self, img: &ImageData, sigma: f32, edge: EdgeMode) -> ImageData {
+        blur::gaussian_blur(img, sigma, edge)
+    }
+    
+    fn color_wheels(&self, img: &ImageData, lift: &ColorWheel, gamma_wheel: &ColorWheel, gain: &ColorWheel, offset: &ColorWheel) -> ImageData {
+        let mut output = img.to_f32();
+        
+        let (lift_r, lift_g, lift_b) = wheel_to_rgb(lift);
+        let (gamma_r, gamma_g, gamma_b) = wheel_to_rgb(gamma_wheel);
+        let (gain_r, gain_g, gain_b) = wheel_to_rgb(gain);
+        let (offset_r, offset_g, offset_b) = wheel_to_rgb(offset);
+        
+        kernel::for_each_pixel(&mut output, |chunk| {
+            let mut r = chunk[0];
+            let mut g = chunk[1];
+            let mut b = chunk[2];
+            
+            // Apply color grading formula: result = gain * (lift * (1 - x) + x * gamma^x) + offset
+            // Simplified version for real-time:
+            
+            // Lift affects shadows (dark values)
+            r += lift_r * (1.0 - r) * 0.5;
+            g += lift_g * (1.0 - g) * 0.5;
+            b += lift_b * (1.0 - b) * 0.5;
+            
+            // Gamma affects midtones
+            let mid = 0.5;
+            let r_mid_weight = 1.0 - (r - mid).abs() * 2.0;
+            let g_mid_weight = 1.0 - (g - mid).abs() * 2.0;
+            let b_mid_weight = 1.0 - (b - mid).abs() * 2.0;
+            r += gamma_r * r_mid_weight.max(0.0) * 0.5;
+            g += gamma_g * g_mid_weight.max(0.0) * 0.5;
+            b += gamma_b * b_mid_weight.max(0.0) * 0.5;
+            
+            // Gain affects highlights (bright values)
+            r += gain_r * r * 0.5;
+            g += gain_g * g * 0.5;
+            b += gain_b * b * 0.5;
+            
+            // Offset is a flat shift
+            r += offset_r * 0.2;
+            g += offset_g * 0.2;
+            b += offset_b * 0.2;
+            
+            // Out-of-range values carry on to later nodes; Output clamps them
+            chunk[0] = r;
+            chunk[1] = g;
+            chunk[2] = b;
+        });
+        
+        ImageData::from_f32(output, img.width, img.height)
+    }
+    
+    fn curves(&self, img: &ImageData, curves: &Curves) -> ImageData {
+        let master = curves.master.to_lut();
+        let channels = [curves.red.to_lut(), curves.green.to_lut(), curves.blue.to_lut()];
+        
+        let mut output = img.to_f32();
+        kernel::for_each_pixel(&mut output, |chunk| {
+            for c in 0..3 {
+                chunk[c] = sample_table(&master, sample_table(&channels[c], chunk[c]));
+            }
+        });
+        
+        ImageData::from_f32(output, img.width, img.height)
+    }
+    
+    fn lut(&self, img: &ImageData, lut: &Lut, interpolation: LutInterpolation, intensity: f32) -> ImageData {
+        let intensity = intensity.clamp(0.0, 1.0);
+        let mut output = img.to_f32();
+        
+        kernel::for_each_pixel(&mut output, |chunk| {
+            let rgb = [chunk[0], chunk[1], chunk[2]];
+            let mapped = lut.apply(rgb, interpolation);
+            for c in 0..3 {
+                chunk[c] = rgb[c] + (mapped[c] - rgb[c]) * intensity;
+            }
+        });
+        
+        ImageData::from_f32(output, img.width, img.height)
+    }
+    
+    fn luminance_mix(&self, original: &ImageData, adjusted: &ImageData, mix: f32) -> ImageData {
+        let mix = (mix / 100.0).clamp(0.0, 1.0);
+        let mut output = adjusted.to_f32();
+        let source = original.to_f32();
+        let luma = |p: &[f32]| 0.299 * p[0] + 0.587 * p[1] + 0.114 * p[2];
+        
+        kernel::for_each_row(&mut output, adjusted.width as usize, |y, row| {
+            let source = &source[y * row.len()..(y + 1) * row.len()];
+            for (chunk, src) in row.chunks_exact_mut(4).zip(source.chunks_exact(4)) {
+                let original_luma = luma(src);
+                let adjusted_luma = luma(chunk);
+                let shift = (original_luma + (adjusted_luma - original_luma) * mix) - adjusted_luma;
+                
+                let alpha = chunk[3];
+                kernel::store(chunk, kernel::load(chunk) + shift);
+                chunk[3] = alpha;
+            }
+        });
+        
+        ImageData::from_f32(output, adjusted.width, adjusted.height)
+    }
+    
+    fn adjust(&self, img: &ImageData, params: &Adjustments) -> ImageData {
+        let Adjustments {
+            brightness, contrast, saturation, exposure,
+            highlights, shadows, temperature, tint,
+            vibrance, gamma, color_boost, hue_rotation,
+        } = *params;
+        let mut output = img.to_f32();
+        
+        // Convert -100..100 ranges to usable values
+        let brightness_factor = brightness / 100.0;
+        let contrast_factor = 1.0 + (contrast / 100.0);
+        let saturation_factor = 1.0 + (saturation / 100.0);
+        let exposure_factor = (exposure / 50.0).exp2(); // 2^(exposure/50) for natural feel
+        let gamma_value = 1.0 / (1.0 + gamma / 100.0).max(0.1);
+        let temp_shift = temperature / 100.0;
+        let tint_shift = tint / 100.0;
+        let vibrance_factor = vibrance / 100.0;
+        let boost_factor = color_boost / 100.0;
+        let hue_matrix = (hue_rotation.abs() > 0.01).then(|| hue_rotate_matrix(hue_rotation));
+        
+        kernel::for_each_pixel(&mut output, |chunk| {
+            let mut r = chunk[0];
+            let mut g = chunk[1];
+            let mut b = chunk[2];
+            
+            // Exposure
+            r *= exposure_factor;
+            g *= exposure_factor;
+            b *= exposure_factor;
+            
+            // Temperature (shift R/B balance)
+            r += temp_shift * 0.1;
+            b -= temp_shift * 0.1;
+            
+            // Tint (shift G/M balance)
+            g += tint_shift * 0.05;
+            
+            // Brightness
+            r += brightness_factor;
+            g += brightness_factor;
+            b += brightness_factor;
+            
+            // Contrast
+            r = (r - 0.5) * contrast_factor + 0.5;
+            g = (g - 0.5) * contrast_factor + 0.5;
+            b = (b - 0.5) * contrast_factor + 0.5;
+            
+            // Saturation
+            let luminance = 0.299 * r + 0.587 * g + 0.114 * b;
+            r = luminance + (r - luminance) * saturation_factor;
+            g = luminance + (g - luminance) * saturation_factor;
+            b = luminance + (b - luminance) * saturation_factor;
+            
+            // Vibrance (saturation that affects less saturated colors more)
+            let max_rgb = r.max(g).max(b);
+            let min_rgb = r.min(g).min(b);
+            let current_sat = if max_rgb > 0.0 { (max_rgb - min_rgb) / max_rgb } else { 0.0 };
+            let vib_mult = 1.0 + vibrance_factor * (1.0 - current_sat);
+            r = luminance + (r - luminance) * vib_mult;
+            g = luminance + (g - luminance) * vib_mult;
+            b = luminance + (b - luminance) * vib_mult;
+            
+            // Color boost (saturation that affects already saturated colors more)
+            if boost_factor != 0.0 {
+                let max_rgb = r.max(g).max(b);
+                let min_rgb = r.min(g).min(b);
+                let current_sat = if max_rgb > 0.0 { (max_rgb - min_rgb) / max_rgb } else { 0.0 };
+                let boost_mult = (1.0 + boost_factor * current_sat.clamp(0.0, 1.0)).max(0.0);
+                r = luminance + (r - luminance) * boost_mult;
+                g = luminance + (g - luminance) * boost_mult;
+                b = luminance + (b - luminance) * boost_mult;
+            }
+            
+            // Hue rotation (same matrix as CSS hue-rotate())
+            if let Some(m) = &hue_matrix {
+                let (r0, g0, b0) = (r, g, b);
+                r = m[0][0] * r0 + m[0][1] * g0 + m[0][2] * b0;
+                g = m[1][0] * r0 + m[1][1] * g0 + m[1][2] * b0;
+                b = m[2][0] * r0 + m[2][1] * g0 + m[2][2] * b0;
+            }
+            
+            // Highlights/Shadows (simplified)
+            if luminance > 0.5 {
+                let highlight_factor = (luminance - 0.5) * 2.0 * (highlights / 200.0);
+                r += highlight_factor;
+                g += highlight_factor;
+                b += highlight_factor;
+            } else {
+                let shadow_factor = (0.5 - luminance) * 2.0 * (shadows / 200.0);
+                r += shadow_factor;
+                g += shadow_factor;
+                b += shadow_factor;
+            }
+            
+            // Gamma, mirrored below zero
+            r = r.abs().powf(gamma_value).copysign(r);
+            g = g.abs().powf(gamma_value).copysign(g);
+            b = b.abs().powf(gamma_value).copysign(b);
+            
+            // Store unclamped; Output clamps
+            chunk[0] = r;
+            chunk[1] = g;
+            chunk[2] = b;
+        });
+        
+        ImageData::from_f32(output, img.width, img.height)
+    }
+    
+    fn composition(&self, inputs: &[(usize, ImageData)], layers: &[CompositionLayer]) -> ImageData {
+        let width = inputs[0].1.width;
+        let height = inputs[0].1.height;
+        let mut canvas = vec![0.0f32; (width * height * 4) as usize];
+        let default_layer = CompositionLayer::default();
+        
+        for (slot, img) in inputs {
+            let layer = layers.get(*slot).unwrap_or(&default_layer);
+            let scale = layer.scale.max(0.01);
+            let opacity = layer.opacity.clamp(0.0, 1.0);
+            let (offset_x, offset_y) = layer.position;
+            
+            // Destination area covered by this layer
+            let x0 = offset_x.floor().max(0.0) as u32;
+            let y0 = offset_y.floor().max(0.0) as u32;
+            let x1 = ((offset_x + img.width as f32 * scale).ceil().max(0.0) as u32).min(width);
+            let y1 = ((offset_y + img.height as f32 * scale).ceil().max(0.0) as u32).min(height);
+            
+            kernel::for_each_row(&mut canvas, width as usize, |y, row| {
+                let y = y as u32;
+                if !(y0..y1).contains(&y) {
+                    return;
+                }
+                for x in x0..x1 {
+                    // Map the destination pixel center back into the layer
+                    let sx = (x as f32 + 0.5 - offset_x) / scale - 0.5;
+                    let sy = (y as f32 + 0.5 - offset_y) / scale - 0.5;
+                    let Some(src) = sample_bilinear(img, sx, sy) else {
+                        continue;
+                    };
+                    
+                    let idx = (x * 4) as usize;
+                    composite_pixel(&mut row[idx..idx + 4], src, opacity, layer.blend_mode);
+                }
+            });
+        }
+        
+        kernel::for_each_pixel(&mut canvas, |p| kernel::store(p, kernel::load(p).max(f32x4::ZERO).min(f32x4::ONE)));
+        ImageData::from_f32(canvas, width, height)
+    }
+    
+    fn compare(&self, a: &ImageData, b: &ImageData, mode: CompareMode, split: f32) -> ImageData {
+        let width = a.width;
+        let height = a.height;
+        let split = split.clamp(0.0, 1.0);
+        let pixels_a = a.to_f32();
+        let pixels_b = b.to_f32();
+        
+        match mode {
+            CompareMode::SplitVertical | CompareMode::SplitHorizontal => {
+                let mut output = pixels_a;
+                let vertical = mode == CompareMode::SplitVertical;
+                let divider = if vertical {
+                    (split * width as f32) as u32
+                } else {
+                    (split * height as f32) as u32
+                };
+                
+                kernel::for_each_row(&mut output, width as usize, |y, row| {
+                    let start = y * row.len();
+                    for (x, pixel) in row.chunks_exact_mut(4).enumerate() {
+                        let pos = if vertical { x as u32 } else { y as u32 };
+                        let idx = start + x * 4;
+                        if pos.abs_diff(divider) <= 1 {
+                            // Divider line
+                            pixel.copy_from_slice(&[1.0; 4]);
+                        } else if pos > divider {
+                            pixel.copy_from_slice(&pixels_b[idx..idx + 4]);
+                        }
+                    }
+                });
+                
+                ImageData::from_f32(output, width, height)
+            }
+            
+            CompareMode::OnionSkin => {
+                let mut output = pixels_a;
+                kernel::for_each_row(&mut output, width as usize, |y, row| {
+                    let other = &pixels_b[y * row.len()..(y + 1) * row.len()];
+                    for (pa, pb) in row.chunks_exact_mut(4).zip(other.chunks_exact(4)) {
+                        kernel::store(pa, (kernel::load(pa) + kernel::load(pb)) * 0.5);
+                    }
+                });
+                ImageData::from_f32(output, width, height)
+            }
+            
+            CompareMode::Difference => {
+                let mut output = pixels_a;
+                kernel::for_each_row(&mut output, width as usize, |y, row| {
+                    let other = &pixels_b[y * row.len()..(y + 1) * row.len()];
+                    for (pa, pb) in row.chunks_exact_mut(4).zip(other.chunks_exact(4)) {
+                        let [dr, dg, db, _] = (kernel::load(pa) - kernel::load(pb)).abs().to_array();
+                        let diff = (dr + dg + db) / 3.0;
+                        // Amplify so small grading changes are visible
+                        let [r, g, b] = heat_map((diff * 4.0).min(1.0));
+                        pa.copy_from_slice(&[r, g, b, 1.0]);
+                    }
+                });
+                ImageData::from_f32(output, width, height)
+            }
+            
+            CompareMode::SideBySide => {
+                let out_width = width * 2;
+                let mut output = vec![0.0f32; (out_width * height * 4) as usize];
+                let row_len = (width * 4) as usize;
+                
+                for y in 0..height as usize {
+                    let src = y * row_len;
+                    let dst = y * row_len * 2;
+                    output[dst..dst + row_len].copy_from_slice(&pixels_a[src..src + row_len]);
+                    output[dst + row_len..dst + row_len * 2].copy_from_slice(&pixels_b[src..src + row_len]);
+                }
+                
+                ImageData::from_f32(output, out_width, height)
+            }
+        }
+    }
+    
+    fn sharpen(&self, img: &ImageData, amount: f32, edge: EdgeMode) -> ImageData {
+        // Create a blurred version
+        let blurred = blur::gaussian_blur(img, SHARPEN_SIGMA, edge).to_f32();
+        let mut output = img.to_f32();
+        
+        kernel::for_each_row(&mut output, img.width as usize, |y, row| {
+            let blurred = &blurred[y * row.len()..(y + 1) * row.len()];
+            for (pixel, blur) in row.chunks_exact_mut(4).zip(blurred.chunks_exact(4)) {
+                // Unsharp mask: original + amount * (original - blur)
+                let original = kernel::load(pixel);
+                let sharpened = original + (original - kernel::load(blur)) * amount;
+                let alpha = pixel[3];
+                kernel::store(pixel, sharpened);
+                pixel[3] = alpha;
+            }
+        });
+        
+        ImageData::from_f32(output, img.width, img.height)
+    }
+    
+    fn vignette(&self, img: &ImageData, intensity: f32, roundness: f32, smoothness: f32) -> ImageData {
+        let mut output = img.to_f32();
+        let width = img.width as f32;
+        let height = img.height as f32;
+        let cx = width / 2.0;
+        let cy = height / 2.0;
+        let max_dist = (cx * cx + cy * cy).sqrt();
+        
+        // Roundness: 0 = elliptical, 1 = circular
+        let aspect = width / height;
+        let x_scale = 1.0 + (1.0 - roundness) * (aspect - 1.0).abs();
+        
+        kernel::for_each_row(&mut output, img.width as usize, |y, row| {
+            for (x, pixel) in row.chunks_exact_mut(4).enumerate() {
+                let dx = (x as f32 - cx) / x_scale;
+                let dy = y as f32 - cy;
+                let dist = (dx * dx + dy * dy).sqrt() / max_dist;
+                
+                // Vignette falloff based on smoothness
+                let falloff_start = 0.3 + smoothness * 0.4;
+                let vignette = if dist < falloff_start {
+                    1.0
+                } else {
+                    let t = (dist - falloff_start) / (1.0 - falloff_start);
+                    1.0 - t.powf(2.0 - smoothness) * intensity
+                };
+                
+                for c in &mut pixel[..3] {
+                    *c *= vignette;
+                }
+            }
+        });
+        
+        ImageData::from_f32(output, img.width, img.height)
+    }
+    
+    fn directional_blur(&self, img: &ImageData, amount: f32, angle: f32, edge: EdgeMode) -> ImageData {
+        let source = img.to_f32();
+        let mut output = vec![0.0f32; source.len()];
+        let width = img.width as usize;
+        let height = img.height as usize;
+        
+        // Convert angle to radians and calculate direction
+        let angle_rad = angle.to_radians();
+        let dx = angle_rad.cos();
+        let dy = angle_rad.sin();
+        
+        // Number of samples based on amount
+        let samples = (amount * 20.0).max(1.0) as i32;
+        let count = (2 * samples + 1) as f32;
+        
+        kernel::for_each_row(&mut output, width, |y, row| {
+            for (x, pixel) in row.chunks_exact_mut(4).enumerate() {
+                // Colour is summed premultiplied by alpha
+                let mut sum = f32x4::ZERO;
+                
+                for i in -samples..=samples {
+                    let sx = edge_index(edge, (x as f32 + dx * i as f32).round() as isize, width);
+                    let sy = edge_index(edge, (y as f32 + dy * i as f32).round() as isize, height);
+                    let idx = (sy * width + sx) * 4;
+                    let a = source[idx + 3];
+                    sum += kernel::load(&source[idx..idx + 4]) * f32x4::new([a, a, a, 1.0]);
+                }
+                
+                let [r, g, b, a] = sum.to_array();
+                if a > 1e-6 {
+                    pixel.copy_from_slice(&[r / a, g / a, b / a, a / count]);
+                } else {
+                    pixel.copy_from_slice(&[0.0, 0.0, 0.0, a / count]);
+                }
+            }
+        });
+        
+        ImageData::from_f32(output, img.width, img.height)
+    }
+    
+    fn progressive_blur(&self, img: &ImageData, amount: f32, direction: &BlurDirection, falloff: f32, edge: EdgeMode) -> ImageData {
+        progressive_blur_with(img, amount, direction, falloff, |img, sigma| blur::gaussian_blur(img, sigma, edge))
+    }
+    
+    fn glass_blinds(&self, img: &ImageData, intensity: f32, frequency: f32, angle: f32, phase: f32) -> ImageData {
+        let source = img.to_f32();
+        let mut output = source.clone();
+        
+        let angle_rad = angle.to_radians();
+        let cos_a = angle_rad.cos();
+        let sin_a = angle_rad.sin();
+        
+        kernel::for_each_row(&mut output, img.width as usize, |y, row| {
+            let y = y as u32;
+            for (x, pixel) in row.chunks_exact_mut(4).enumerate() {
+                let x = x as u32;
+                // Rotate coordinates
+                let rx = x as f32 * cos_a + y as f32 * sin_a;
+                
+                // Calculate wave displacement
+                let wave = ((rx * frequency / 100.0 + phase * std::f32::consts::PI * 2.0).sin() * intensity * 20.0) as i32;
+                
+                // Calculate source coordinates
+                let sx = (x as i32 - (wave as f32 * sin_a) as i32).clamp(0, img.width as i32 - 1) as u32;
+                let sy = (y as i32 + (wave as f32 * cos_a) as i32).clamp(0, img.height as i32 - 1) as u32;
+                
+                let src_idx = ((sy * img.width + sx) * 4) as usize;
+                pixel[..3].copy_from_slice(&source[src_idx..src_idx + 3]);
+            }
+        });
+        
+        ImageData::from_f32(output, img.width, img.height)
+    }
+    
+    fn grain(&self, img: &ImageData, amount: f32, size: f32, monochrome: bool, seed: u32) -> ImageData {
+        let noise = GrainNoise::new(img.width, img.height, amount, size, monochrome, seed);
+        let mut output = img.to_f32();
+        kernel::for_each_row(&mut output, img.width as usize, |y, row| {
+            for (x, pixel) in row.chunks_exact_mut(4).enumerate() {
+                let alpha = pixel[3];
+                let value = kernel::load(pixel) + f32x4::new(noise.at(x as u32, y as u32));
+                kernel::store(pixel, value);
+                pixel[3] = alpha;
+            }
+        });
+        
+        ImageData::from_f32(output, img.width, img.height)
+    }
+    
+    fn mask_mix(&self, original: &ImageData, processed: &ImageData, mask: &Mask) -> ImageData {
+        let mut output = processed.to_f32();
+        let source = original.to_f32();
+        let values = mask.values();
+        
+        kernel::for_each_row(&mut output, processed.width as usize, |y, row| {
+            let width = row.len() / 4;
+            let source = &source[y * row.len()..(y + 1) * row.len()];
+            let values = &values[y * width..(y + 1) * width];
+            for ((pixel, src), &m) in row.chunks_exact_mut(4).zip(source.chunks_exact(4)).zip(values) {
+                let from = kernel::load(src);
+                kernel::store(pixel, from + (kernel::load(pixel) - from) * m);
+            }
+        });
+        
+        ImageData::from_f32(output, processed.width, processed.height)
+    }
+}
+
+/// Progressive (gradient) blur with the given Gaussian blur. The image is
+/// blurred at a few strengths and each pixel blends the two nearest its own,
+/// so the cost doesn't depend on the radius.
+pub fn progressive_blur_with(
+    img: &ImageData,
+    amount: f32,
+    direction: &BlurDirection,
+    falloff: f32,
+    blur: impl Fn(&ImageData, f32) -> ImageData,
+) -> ImageData {
+    let width = img.width;
+    let height = img.height;
+    let max_sigma = amount * PROGRESSIVE_MAX_SIGMA;
+    
+    // Blur strength at each pixel, in levels
+    let mut strength = Vec::with_capacity(img.pixel_count());
+    for y in 0..height {
+        for x in 0..width {
+            // Calculate blur factor based on position and direction
+            let factor = match direction {
+                BlurDirection::Top => 1.0 - (y as f32 / height as f32),
+                BlurDirection::Bottom => y as f32 / height as f32,
+                BlurDirection::Left => 1.0 - (x as f32 / width as f32),
+                BlurDirection::Right => x as f32 / width as f32,
+            };
+            
+            // Apply falloff curve
+            let blur_factor = (factor / falloff.max(0.01)).min(1.0).powf(2.0);
+            strength.push(blur_factor * PROGRESSIVE_LEVELS as f32);
+        }
+    }
+    
+    // Accumulate each level's share, premultiplied, one level at a time
+    let mut sum = vec![0.0f32; img.pixel_count() * 4];
+    for level in 0..=PROGRESSIVE_LEVELS {
+        let needed = |s: &f32| (s - level as f32).abs() < 1.0;
+        if !strength.iter().any(needed) {
+            continue;
+        }
+        let sigma = max_sigma * level as f32 / PROGRESSIVE_LEVELS as f32;
+        let pixels = blur(img, sigma).to_f32();
+        kernel::for_each_row(&mut sum, width as usize, |y, row| {
+            let start = y * width as usize;
+            for (x, total) in row.chunks_exact_mut(4).enumerate() {
+                let share = 1.0 - (strength[start + x] - level as f32).abs();
+                if share > 0.0 {
+                    let i = (start + x) * 4;
+                    let a = pixels[i + 3] * share;
+                    let p = kernel::load(&pixels[i..i + 4]) * f32x4::new([a, a, a, share]);
+                    kernel::store(total, kernel::load(total) + p);
+                }
+            }
+        });
+    }
+    
+    kernel::unpremultiply(&mut sum);
+    
+    ImageData::from_f32(sum, width, height)
+}
+
+/// Film grain offsets, one RGB triple per square block of pixels
+pub struct GrainNoise {
+    /// Block side in pixels
+    pub block_size: u32,
+    /// Blocks per row
+    pub blocks_x: u32,
+    /// `[r, g, b, 0]` per block, row by row
+    pub blocks: Vec<[f32; 4]>,
+}
+
+impl GrainNoise {
+    /// Draw the noise for an image. Blocks are drawn in order so a seed
+    /// always gives the same grain.
+    pub fn new(width: u32, height: u32, amount: f32, size: f32, monochrome: bool, seed: u32) -> Self {
+        // Peak grain strength: 50 levels out of 255
+        const GRAIN_SCALE: f32 = 50.0 / 255.0;
+        
+        // Use seed for reproducible noise
+        let mut rng_state = seed.wrapping_add(12345);
+        
+        // Size determines the grain "block" size
+        let block_size = size.max(1.0) as u32;
+        let blocks_x = width.div_ceil(block_size);
+        
+        let mut blocks = Vec::with_capacity((blocks_x * height.div_ceil(block_size)) as usize);
+        for _by in (0..height).step_by(block_size as usize) {
+            for _bx in (0..width).step_by(block_size as usize) {
+                // Generate noise for this block
+                rng_state = rng_state.wrapping_mul(1103515245).wrapping_add(12345);
+                let noise_val = ((rng_state >> 16) & 0xFF) as f32 / 255.0 - 0.5;
+                let noise = noise_val * 2.0 * amount * GRAIN_SCALE;
+                
+                // Color noise if not monochrome
+                let (nr, ng, nb) = if monochrome {
+                    (noise, noise, noise)
+                } else {
+                    rng_state = rng_state.wrapping_mul(1103515245).wrapping_add(12345);
+                    let nr = ((rng_state >> 16) & 0xFF) as f32 / 255.0 - 0.5;
+                    rng_state = rng_state.wrapping_mul(1103515245).wrapping_add(12345);
+                    let ng = ((rng_state >> 16) & 0xFF) as f32 / 255.0 - 0.5;
+                    rng_state = rng_state.wrapping_mul(1103515245).wrapping_add(12345);
+                    let nb = ((rng_state >> 16) & 0xFF) as f32 / 255.0 - 0.5;
+                    (nr * 2.0 * amount * GRAIN_SCALE, ng * 2.0 * amount * GRAIN_SCALE, nb * 2.0 * amount * GRAIN_SCALE)
+                };
+                blocks.push([nr, ng, nb, 0.0]);
+            }
+        }
+        
+        Self { block_size, blocks_x, blocks }
+    }
+    
+    /// Offset for the pixel at (x, y)
+    #[inline]
+    pub fn at(&self, x: u32, y: u32) -> [f32; 4] {
+        self.blocks[(y / self.block_size * self.blocks_x + x / self.block_size) as usize]
+    }
+}
+
+/// Look up a 0-1 value in an evenly spaced table, interpolating linearly
+fn sample_table(table: &[f32], v: f32) -> f32 {
+    let pos = v.clamp(0.0, 1.0) * (table.len() - 1) as f32;
+    let i = (pos as usize).min(table.len() - 2);
+    let f = pos - i as f32;
+    table[i] + (table[i + 1] - table[i]) * f
+}
+
+/// Convert a wheel position to an RGB shift: the angle picks the hue, the
+/// distance the strength
+pub(crate) fn wheel_to_rgb(wheel: &ColorWheel) -> (f32, f32, f32) {
+    // Angle determines hue, distance determines intensity
+    let angle = wheel.y.atan2(wheel.x);
+    let intensity = (wheel.x * wheel.x + wheel.y * wheel.y).sqrt();
+    
+    // Convert angle to RGB (simplified color wheel)
+    let r = (angle.cos() * 0.5 + 0.5) * intensity;
+    let g = ((angle + std::f32::consts::FRAC_PI_3 * 2.0).cos() * 0.5 + 0.5) * intensity;
+    let b = ((angle + std::f32::consts::FRAC_PI_3 * 4.0).cos() * 0.5 + 0.5) * intensity;
+    
+    // Also apply luminance as overall shift
+    let lum = wheel.luminance / 100.0;
+    
+    (r + lum * 0.5, g + lum * 0.5, b + lum * 0.5)
+}
+
+/// Hue rotation matrix from the CSS Filter Effects spec (`hue-rotate()`)
+pub(crate) fn hue_rotate_matrix(degrees: f32) -> [[f32; 3]; 3] {
+    let (sin, cos) = degrees.to_radians().sin_cos();
+    [
+        [
+            0.213 + cos * 0.787 - sin * 0.213,
+            0.715 - cos * 0.715 - sin * 0.715,
+            0.072 - cos * 0.072 + sin * 0.928,
+        ],
+        [
+            0.213 - cos * 0.213 + sin * 0.143,
+            0.715 + cos * 0.285 + sin * 0.140,
+            0.072 - cos * 0.072 - sin * 0.283,
+        ],
+        [
+            0.213 - cos * 0.213 - sin * 0.787,
+            0.715 - cos * 0.715 + sin * 0.715,
+            0.072 + cos * 0.928 + sin * 0.072,
+        ],
+    ]
+}
+
+/// Blend one channel (0-1) of source over backdrop, per the W3C compositing spec
+pub fn blend_channel(mode: BlendMode, backdrop: f32, source: f32) -> f32 {
+    let (cb, cs) = (backdrop, source);
+    match mode {
+        BlendMode::Normal => cs,
+        BlendMode::Multiply => cb * cs,
+        BlendMode::Screen => cb + cs - cb * cs,
+        BlendMode::Overlay => blend_channel(BlendMode::HardLight, cs, cb),
+        BlendMode::HardLight => {
+            if cs <= 0.5 {
+                cb * 2.0 * cs
+            } else {
+                blend_channel(BlendMode::Screen, cb, 2.0 * cs - 1.0)
+            }
+        }
+        BlendMode::SoftLight => {
+            if cs <= 0.5 {
+                cb - (1.0 - 2.0 * cs) * cb * (1.0 - cb)
+            } else {
+                let d = if cb <= 0.25 {
+                    ((16.0 * cb - 12.0) * cb + 4.0) * cb
+                } else {
+                    cb.sqrt()
+                };
+                cb + (2.0 * cs - 1.0) * (d - cb)
+            }
+        }
+        BlendMode::ColorDodge => {
+            if cb <= 0.0 {
+                0.0
+            } else if cs >= 1.0 {
+                1.0
+            } else {
+                (cb / (1.0 - cs)).min(1.0)
+            }
+        }
+        BlendMode::ColorBurn => {
+            if cb >= 1.0 {
+                1.0
+            } else if cs <= 0.0 {
+                0.0
+            } else {
+                1.0 - ((1.0 - cb) / cs).min(1.0)
+            }
+        }
+        BlendMode::Difference => (cb - cs).abs(),
+        BlendMode::Exclusion => cb + cs - 2.0 * cb * cs,
+    }
+}
+
+/// Composite a straight-alpha source pixel (0-1) onto a straight-alpha backdrop
+/// using source-over with the given blend mode
+fn composite_pixel(dst: &mut [f32], src: [f32; 4], opacity: f32, mode: BlendMode) {
+    let alpha_s = src[3] * opacity;
+    let alpha_b = dst[3];
+    let alpha_o = alpha_s + alpha_b * (1.0 - alpha_s);
+    
+    if alpha_o <= 0.0 {
+        dst.copy_from_slice(&[0.0; 4]);
+        return;
+    }
+    
+    for c in 0..3 {
+        let cb = dst[c];
+        // Where the backdrop is transparent the source shows unblended
+        let blended = (1.0 - alpha_b) * src[c] + alpha_b * blend_channel(mode, cb, src[c]);
+        let premultiplied = alpha_s * blended + alpha_b * cb * (1.0 - alpha_s);
+        dst[c] = premultiplied / alpha_o;
+    }
+    dst[3] = alpha_o;
+}
+
+/// Sample an image with bilinear filtering at pixel coordinates (pixel centers at
+/// integer positions). Returns None outside the image. Values are 0-1.
+fn sample_bilinear(img: &ImageData, x: f32, y: f32) -> Option<[f32; 4]> {
+    let max_x = img.width as f32 - 1.0;
+    let max_y = img.height as f32 - 1.0;
+    if x < -0.5 || y < -0.5 || x > max_x + 0.5 || y > max_y + 0.5 {
+        return None;
+    }
+    
+    let x = x.clamp(0.0, max_x);
+    let y = y.clamp(0.0, max_y);
+    let x0 = x.floor() as u32;
+    let y0 = y.floor() as u32;
+    let x1 = (x0 + 1).min(img.width - 1);
+    let y1 = (y0 + 1).min(img.height - 1);
+    let fx = x - x0 as f32;
+    let fy = y - y0 as f32;
+    
+    let p00 = img.get_pixel(x0, y0);
+    let p10 = img.get_pixel(x1, y0);
+    let p01 = img.get_pixel(x0, y1);
+    let p11 = img.get_pixel(x1, y1);
+    
+    let mut out = [0.0f32; 4];
+    for c in 0..4 {
+        let top = p00[c] * (1.0 - fx) + p10[c] * fx;
+        let bottom = p01[c] * (1.0 - fx) + p11[c] * fx;
+        out[c] = top * (1.0 - fy) + bottom * fy;
+    }
+    Some(out)
+}
+
+/// Map 0-1 to a black → blue → red → yellow → white heat-map colour
+fn heat_map(t: f32) -> [f32; 3] {
+    const STOPS: [[f32; 3]; 5] = [
+        [0.0, 0.0, 0.0],
+        [0.0, 0.0, 1.0],
+        [1.0, 0.0, 0.0],
+        [1.0, 1.0, 0.0],
+        [1.0, 1.0, 1.0],
+    ];
+    
+    let scaled = t.clamp(0.0, 1.0) * (STOPS.len() - 1) as f32;
+    let i = (scaled as usize).min(STOPS.len() - 2);
+    let f = scaled - i as f32;
+    
+    let mut rgb = [0.0f32; 3];
+    for c in 0..3 {
+        rgb[c] = STOPS[i][c] * (1.0 - f) + STOPS[i + 1][c] * f;
+    }
+    rgb
+}
+
@@ -0,0 +1,893 @@
+//! Node graph execution engine
+//!
+//! Executes the node graph in topological order, processing images through connected nodes.
+//! Node outputs are cached between runs and only recomputed when the node's
+//! properties or anything upstream of it has changed. Images are converted into
+//! the graph's working space on entry and out of it at Output nodes.
+//!
+//! Bucket and Batch nodes output a list of items. A node with a list on any
+//! input runs once per item and outputs a list itself, so a Bucket of 50
+//! images gives 50 results at every Output downstream.
+//!
+//! Adjust, Effects and Composition take an optional mask; where it is 0 the
+//! node passes its input through unchanged.
+//!
+//! The pixel work itself is done by a [`ProcessingBackend`]: the CPU one by
+//! default, or the GPU with the `gpu` feature.
+
+use std::collections::HashMap;
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+use std::sync::Arc;
+use uuid::Uuid;
+
+use crate::color::{self, ColorSpace, WorkingSpace};
+use crate::graph::NodeGraph;
+use crate::nodes::{NodeType, NodeProperties, CompareMetrics, CompositionLayer, EdgeMode, MaskSource, SlotType};
+use crate::image_data::{ImageData, ImageLimits};
+use crate::mask::Mask;
+use crate::backend::{Adjustments, ProcessingBackend, SHARPEN_SIGMA};
+use crate::cpu::CpuBackend;
+
+/// Error returned when a progress callback cancels a run
+pub const CANCELLED: &str = "Run cancelled";
+
+/// Result of executing a node
+#[derive(Clone)]
+pub enum NodeOutput {
+    /// Image/content output
+    Image(ImageData),
+    /// Text output
+    Text(String),
+    /// Mask output, from Mask nodes
+    Mask(Mask),
+    /// One value per item, from Bucket/Batch nodes and everything downstream
+    Batch(Vec<NodeOutput>),
+    /// No output
+    None,
+}
+
+/// How far a run has got, reported as each node (or batch item) starts
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Progress {
+    /// Nodes finished so far, of `nodes` in the graph
+    pub node: usize,
+    pub nodes: usize,
+    /// Batch item being computed, of `items` (1 for nodes that don't fan out)
+    pub item: usize,
+    pub items: usize,
+}
+
+impl Progress {
+    /// Overall completion from 0 to 1
+    pub fn fraction(&self) -> f32 {
+        if self.nodes == 0 {
+            return 1.0;
+        }
+        (self.node as f32 + self.item as f32 / self.items.max(1) as f32) / self.nodes as f32
+    }
+}
+
+/// Statistics from the last execution
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ExecutionStats {
+    /// Nodes that were (re)computed
+    pub computed: usize,
+    /// Nodes whose cached output was reused
+    pub cached: usize,
+    /// Batch items computed by nodes that fanned out
+    pub items: usize,
+}
+
+/// A run in progress: the nodes in order, how far it has got and the cache
+/// key of every node done. Made by [`Executor::start`].
+pub struct Run {
+    order: Vec<Uuid>,
+    keys: HashMap<Uuid, u64>,
+    progress: Progress,
+}
+
+/// Execution context for running the node graph
+pub struct Executor {
+    /// Cached outputs from nodes (kept across runs)
+    outputs: HashMap<Uuid, NodeOutput>,
+    
+    /// Cache key each output was computed with
+    keys: HashMap<Uuid, u64>,
+    
+    /// Similarity metrics from Compare nodes (of the last item when fanned out)
+    metrics: HashMap<Uuid, CompareMetrics>,
+    
+    /// Statistics from the last run
+    stats: ExecutionStats,
+    
+    /// Tile size for neighbourhood filters
+    limits: ImageLimits,
+    
+    /// Working space the cached outputs were computed in
+    working_space: WorkingSpace,
+    
+    /// Batch item being computed while a node fans out
+    item: Option<usize>,
+    
+    /// Where the pixel work runs
+    backend: Arc<dyn ProcessingBackend>,
+    
+    /// Size of the input images relative to the ones the settings were made
+    /// on; below 1 for proxy runs
+    pixel_scale: f32,
+}
+
+impl Executor {
+    pub fn new() -> Self {
+        Self {
+            outputs: HashMap::new(),
+            keys: HashMap::new(),
+            metrics: HashMap::new(),
+            stats: ExecutionStats::default(),
+            limits: ImageLimits::default(),
+            working_space: WorkingSpace::default(),
+            item: None,
+            backend: Arc::new(CpuBackend),
+            pixel_scale: 1.0,
+        }
+    }
+    
+    /// Run node operations on `backend`. Backends may differ by rounding
+    /// error, so the cache is dropped.
+    pub fn set_backend(&mut self, backend: Arc<dyn ProcessingBackend>) {
+        self.backend = backend;
+        self.invalidate();
+    }
+    
+    /// Backend node operations run on
+    pub fn backend(&self) -> &dyn ProcessingBackend {
+        self.backend.as_ref()
+    }
+    
+    /// Change the processing limits. Tiling doesn't change results, so the
+    /// cache stays valid.
+    pub fn set_limits(&mut self, limits: ImageLimits) {
+        self.limits = limits;
+    }
+    
+    /// Scale settings measured in pixels (blur radii, grain size, mask
+    /// feather, layer positions) for inputs downscaled by `scale`, so proxy
+    /// runs look like the full-resolution result. Changing it drops the cache.
+    pub fn set_pixel_scale(&mut self, scale: f32) {
+        if scale != self.pixel_scale {
+            self.pixel_scale = scale;
+            self.invalidate();
+        }
+    }
+    
+    /// Drop all cached outputs so the next run recomputes every node
+    pub fn invalidate(&mut self) {
+        self.outputs.clear();
+        self.keys.clear();
+        self.metrics.clear();
+    }
+    
+    /// Statistics from the last run
+    pub fn stats(&self) -> ExecutionStats {
+        self.stats
+    }
+    
+    /// PSNR/SSIM of every Compare node with both inputs connected. A Compare
+    /// node fed a batch runs once per item and keeps the last item's metrics.
+    pub fn compare_metrics(&self) -> &HashMap<Uuid, CompareMetrics> {
+        &self.metrics
+    }
+    
+    /// Output a node produced in the last run
+    pub fn output(&self, node_id: Uuid) -> Option<&NodeOutput> {
+        self.outputs.get(&node_id)
+    }
+    
+    /// Execute the entire graph and return the results of every Output node,
+    /// keyed by node ID: one image, or one per item if a batch reached it.
+    /// Output nodes with nothing connected are left out.
+    pub fn execute(&mut self, graph: &NodeGraph, input_images: &HashMap<u64, ImageData>) -> Result<HashMap<Uuid, Vec<ImageData>>, String> {
+        self.execute_with_progress(graph, input_images, &mut |_| true)
+    }
+    
+    /// [`Executor::execute`], calling `on_progress` as each node or batch item
+    /// starts. Returning false from it cancels the run; outputs computed so far
+    /// stay cached.
+    pub fn execute_with_progress(
+        &mut self,
+        graph: &NodeGraph,
+        input_images: &HashMap<u64, ImageData>,
+        on_progress: &mut dyn FnMut(Progress) -> bool,
+    ) -> Result<HashMap<Uuid, Vec<ImageData>>, String> {
+        let mut run = self.start(graph)?;
+        while self.step(&mut run, graph, input_images, on_progress)? {}
+        Ok(self.finish(run, graph))
+    }
+    
+    /// Begin running `graph` a node at a time with [`Executor::step`], for
+    /// callers that handle other work between nodes
+    pub fn start(&mut self, graph: &NodeGraph) -> Result<Run, String> {
+        self.stats = ExecutionStats::default();
+        
+        if graph.working_space != self.working_space {
+            self.invalidate();
+            self.working_space = graph.working_space;
+        }
+        
+        // Get topological order
+        let order = self.topological_sort(graph)?;
+        let progress = Progress { nodes: order.len(), items: 1, ..Default::default() };
+        Ok(Run { order, keys: HashMap::new(), progress })
+    }
+    
+    /// Compute the next node of `run` that isn't cached, reusing cached
+    /// outputs on the way. Returns false once every node is done.
+    pub fn step(
+        &mut self,
+        run: &mut Run,
+        graph: &NodeGraph,
+        input_images: &HashMap<u64, ImageData>,
+        on_progress: &mut dyn FnMut(Progress) -> bool,
+    ) -> Result<bool, String> {
+        // Skip nodes whose inputs haven't changed
+        while let Some(&node_id) = run.order.get(run.progress.node) {
+            let key = self.node_key(graph, node_id, &run.keys)?;
+            run.progress = Progress { item: 0, items: 1, ..run.progress };
+            
+            let cached = self.keys.get(&node_id) == Some(&key) && self.outputs.contains_key(&node_id);
+            if cached {
+                self.stats.cached += 1;
+            } else {
+                self.execute_node(graph, node_id, input_images, run.progress, on_progress)?;
+                self.keys.insert(node_id, key);
+                self.stats.computed += 1;
+            }
+            
+            run.keys.insert(node_id, key);
+            run.progress.node += 1;
+            if !cached {
+                return Ok(true);
+            }
+        }
+        on_progress(run.progress);
+        Ok(false)
+    }
+    
+    /// End a run once [`Executor::step`] has returned false, and return the
+    /// results of every Output node as [`Executor::execute`] does
+    pub fn finish(&mut self, run: Run, graph: &NodeGraph) -> HashMap<Uuid, Vec<ImageData>> {
+        let keys = run.keys;
+        
+        // Forget nodes that are no longer in the graph
+        self.outputs.retain(|id, _| keys.contains_key(id));
+        self.metrics.retain(|id, _| keys.contains_key(id));
+        self.keys = keys;
+        
+        // Collect the results of all Output nodes
+        let mut results = HashMap::new();
+        
+        for (id, node) in graph.nodes_iter() {
+            if node.node_type != NodeType::Output {
+                continue;
+            }
+            let images: Vec<ImageData> = match self.outputs.get(id) {
+                Some(NodeOutput::Image(img)) => vec![img.clone()],
+                Some(NodeOutput::Batch(items)) => items.iter()
+                    .filter_map(|item| match item {
+                        NodeOutput::Image(img) => Some(img.clone()),
+                        _ => None,
+                    })
+                    .collect(),
+                _ => Vec::new(),
+            };
+            if !images.is_empty() {
+                results.insert(*id, images);
+            }
+        }
+        
+        results
+    }
+    
+    /// Execute a single node, once per item if any of its inputs is a batch
+    fn execute_node(
+        &mut self,
+        graph: &NodeGraph,
+        node_id: Uuid,
+        input_images: &HashMap<u64, ImageData>,
+        progress: Progress,
+        on_progress: &mut dyn FnMut(Progress) -> bool,
+    ) -> Result<(), String> {
+        let node = graph.nodes_iter()
+            .find(|(id, _)| **id == node_id)
+            .map(|(_, n)| n)
+            .ok_or("Node not found")?;
+        
+        let output = match self.batch_len(graph, node_id) {
+            // Buckets collect batches rather than fanning out over them
+            Some(items) if node.node_type != NodeType::Bucket => {
+                let mut results = Vec::with_capacity(items);
+                for item in 0..items {
+                    if !on_progress(Progress { item, items, ..progress }) {
+                        return Err(CANCELLED.to_string());
+                    }
+                    self.item = Some(item);
+                    let result = self.compute_node(graph, node, input_images);
+                    self.item = None;
+                    results.push(result?);
+                }
+                self.stats.items += items;
+                NodeOutput::Batch(results)
+            }
+            _ => {
+                if !on_progress(progress) {
+                    return Err(CANCELLED.to_string());
+                }
+                self.compute_node(graph, node, input_images)?
+            }
+        };
+        
+        self.outputs.insert(node_id, output);
+        Ok(())
+    }
+    
+    /// Number of items a node fans out over: the shortest batch on any of its
+    /// inputs, or `None` if no input is a batch
+    fn batch_len(&self, graph: &NodeGraph, node_id: Uuid) -> Option<usize> {
+        graph.connections_iter()
+            .filter(|c| c.to_node == node_id)
+            .filter_map(|c| match self.outputs.get(&c.from_node) {
+                Some(NodeOutput::Batch(items)) => Some(items.len()),
+                _ => None,
+            })
+            .min()
+    }
+    
+    /// Compute a node's output from its inputs (for the current batch item)
+    fn compute_node(
+        &mut self,
+        graph: &NodeGraph,
+        node: &crate::nodes::Node,
+        input_images: &HashMap<u64, ImageData>,
+    ) -> Result<NodeOutput, String> {
+        let node_id = node.id;
+        let output = match &node.properties {
+            // === Image Node ===
+            NodeProperties::Image { texture_id, .. } => {
+                if let Some(tex_id) = texture_id {
+                    if let Some(img) = input_images.get(tex_id) {
+                        NodeOutput::Image(color::convert(img, self.working_space.color_space()))
+                    } else {
+                        NodeOutput::None
+                    }
+                } else {
+                    NodeOutput::None
+                }
+            }
+            
+            // === Adjust Node (full color grading) ===
+            NodeProperties::Adjust { 
+                brightness, contrast, saturation, exposure,
+                highlights, shadows, temperature, tint,
+                vibrance, gamma,
+                lift, gamma_wheel, gain, offset,
+                color_boost, hue_rotation, luminance_mix,
+                curves_enabled, curves,
+            } => {
+                let input = self.get_input_image(graph, node_id)?;
+                if let Some(img) = input {
+                    let mut result = self.backend.adjust(&img, &Adjustments {
+                        brightness: *brightness, contrast: *contrast, saturation: *saturation, exposure: *exposure,
+                        highlights: *highlights, shadows: *shadows, temperature: *temperature, tint: *tint,
+                        vibrance: *vibrance, gamma: *gamma,
+                        color_boost: *color_boost, hue_rotation: *hue_rotation,
+                    });
+                    
+                    // Apply color wheels if any are non-zero
+                    if lift.x.abs() > 0.01 || lift.y.abs() > 0.01 ||
+                       gamma_wheel.x.abs() > 0.01 || gamma_wheel.y.abs() > 0.01 ||
+                       gain.x.abs() > 0.01 || gain.y.abs() > 0.01 ||
+                       offset.x.abs() > 0.01 || offset.y.abs() > 0.01 {
+                        result = self.backend.color_wheels(&result, lift, gamma_wheel, gain, offset);
+                    }
+                    
+                    if *curves_enabled && !curves.is_identity() {
+                        // Curves are edited on perceptual values
+                        let space = self.working_space.color_space().encoded();
+                        result = self.in_space(&result, space, |img| self.backend.curves(img, curves));
+                    }
+                    
+                    if *luminance_mix < 100.0 {
+                        result = self.backend.luminance_mix(&img, &result, *luminance_mix);
+                    }
+                    
+                    NodeOutput::Image(self.masked(graph, node, &img, result))
+                } else {
+                    NodeOutput::None
+                }
+            }
+            
+            // === Effects Node ===
+            NodeProperties::Effects {
+                gaussian_blur, blur_edge_mode, directional_blur, directional_blur_angle,
+                progressive_blur, progressive_blur_direction, progressive_blur_falloff,
+                glass_blinds, glass_blinds_frequency, glass_blinds_angle, glass_blinds_phase,
+                grain, grain_size, grain_monochrome, grain_seed,
+                sharpen, vignette, vignette_roundness, vignette_smoothness
+            } => {
+                let input = self.get_input_image(graph, node_id)?;
+                if let Some(img) = input {
+                    let mut result = img.clone();
+                    
+                    // Apply effects in order
+                    let edge = *blur_edge_mode;
+                    if *gaussian_blur > 0.0 {
+                        // The slider is a radius in half pixels; the radius is σ
+                        let sigma = *gaussian_blur * 0.5 * self.pixel_scale;
+                        let halo = (sigma * 3.0).ceil() as u32 + 1;
+                        result = self.blur_tiled(&result, halo, edge, |tile| self.backend.gaussian_blur(tile, sigma, edge));
+                    }
+                    if *directional_blur > 0.0 {
+                        // Reaches 20 px at full amount
+                        let amount = *directional_blur / 100.0 * self.pixel_scale;
+                        let halo = (amount * 20.0).max(1.0) as u32 + 1;
+                        result = self.blur_tiled(&result, halo, edge, |tile| {
+                            self.backend.directional_blur(tile, amount, *directional_blur_angle, edge)
+                        });
+                    }
+                    if *progressive_blur > 0.0 {
+                        result = self.backend.progressive_blur(&result, *progressive_blur / 100.0 * self.pixel_scale, progressive_blur_direction, *progressive_blur_falloff / 100.0, edge);
+                    }
+                    if *glass_blinds > 0.0 {
+                        result = self.backend.glass_blinds(&result, *glass_blinds / 100.0 * self.pixel_scale, *glass_blinds_frequency / self.pixel_scale, *glass_blinds_angle, *glass_blinds_phase / 100.0);
+                    }
+                    if *sharpen > 0.0 {
+                        let halo = (SHARPEN_SIGMA * 3.0).ceil() as u32 + 1;
+                        result = self.blur_tiled(&result, halo, edge, |tile| self.backend.sharpen(tile, *sharpen / 100.0, edge));
+                    }
+                    if *grain > 0.0 {
+                        result = self.backend.grain(&result, *grain / 100.0, *grain_size * self.pixel_scale, *grain_monochrome, *grain_seed);
+                    }
+                    if *vignette > 0.0 {
+                        result = self.backend.vignette(&result, *vignette / 100.0, *vignette_roundness / 100.0, *vignette_smoothness / 100.0);
+                    }
+                    
+                    NodeOutput::Image(self.masked(graph, node, &img, result))
+                } else {
+                    NodeOutput::None
+                }
+            }
+            
+            // === LUT Node ===
+            NodeProperties::Lut { lut, interpolation, intensity, .. } => {
+                let lut = lut.as_ref().and_then(|r| graph.luts.get(r.hash));
+                match (self.get_input_image(graph, node_id)?, lut) {
+                    (Some(img), Some(lut)) if *intensity > 0.0 => {
+                        // .cube files expect sRGB-encoded input
+                        NodeOutput::Image(self.in_space(&img, ColorSpace::Srgb, |img| {
+                            self.backend.lut(img, lut, *interpolation, *intensity / 100.0)
+                        }))
+                    }
+                    // No table loaded yet, or it hasn't reached this graph: pass
+                    // the image through
+                    (Some(img), _) => NodeOutput::Image(img),
+                    (None, _) => NodeOutput::None,
+                }
+            }
+            
+            // === Mask Node ===
+            NodeProperties::Mask { source, center, size, angle, invert, feather, density } => {
+                match self.get_input_image(graph, node_id)? {
+                    Some(img) => {
+                        let mut mask = if source.is_shape() {
+                            Mask::shape(img.width, img.height, *source, *center, *size, *angle)
+                        } else {
+                            // Channels are read as perceptual values, like curves
+                            let space = self.working_space.color_space().encoded();
+                            Mask::from_channel(&color::convert(&img, space), *source)
+                        };
+                        
+                        if *feather > 0.0 {
+                            // Same scale as the Effects blur slider
+                            let sigma = *feather * 0.5 * self.pixel_scale;
+                            let halo = (sigma * 3.0).ceil() as u32 + 1;
+                            let blurred = self.blur_tiled(&mask.to_image(), halo, EdgeMode::Clamp, |tile| {
+                                self.backend.gaussian_blur(tile, sigma, EdgeMode::Clamp)
+                            });
+                            mask = Mask::from_channel(&blurred, MaskSource::Red);
+                        }
+                        if *invert {
+                            mask = mask.inverted();
+                        }
+                        if *density < 100.0 {
+                            mask = mask.with_density(*density / 100.0);
+                        }
+                        
+                        NodeOutput::Mask(mask)
+                    }
+                    None => NodeOutput::None,
+                }
+            }
+            
+            // === Text Nodes ===
+            NodeProperties::Text { text } => {
+                NodeOutput::Text(text.clone())
+            }
+            
+            NodeProperties::Concat { separator } => {
+                // Get text inputs and join them
+                let text1 = self.get_input_text(graph, node_id, 0);
+                let text2 = self.get_input_text(graph, node_id, 1);
+                let result = format!("{}{}{}", text1.unwrap_or_default(), separator, text2.unwrap_or_default());
+                NodeOutput::Text(result)
+            }
+            
+            // === Compare Node ===
+            NodeProperties::Compare { mode, split, .. } => {
+                let first = self.get_input_image_at(graph, node_id, 0);
+                let second = self.get_input_image_at(graph, node_id, 1);
+                self.metrics.remove(&node_id);
+                
+                match (first, second) {
+                    (Some(a), Some(b)) => {
+                        // Compare at the first input's resolution
+                        let b = crate::image_data::resize(&b, a.width, a.height);
+                        self.metrics.insert(node_id, compare_metrics(&a, &b));
+                        NodeOutput::Image(self.backend.compare(&a, &b, *mode, *split))
+                    }
+                    (Some(img), None) | (None, Some(img)) => NodeOutput::Image(img),
+                    (None, None) => NodeOutput::None,
+                }
+            }
+            
+            // Pass through for content nodes
+            NodeProperties::Content { .. } => {
+                let input = self.get_input_image(graph, node_id)?;
+                if let Some(img) = input {
+                    NodeOutput::Image(img)
+                } else {
+                    NodeOutput::None
+                }
+            }
+            
+            // === Bucket Node: its own images, then whatever is connected ===
+            NodeProperties::Bucket { image_ids, .. } => {
+                let space = self.working_space.color_space();
+                let mut items: Vec<NodeOutput> = image_ids.iter()
+                    .filter_map(|id| input_images.get(id))
+                    .map(|img| NodeOutput::Image(color::convert(img, space)))
+                    .collect();
+                match self.input_at(graph, node_id, 0) {
+                    Some(NodeOutput::Batch(batch)) => items.extend(batch.iter().cloned()),
+                    Some(NodeOutput::Image(img)) => items.push(NodeOutput::Image(img.clone())),
+                    _ => {}
+                }
+                NodeOutput::Batch(items)
+            }
+            
+            // === Batch Node: one text item per entry ===
+            NodeProperties::Batch { items } => {
+                NodeOutput::Batch(items.iter().cloned().map(NodeOutput::Text).collect())
+            }
+            
+            // === Composition Node (layer stack) ===
+            NodeProperties::Composition { layers } => {
+                let inputs: Vec<(usize, ImageData)> = node.node_type.inputs().iter()
+                    .enumerate()
+                    .filter(|(_, info)| info.slot_type == SlotType::Content)
+                    .filter_map(|(slot, _)| self.get_input_image_at(graph, node_id, slot).map(|img| (slot, img)))
+                    .collect();
+                
+                if inputs.is_empty() {
+                    NodeOutput::None
+                } else {
+                    let layers: Vec<CompositionLayer> = layers.iter()
+                        .map(|layer| CompositionLayer {
+                            position: (layer.position.0 * self.pixel_scale, layer.position.1 * self.pixel_scale),
+                            ..layer.clone()
+                        })
+                        .collect();
+                    let result = self.backend.composition(&inputs, &layers);
+                    if self.input_mask(graph, node).is_some() {
+                        // Outside the mask only the bottom layer shows
+                        let bottom = self.backend.composition(&inputs[..1], &layers);
+                        NodeOutput::Image(self.masked(graph, node, &bottom, result))
+                    } else {
+                        NodeOutput::Image(result)
+                    }
+                }
+            }
+            
+            // === Output Node ===
+            NodeProperties::Output { max_size, color_space, .. } => {
+                let input = self.get_input_image(graph, node_id)?;
+                // Values outside 0-1 are kept through the graph and clamped here
+                match input {
+                    Some(img) if *max_size > 0 => NodeOutput::Image(color::convert(&crate::image_data::downscale(&img, *max_size), *color_space).clamped()),
+                    Some(img) => NodeOutput::Image(color::convert(&img, *color_space).clamped()),
+                    None => NodeOutput::None,
+                }
+            }
+            
+            // Other node types - not yet implemented
+            _ => NodeOutput::None,
+        };
+        
+        // Everything upstream of the Output nodes is in the working space
+        Ok(match output {
+            NodeOutput::Image(img) if node.node_type != NodeType::Output => {
+                NodeOutput::Image(img.with_color_space(self.working_space.color_space()))
+            }
+            other => other,
+        })
+    }
+    
+    /// Compute the cache key for a node from its properties and the keys of its inputs.
+    /// Nodes must be visited in topological order so upstream keys are already known.
+    fn node_key(&self, graph: &NodeGraph, node_id: Uuid, upstream: &HashMap<Uuid, u64>) -> Result<u64, String> {
+        let node = graph.nodes.get(&node_id).ok_or("Node not found")?;
+        let mut hasher = DefaultHasher::new();
+        
+        node.node_type.name().hash(&mut hasher);
+        serde_json::to_string(&node.properties)
+            .map_err(|e| format!("Failed to hash node properties: {}", e))?
+            .hash(&mut hasher);
+        
+        // Loaded images aren't part of the serialized properties
+        match &node.properties {
+            NodeProperties::Image { texture_id, .. } => texture_id.hash(&mut hasher),
+            NodeProperties::Bucket { image_ids, .. } => image_ids.hash(&mut hasher),
+            // Properties hold only the table's hash; a missing table renders differently
+            NodeProperties::Lut { lut: Some(r), .. } => graph.luts.get(r.hash).is_some().hash(&mut hasher),
+            _ => {}
+        }
+        
+        // Connections in a stable order so the key doesn't depend on wiring history
+        let mut inputs: Vec<(usize, usize, u64)> = graph.connections_iter()
+            .filter(|c| c.to_node == node_id)
+            .map(|c| (c.to_slot, c.from_slot, upstream.get(&c.from_node).copied().unwrap_or(0)))
+            .collect();
+        inputs.sort_unstable();
+        inputs.hash(&mut hasher);
+        
+        Ok(hasher.finish())
+    }
+    
+    /// Get the image input for a node (from first connected input)
+    fn get_input_image(&self, graph: &NodeGraph, node_id: Uuid) -> Result<Option<ImageData>, String> {
+        Ok(self.get_input_image_at(graph, node_id, 0))
+    }
+    
+    /// Output connected to a specific input slot
+    fn input_at(&self, graph: &NodeGraph, node_id: Uuid, slot: usize) -> Option<&NodeOutput> {
+        let conn = graph.connections_iter().find(|c| c.to_node == node_id && c.to_slot == slot)?;
+        self.outputs.get(&conn.from_node)
+    }
+    
+    /// Value at an input slot; while fanning out, the current item of a batch
+    fn input_value(&self, graph: &NodeGraph, node_id: Uuid, slot: usize) -> Option<&NodeOutput> {
+        match self.input_at(graph, node_id, slot)? {
+            NodeOutput::Batch(items) => items.get(self.item.unwrap_or(0)),
+            other => Some(other),
+        }
+    }
+    
+    /// Get image input at a specific slot
+    fn get_input_image_at(&self, graph: &NodeGraph, node_id: Uuid, slot: usize) -> Option<ImageData> {
+        match self.input_value(graph, node_id, slot) {
+            Some(NodeOutput::Image(img)) => Some(img.clone()),
+            _ => None,
+        }
+    }
+    
+    /// Mask on the node's mask input, if it has one and it's connected
+    fn input_mask(&self, graph: &NodeGraph, node: &crate::nodes::Node) -> Option<&Mask> {
+        match self.input_value(graph, node.id, node.node_type.mask_slot()?) {
+            Some(NodeOutput::Mask(mask)) => Some(mask),
+            _ => None,
+        }
+    }
+    
+    /// Keep `result` only where the node's mask is set, showing `original`
+    /// elsewhere. Without a mask the result is returned unchanged.
+    fn masked(&self, graph: &NodeGraph, node: &crate::nodes::Node, original: &ImageData, result: ImageData) -> ImageData {
+        match self.input_mask(graph, node) {
+            Some(mask) => {
+                let mask = mask.resize(result.width, result.height);
+                self.backend.mask_mix(original, &result, &mask)
+            }
+            None => result,
+        }
+    }
+    
+    /// Get text input at a specific slot
+    fn get_input_text(&self, graph: &NodeGraph, node_id: Uuid, slot: usize) -> Option<String> {
+        match self.input_value(graph, node_id, slot) {
+            Some(NodeOutput::Text(text)) => Some(text.clone()),
+            _ => None,
+        }
+    }
+    
+    /// Topological sort of the graph
+    fn topological_sort(&self, graph: &NodeGraph) -> Result<Vec<Uuid>, String> {
+        let mut result = Vec::new();
+        let mut visited = HashMap::new();
+        
+        let node_ids: Vec<Uuid> = graph.nodes_iter().map(|(id, _)| *id).collect();
+        
+        for node_id in node_ids {
+            self.visit_node(graph, node_id, &mut visited, &mut result)?;
+        }
+        
+        Ok(result)
+    }
+    
+    fn visit_node(
+        &self,
+        graph: &NodeGraph,
+        node_id: Uuid,
+        visited: &mut HashMap<Uuid, bool>,
+        result: &mut Vec<Uuid>,
+    ) -> Result<(), String> {
+        if let Some(&in_progress) = visited.get(&node_id) {
+            if in_progress {
+                return Err("Cycle detected in graph".to_string());
+            }
+            return Ok(());
+        }
+        
+        visited.insert(node_id, true);
+        
+        // Visit all nodes that this node depends on
+        for conn in graph.connections_iter() {
+            if conn.to_node == node_id {
+                self.visit_node(graph, conn.from_node, visited, result)?;
+            }
+        }
+        
+        visited.insert(node_id, false);
+        result.push(node_id);
+        
+        Ok(())
+    }
+    
+    // === Image Processing Functions ===
+    
+    /// Run a position-independent neighbourhood filter tile by tile. Each tile
+    /// is padded with `halo` pixels of its surroundings so the filter sees the
+    /// same neighbours as on the whole image; only the tile itself is kept.
+    /// Keeps the filter's scratch buffers small on very large images.
+    fn process_tiled(&self, img: &ImageData, halo: u32, filter: impl Fn(&ImageData) -> ImageData) -> ImageData {
+        let tile_size = self.limits.tile_size.max(64);
+        if img.width <= tile_size && img.height <= tile_size {
+            return filter(img);
+        }
+        
+        let mut output = vec![0.0f32; img.pixel_count() * 4];
+        let row_len = (img.width * 4) as usize;
+        
+        for tile_y in (0..img.height).step_by(tile_size as usize) {
+            for tile_x in (0..img.width).step_by(tile_size as usize) {
+                let tile_w = tile_size.min(img.width - tile_x);
+                let tile_h = tile_size.min(img.height - tile_y);
+                
+                // Padded area, clipped to the image so edge handling is unchanged
+                let x0 = tile_x.saturating_sub(halo);
+                let y0 = tile_y.saturating_sub(halo);
+                let x1 = (tile_x + tile_w + halo).min(img.width);
+                let y1 = (tile_y + tile_h + halo).min(img.height);
+                
+                let padded = img.crop(x0, y0, x1 - x0, y1 - y0);
+                let filtered = filter(&padded).to_f32();
+                
+                // Copy the tile's own rows back
+                let src_row_len = ((x1 - x0) * 4) as usize;
+                let copy_len = (tile_w * 4) as usize;
+                for row in 0..tile_h {
+                    let src = (row + tile_y - y0) as usize * src_row_len + ((tile_x - x0) * 4) as usize;
+                    let dst = (row + tile_y) as usize * row_len + (tile_x * 4) as usize;
+                    output[dst..dst + copy_len].copy_from_slice(&filtered[src..src + copy_len]);
+                }
+            }
+        }
+        
+        ImageData::from_f32(output, img.width, img.height)
+    }
+    
+    /// Run a blur tile by tile, except when it wraps around the image edges:
+    /// wrapping needs the opposite side of the whole image
+    fn blur_tiled(&self, img: &ImageData, halo: u32, edge: EdgeMode, filter: impl Fn(&ImageData) -> ImageData) -> ImageData {
+        if edge == EdgeMode::Wrap {
+            filter(img)
+        } else {
+            self.process_tiled(img, halo, filter)
+        }
+    }
+    
+    /// Run a filter on the image converted to `space`, converting the result
+    /// back to the working space
+    fn in_space(&self, img: &ImageData, space: ColorSpace, filter: impl Fn(&ImageData) -> ImageData) -> ImageData {
+        let working = self.working_space.color_space();
+        let result = filter(&color::convert(img, space)).with_color_space(space);
+        color::convert(&result, working)
+    }
+}
+
+/// PSNR over RGB and SSIM over luma for two same-sized images. Both are
+/// measured on display values: the images are converted to the encoded form of
+/// the first one's colour space and clamped to 0-1, as they would be exported.
+pub fn compare_metrics(a: &ImageData, b: &ImageData) -> CompareMetrics {
+    let space = a.color_space.encoded();
+    let display = |img: &ImageData| -> Vec<f32> {
+        color::convert(img, space).to_f32().into_iter().map(|v| v.clamp(0.0, 1.0)).collect()
+    };
+    let (pixels_a, pixels_b) = (display(a), display(b));
+    
+    // PSNR
+    // Measured on the 0-255 scale so values match common tools
+    let mut squared_error = 0.0f64;
+    for (pa, pb) in pixels_a.chunks_exact(4).zip(pixels_b.chunks_exact(4)) {
+        for c in 0..3 {
+            let d = (pa[c] as f64 - pb[c] as f64) * 255.0;
+            squared_error += d * d;
+        }
+    }
+    let mse = squared_error / (a.pixel_count().max(1) * 3) as f64;
+    let psnr = if mse == 0.0 {
+        f32::INFINITY
+    } else {
+        (10.0 * (255.0 * 255.0 / mse).log10()) as f32
+    };
+    
+    // SSIM, averaged over 8×8 windows
+    const WINDOW: u32 = 8;
+    const C1: f64 = (0.01 * 255.0) * (0.01 * 255.0);
+    const C2: f64 = (0.03 * 255.0) * (0.03 * 255.0);
+    
+    let luma = |pixels: &[f32], x: u32, y: u32| -> f64 {
+        let i = ((y * a.width + x) * 4) as usize;
+        (0.299 * pixels[i] as f64 + 0.587 * pixels[i + 1] as f64 + 0.114 * pixels[i + 2] as f64) * 255.0
+    };
+    
+    let mut ssim_sum = 0.0f64;
+    let mut windows = 0u32;
+    let mut wy = 0;
+    while wy < a.height {
+        let mut wx = 0;
+        while wx < a.width {
+            let x_end = (wx + WINDOW).min(a.width);
+            let y_end = (wy + WINDOW).min(a.height);
+            let n = ((x_end - wx) * (y_end - wy)) as f64;
+            
+            let (mut sum_a, mut sum_b) = (0.0, 0.0);
+            let (mut sum_aa, mut sum_bb, mut sum_ab) = (0.0, 0.0, 0.0);
+            for y in wy..y_end {
+                for x in wx..x_end {
+                    let la = luma(&pixels_a, x, y);
+                    let lb = luma(&pixels_b, x, y);
+                    sum_a += la;
+                    sum_b += lb;
+                    sum_aa += la * la;
+                    sum_bb += lb * lb;
+                    sum_ab += la * lb;
+                }
+            }
+            
+            let mean_a = sum_a / n;
+            let mean_b = sum_b / n;
+            let var_a = sum_aa / n - mean_a * mean_a;
+            let var_b = sum_bb / n - mean_b * mean_b;
+            let covariance = sum_ab / n - mean_a * mean_b;
+            
+            ssim_sum += ((2.0 * mean_a * mean_b + C1) * (2.0 * covariance + C2))
+                / ((mean_a * mean_a + mean_b * mean_b + C1) * (var_a + var_b + C2));
+            windows += 1;
+            wx += WINDOW;
+        }
+        wy += WINDOW;
+    }
+    
+    let ssim = if windows == 0 { 1.0 } else { (ssim_sum / windows as f64) as f32 };
+    
+    CompareMetrics { psnr, ssim }
+}
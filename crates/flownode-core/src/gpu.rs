@@ -0,0 +1,548 @@
+//! Node operations as wgpu compute shaders
+//!
+//! [`GpuContext`] implements [`ProcessingBackend`] with one compute shader
+//! per operation (`shaders/*.wgsl`). Images go up as storage buffers of
+//! `vec4<f32>` pixels and come back the same way, so results match the CPU
+//! backend to within floating point error. Anything too big for the device's
+//! buffers runs on the CPU instead.
+//!
+//! Adapters are requested by blocking on wgpu's futures, so this backend is
+//! for native builds.
+
+use std::collections::HashMap;
+
+use wgpu::util::DeviceExt;
+
+use crate::backend::{Adjustments, ProcessingBackend, SHARPEN_SIGMA};
+use crate::blur;
+use crate::cpu::{self, CpuBackend, GrainNoise};
+use crate::image_data::ImageData;
+use crate::lut::{Lut, LutKind};
+use crate::mask::Mask;
+use crate::nodes::{BlendMode, BlurDirection, ColorWheel, CompareMode, CompositionLayer, Curves, EdgeMode, LutInterpolation};
+
+/// Pixels per workgroup side, as in the shaders
+const WORKGROUP: u32 = 16;
+
+/// Helpers every kernel is compiled with
+const COMMON: &str = include_str!("shaders/common.wgsl");
+
+/// Kernel names and sources
+const KERNELS: [(&str, &str); 14] = [
+    ("adjust", include_str!("shaders/adjust.wgsl")),
+    ("color_wheels", include_str!("shaders/color_wheels.wgsl")),
+    ("curves", include_str!("shaders/curves.wgsl")),
+    ("lut", include_str!("shaders/lut.wgsl")),
+    ("luminance_mix", include_str!("shaders/luminance_mix.wgsl")),
+    ("blur", include_str!("shaders/blur.wgsl")),
+    ("directional_blur", include_str!("shaders/directional_blur.wgsl")),
+    ("glass_blinds", include_str!("shaders/glass_blinds.wgsl")),
+    ("unsharp", include_str!("shaders/unsharp.wgsl")),
+    ("grain", include_str!("shaders/grain.wgsl")),
+    ("vignette", include_str!("shaders/vignette.wgsl")),
+    ("compare", include_str!("shaders/compare.wgsl")),
+    ("composition", include_str!("shaders/composition.wgsl")),
+    ("mask_mix", include_str!("shaders/mask_mix.wgsl")),
+];
+
+/// A GPU device with the kernels compiled for it
+pub struct GpuContext {
+    device: wgpu::Device,
+    queue: wgpu::Queue,
+    info: wgpu::AdapterInfo,
+    /// Largest storage buffer a kernel can bind, in bytes
+    max_buffer: u64,
+    layout: wgpu::BindGroupLayout,
+    pipelines: HashMap<&'static str, wgpu::ComputePipeline>,
+}
+
+/// A kernel's uniform parameters: 32-bit scalars in declaration order
+#[derive(Default)]
+struct Params(Vec<u8>);
+
+impl Params {
+    /// Every kernel's parameters start with the output size
+    fn new(width: u32, height: u32) -> Self {
+        Self::default().u32(width).u32(height)
+    }
+
+    fn u32(mut self, value: u32) -> Self {
+        self.0.extend_from_slice(&value.to_le_bytes());
+        self
+    }
+
+    fn i32(mut self, value: i32) -> Self {
+        self.0.extend_from_slice(&value.to_le_bytes());
+        self
+    }
+
+    fn f32(mut self, value: f32) -> Self {
+        self.0.extend_from_slice(&value.to_le_bytes());
+        self
+    }
+
+    fn flag(self, value: bool) -> Self {
+        self.u32(value as u32)
+    }
+
+    /// Uniform buffers are sized in 16-byte steps
+    fn bytes(&self) -> Vec<u8> {
+        let mut bytes = self.0.clone();
+        bytes.resize(bytes.len().div_ceil(16) * 16, 0);
+        bytes
+    }
+}
+
+/// One kernel run, reading the previous pass's output (or the source image)
+struct Pass<'a> {
+    kernel: &'static str,
+    params: Params,
+    /// Extra input at binding 3: tables, weights or a second image
+    extra: Option<&'a [f32]>,
+}
+
+impl<'a> Pass<'a> {
+    fn new(kernel: &'static str, params: Params) -> Self {
+        Self { kernel, params, extra: None }
+    }
+
+    fn with(mut self, extra: &'a [f32]) -> Self {
+        self.extra = Some(extra);
+        self
+    }
+}
+
+fn to_bytes(values: &[f32]) -> Vec<u8> {
+    values.iter().flat_map(|v| v.to_le_bytes()).collect()
+}
+
+fn edge_code(edge: EdgeMode) -> u32 {
+    match edge {
+        EdgeMode::Clamp => 0,
+        EdgeMode::Mirror => 1,
+        EdgeMode::Wrap => 2,
+    }
+}
+
+fn blend_code(mode: BlendMode) -> u32 {
+    match mode {
+        BlendMode::Normal => 0,
+        BlendMode::Multiply => 1,
+        BlendMode::Screen => 2,
+        BlendMode::Overlay => 3,
+        BlendMode::SoftLight => 4,
+        BlendMode::HardLight => 5,
+        BlendMode::ColorDodge => 6,
+        BlendMode::ColorBurn => 7,
+        BlendMode::Difference => 8,
+        BlendMode::Exclusion => 9,
+    }
+}
+
+impl GpuContext {
+    /// Open the best GPU available
+    pub fn new() -> Result<Self, String> {
+        Self::open(false)
+    }
+
+    /// Open a software adapter (e.g. llvmpipe or WARP), for machines
+    /// without a GPU and for testing
+    pub fn software() -> Result<Self, String> {
+        Self::open(true)
+    }
+
+    fn open(force_fallback_adapter: bool) -> Result<Self, String> {
+        let instance = wgpu::Instance::new(&wgpu::InstanceDescriptor::default());
+        let adapter = pollster::block_on(instance.request_adapter(&wgpu::RequestAdapterOptions {
+            power_preference: wgpu::PowerPreference::HighPerformance,
+            force_fallback_adapter,
+            compatible_surface: None,
+        }))
+        .ok_or("No GPU adapter found")?;
+
+        let info = adapter.get_info();
+        let limits = adapter.limits();
+        let (device, queue) = pollster::block_on(adapter.request_device(
+            &wgpu::DeviceDescriptor {
+                label: Some("flownode"),
+                required_features: wgpu::Features::empty(),
+                required_limits: limits.clone(),
+                memory_hints: wgpu::MemoryHints::Performance,
+            },
+            None,
+        ))
+        .map_err(|e| format!("Failed to open {}: {}", info.name, e))?;
+
+        let storage = |binding, read_only| wgpu::BindGroupLayoutEntry {
+            binding,
+            visibility: wgpu::ShaderStages::COMPUTE,
+            ty: wgpu::BindingType::Buffer {
+                ty: wgpu::BufferBindingType::Storage { read_only },
+                has_dynamic_offset: false,
+                min_binding_size: None,
+            },
+            count: None,
+        };
+        let layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            label: Some("kernel"),
+            entries: &[
+                wgpu::BindGroupLayoutEntry {
+                    binding: 0,
+                    visibility: wgpu::ShaderStages::COMPUTE,
+                    ty: wgpu::BindingType::Buffer {
+                        ty: wgpu::BufferBindingType::Uniform,
+                        has_dynamic_offset: false,
+                        min_binding_size: None,
+                    },
+                    count: None,
+                },
+                storage(1, true),
+                storage(2, false),
+                storage(3, true),
+            ],
+        });
+        let pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            label: Some("kernel"),
+            bind_group_layouts: &[&layout],
+            push_constant_ranges: &[],
+        });
+
+        let pipelines = KERNELS.iter()
+            .map(|&(name, source)| {
+                let module = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+                    label: Some(name),
+                    source: wgpu::ShaderSource::Wgsl(format!("{}\n{}", COMMON, source).into()),
+                });
+                let pipeline = device.create_compute_pipeline(&wgpu::ComputePipelineDescriptor {
+                    label: Some(name),
+                    layout: Some(&pipeline_layout),
+                    module: &module,
+                    entry_point: Some("main"),
+                    compilation_options: Default::default(),
+                    cache: None,
+                });
+                (name, pipeline)
+            })
+            .collect();
+
+        log::info!("GPU backend on {} ({:?})", info.name, info.backend);
+        Ok(Self {
+            device,
+            queue,
+            max_buffer: (limits.max_storage_buffer_binding_size as u64).min(limits.max_buffer_size),
+            info,
+            layout,
+            pipelines,
+        })
+    }
+
+    /// The adapter in use
+    pub fn adapter_info(&self) -> &wgpu::AdapterInfo {
+        &self.info
+    }
+
+    /// Run `passes` one after another, the first reading `src`, and read back
+    /// the last one's `width`×`height` output. None when a buffer is too big
+    /// for the device.
+    fn run(&self, src: &[f32], width: u32, height: u32, passes: &[Pass]) -> Option<Vec<f32>> {
+        let out_bytes = width as u64 * height as u64 * 16;
+        let fits = |bytes: u64| bytes > 0 && bytes <= self.max_buffer;
+        let groups = (width.div_ceil(WORKGROUP), height.div_ceil(WORKGROUP));
+        let max_groups = self.device.limits().max_compute_workgroups_per_dimension;
+        if !fits(out_bytes)
+            || !fits(src.len() as u64 * 4)
+            || passes.iter().any(|p| p.extra.is_some_and(|e| !fits(e.len() as u64 * 4)))
+            || groups.0 > max_groups
+            || groups.1 > max_groups
+        {
+            return None;
+        }
+
+        let storage = wgpu::BufferUsages::STORAGE | wgpu::BufferUsages::COPY_SRC;
+        let upload = |label, data: &[u8], usage| {
+            self.device.create_buffer_init(&wgpu::util::BufferInitDescriptor { label: Some(label), contents: data, usage })
+        };
+        let output = || self.device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("output"),
+            size: out_bytes,
+            usage: storage,
+            mapped_at_creation: false,
+        });
+
+        let source = upload("source", &to_bytes(src), storage);
+        // Binding 3 must be bound even when a kernel doesn't use it
+        let empty = upload("empty", &[0; 16], wgpu::BufferUsages::STORAGE);
+        let mut targets: Vec<wgpu::Buffer> = (0..passes.len().min(2)).map(|_| output()).collect();
+
+        let mut encoder = self.device.create_command_encoder(&Default::default());
+        for (i, pass) in passes.iter().enumerate() {
+            let params = upload("params", &pass.params.bytes(), wgpu::BufferUsages::UNIFORM);
+            let extra = pass.extra.map(|e| upload("extra", &to_bytes(e), wgpu::BufferUsages::STORAGE));
+            let input = if i == 0 { &source } else { &targets[(i + 1) % 2] };
+            let bind_group = self.device.create_bind_group(&wgpu::BindGroupDescriptor {
+                label: Some(pass.kernel),
+                layout: &self.layout,
+                entries: &[
+                    wgpu::BindGroupEntry { binding: 0, resource: params.as_entire_binding() },
+                    wgpu::BindGroupEntry { binding: 1, resource: input.as_entire_binding() },
+                    wgpu::BindGroupEntry { binding: 2, resource: targets[i % 2].as_entire_binding() },
+                    wgpu::BindGroupEntry { binding: 3, resource: extra.as_ref().unwrap_or(&empty).as_entire_binding() },
+                ],
+            });
+
+            let mut compute = encoder.begin_compute_pass(&Default::default());
+            compute.set_pipeline(&self.pipelines[pass.kernel]);
+            compute.set_bind_group(0, &bind_group, &[]);
+            compute.dispatch_workgroups(groups.0, groups.1, 1);
+        }
+
+        let result = targets.swap_remove((passes.len() - 1) % 2);
+        let staging = self.device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("readback"),
+            size: out_bytes,
+            usage: wgpu::BufferUsages::MAP_READ | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+        encoder.copy_buffer_to_buffer(&result, 0, &staging, 0, out_bytes);
+        self.queue.submit([encoder.finish()]);
+
+        let slice = staging.slice(..);
+        let (sender, receiver) = std::sync::mpsc::channel();
+        slice.map_async(wgpu::MapMode::Read, move |result| {
+            let _ = sender.send(result);
+        });
+        self.device.poll(wgpu::Maintain::Wait);
+        if let Err(e) = receiver.recv().ok()? {
+            log::error!("GPU readback failed: {}", e);
+            return None;
+        }
+
+        let pixels = slice.get_mapped_range()
+            .chunks_exact(4)
+            .map(|b| f32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+            .collect();
+        staging.unmap();
+        Some(pixels)
+    }
+
+    /// Run a single kernel over an image, keeping its size
+    fn filter(&self, img: &ImageData, pass: Pass) -> Option<ImageData> {
+        let pixels = self.run(&img.to_f32(), img.width, img.height, &[pass])?;
+        Some(ImageData::from_f32(pixels, img.width, img.height))
+    }
+}
+
+impl ProcessingBackend for GpuContext {
+    fn name(&self) -> String {
+        format!("GPU ({})", self.info.name)
+    }
+
+    fn adjust(&self, img: &ImageData, params: &Adjustments) -> ImageData {
+        let rotate = params.hue_rotation.abs() > 0.01;
+        let mut uniforms = Params::new(img.width, img.height)
+            .f32(params.brightness / 100.0)
+            .f32(1.0 + params.contrast / 100.0)
+            .f32(1.0 + params.saturation / 100.0)
+            .f32((params.exposure / 50.0).exp2())
+            .f32(1.0 / (1.0 + params.gamma / 100.0).max(0.1))
+            .f32(params.temperature / 100.0)
+            .f32(params.tint / 100.0)
+            .f32(params.vibrance / 100.0)
+            .f32(params.color_boost / 100.0)
+            .f32(params.highlights / 200.0)
+            .f32(params.shadows / 200.0)
+            .flag(rotate);
+        for m in cpu::hue_rotate_matrix(params.hue_rotation).as_flattened() {
+            uniforms = uniforms.f32(*m);
+        }
+        self.filter(img, Pass::new("adjust", uniforms))
+            .unwrap_or_else(|| CpuBackend.adjust(img, params))
+    }
+
+    fn color_wheels(&self, img: &ImageData, lift: &ColorWheel, gamma: &ColorWheel, gain: &ColorWheel, offset: &ColorWheel) -> ImageData {
+        let mut params = Params::new(img.width, img.height);
+        for wheel in [lift, gamma, gain, offset] {
+            let (r, g, b) = cpu::wheel_to_rgb(wheel);
+            params = params.f32(r).f32(g).f32(b);
+        }
+        self.filter(img, Pass::new("color_wheels", params))
+            .unwrap_or_else(|| CpuBackend.color_wheels(img, lift, gamma, gain, offset))
+    }
+
+    fn curves(&self, img: &ImageData, curves: &Curves) -> ImageData {
+        let tables: Vec<f32> = [&curves.master, &curves.red, &curves.green, &curves.blue]
+            .iter()
+            .flat_map(|curve| curve.to_lut())
+            .collect();
+        let size = tables.len() as u32 / 4;
+        self.filter(img, Pass::new("curves", Params::new(img.width, img.height).u32(size)).with(&tables))
+            .unwrap_or_else(|| CpuBackend.curves(img, curves))
+    }
+
+    fn lut(&self, img: &ImageData, lut: &Lut, interpolation: LutInterpolation, intensity: f32) -> ImageData {
+        let table: Vec<f32> = lut.table.iter().flatten().copied().collect();
+        let mut params = Params::new(img.width, img.height)
+            .flag(lut.kind == LutKind::ThreeD)
+            .flag(interpolation == LutInterpolation::Tetrahedral)
+            .u32(lut.size as u32)
+            .f32(intensity.clamp(0.0, 1.0));
+        for v in lut.domain_min.into_iter().chain(lut.domain_max) {
+            params = params.f32(v);
+        }
+        self.filter(img, Pass::new("lut", params).with(&table))
+            .unwrap_or_else(|| CpuBackend.lut(img, lut, interpolation, intensity))
+    }
+
+    fn luminance_mix(&self, original: &ImageData, adjusted: &ImageData, mix: f32) -> ImageData {
+        let source = original.to_f32();
+        let params = Params::new(adjusted.width, adjusted.height).f32((mix / 100.0).clamp(0.0, 1.0));
+        self.filter(adjusted, Pass::new("luminance_mix", params).with(&source))
+            .unwrap_or_else(|| CpuBackend.luminance_mix(original, adjusted, mix))
+    }
+
+    fn gaussian_blur(&self, img: &ImageData, sigma: f32, edge: EdgeMode) -> ImageData {
+        if sigma <= 0.0 {
+            return img.clone();
+        }
+        let weights = blur::gaussian_kernel(sigma);
+        let reach = (weights.len() / 2) as i32;
+        let axis = |vertical: bool| {
+            Params::new(img.width, img.height)
+                .flag(vertical)
+                .u32(edge_code(edge))
+                .i32(reach)
+                .flag(!vertical)
+                .flag(vertical)
+        };
+        let passes = [
+            Pass::new("blur", axis(false)).with(&weights),
+            Pass::new("blur", axis(true)).with(&weights),
+        ];
+        self.run(&img.to_f32(), img.width, img.height, &passes)
+            .map(|pixels| ImageData::from_f32(pixels, img.width, img.height).with_color_space(img.color_space))
+            .unwrap_or_else(|| CpuBackend.gaussian_blur(img, sigma, edge))
+    }
+
+    fn directional_blur(&self, img: &ImageData, amount: f32, angle: f32, edge: EdgeMode) -> ImageData {
+        let (dy, dx) = angle.to_radians().sin_cos();
+        let params = Params::new(img.width, img.height)
+            .u32(edge_code(edge))
+            .i32((amount * 20.0).max(1.0) as i32)
+            .f32(dx)
+            .f32(dy);
+        self.filter(img, Pass::new("directional_blur", params))
+            .unwrap_or_else(|| CpuBackend.directional_blur(img, amount, angle, edge))
+    }
+
+    fn progressive_blur(&self, img: &ImageData, amount: f32, direction: &BlurDirection, falloff: f32, edge: EdgeMode) -> ImageData {
+        cpu::progressive_blur_with(img, amount, direction, falloff, |img, sigma| self.gaussian_blur(img, sigma, edge))
+    }
+
+    fn glass_blinds(&self, img: &ImageData, intensity: f32, frequency: f32, angle: f32, phase: f32) -> ImageData {
+        let (sin_a, cos_a) = angle.to_radians().sin_cos();
+        let params = Params::new(img.width, img.height)
+            .f32(intensity)
+            .f32(frequency)
+            .f32(phase)
+            .f32(cos_a)
+            .f32(sin_a);
+        self.filter(img, Pass::new("glass_blinds", params))
+            .unwrap_or_else(|| CpuBackend.glass_blinds(img, intensity, frequency, angle, phase))
+    }
+
+    fn sharpen(&self, img: &ImageData, amount: f32, edge: EdgeMode) -> ImageData {
+        let blurred = self.gaussian_blur(img, SHARPEN_SIGMA, edge).to_f32();
+        let params = Params::new(img.width, img.height).f32(amount);
+        self.filter(img, Pass::new("unsharp", params).with(&blurred))
+            .unwrap_or_else(|| CpuBackend.sharpen(img, amount, edge))
+    }
+
+    fn grain(&self, img: &ImageData, amount: f32, size: f32, monochrome: bool, seed: u32) -> ImageData {
+        let noise = GrainNoise::new(img.width, img.height, amount, size, monochrome, seed);
+        let blocks: Vec<f32> = noise.blocks.iter().flatten().copied().collect();
+        let params = Params::new(img.width, img.height).u32(noise.block_size).u32(noise.blocks_x);
+        self.filter(img, Pass::new("grain", params).with(&blocks))
+            .unwrap_or_else(|| CpuBackend.grain(img, amount, size, monochrome, seed))
+    }
+
+    fn vignette(&self, img: &ImageData, intensity: f32, roundness: f32, smoothness: f32) -> ImageData {
+        let width = img.width as f32;
+        let height = img.height as f32;
+        let (cx, cy) = (width / 2.0, height / 2.0);
+        let params = Params::new(img.width, img.height)
+            .f32(intensity)
+            .f32(smoothness)
+            .f32(cx)
+            .f32(cy)
+            .f32((cx * cx + cy * cy).sqrt())
+            .f32(1.0 + (1.0 - roundness) * (width / height - 1.0).abs());
+        self.filter(img, Pass::new("vignette", params))
+            .unwrap_or_else(|| CpuBackend.vignette(img, intensity, roundness, smoothness))
+    }
+
+    fn composition(&self, inputs: &[(usize, ImageData)], layers: &[CompositionLayer]) -> ImageData {
+        let width = inputs[0].1.width;
+        let height = inputs[0].1.height;
+        let default_layer = CompositionLayer::default();
+
+        let images: Vec<Vec<f32>> = inputs.iter().map(|(_, img)| img.to_f32()).collect();
+        let passes: Vec<Pass> = inputs.iter().zip(&images)
+            .map(|((slot, img), pixels)| {
+                let layer = layers.get(*slot).unwrap_or(&default_layer);
+                let scale = layer.scale.max(0.01);
+                let (offset_x, offset_y) = layer.position;
+                let x1 = ((offset_x + img.width as f32 * scale).ceil().max(0.0) as u32).min(width);
+                let y1 = ((offset_y + img.height as f32 * scale).ceil().max(0.0) as u32).min(height);
+                let params = Params::new(width, height)
+                    .u32(img.width)
+                    .u32(img.height)
+                    .u32(offset_x.floor().max(0.0) as u32)
+                    .u32(offset_y.floor().max(0.0) as u32)
+                    .u32(x1)
+                    .u32(y1)
+                    .f32(offset_x)
+                    .f32(offset_y)
+                    .f32(scale)
+                    .f32(layer.opacity.clamp(0.0, 1.0))
+                    .u32(blend_code(layer.blend_mode));
+                Pass::new("composition", params).with(pixels)
+            })
+            .collect();
+
+        let canvas = vec![0.0f32; (width * height * 4) as usize];
+        match self.run(&canvas, width, height, &passes) {
+            Some(mut pixels) => {
+                pixels.iter_mut().for_each(|v| *v = v.clamp(0.0, 1.0));
+                ImageData::from_f32(pixels, width, height)
+            }
+            None => CpuBackend.composition(inputs, layers),
+        }
+    }
+
+    fn compare(&self, a: &ImageData, b: &ImageData, mode: CompareMode, split: f32) -> ImageData {
+        let split = split.clamp(0.0, 1.0);
+        let (code, divider) = match mode {
+            CompareMode::SplitVertical => (0, (split * a.width as f32) as u32),
+            CompareMode::SplitHorizontal => (1, (split * a.height as f32) as u32),
+            CompareMode::OnionSkin => (2, 0),
+            CompareMode::Difference => (3, 0),
+            CompareMode::SideBySide => (4, 0),
+        };
+        let out_width = if mode == CompareMode::SideBySide { a.width * 2 } else { a.width };
+        let other = b.to_f32();
+        let pass = Pass::new("compare", Params::new(out_width, a.height).u32(code).u32(divider)).with(&other);
+        self.run(&a.to_f32(), out_width, a.height, &[pass])
+            .map(|pixels| ImageData::from_f32(pixels, out_width, a.height))
+            .unwrap_or_else(|| CpuBackend.compare(a, b, mode, split))
+    }
+
+    fn mask_mix(&self, original: &ImageData, processed: &ImageData, mask: &Mask) -> ImageData {
+        // The original's pixels, then the mask packed four values to a vec4
+        let mut extra = original.to_f32();
+        extra.extend_from_slice(mask.values());
+        extra.resize(extra.len().div_ceil(4) * 4, 0.0);
+        let params = Params::new(processed.width, processed.height);
+        self.filter(processed, Pass::new("mask_mix", params).with(&extra))
+            .unwrap_or_else(|| CpuBackend.mask_mix(original, processed, mask))
+    }
+}
@@ -0,0 +1,341 @@
+//! Node graph data: nodes, the connections between them and the working space
+//!
+//! This is the part of a graph that is saved and executed. View state such as
+//! selection, pan and zoom belongs to whichever front end shows the graph.
+
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+use std::collections::{HashMap, HashSet};
+
+use crate::color::WorkingSpace;
+use crate::lut::{Lut, LutRef, LutStore};
+use crate::nodes::{Node, NodeProperties, NodeType, SlotType, CompareMetrics, Vec2};
+
+/// How far right of its source a migrated Output node is placed
+const OUTPUT_OFFSET: f32 = 260.0;
+
+/// A connection between two nodes
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Connection {
+    pub from_node: Uuid,
+    pub from_slot: usize,
+    pub to_node: Uuid,
+    pub to_slot: usize,
+}
+
+/// A set of nodes and the connections between them (for copy/paste)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Subgraph {
+    pub nodes: Vec<Node>,
+    pub connections: Vec<Connection>,
+    /// Tables used by the LUT nodes in `nodes`
+    #[serde(default)]
+    pub luts: LutStore,
+}
+
+/// The entire node graph
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct NodeGraph {
+    pub nodes: HashMap<Uuid, Node>,
+    pub connections: Vec<Connection>,
+    
+    /// Colour space the graph is processed in
+    #[serde(default = "legacy_working_space")]
+    pub working_space: WorkingSpace,
+    
+    /// Tables loaded into LUT nodes, which refer to them by hash
+    #[serde(default, skip_serializing_if = "LutStore::is_empty")]
+    pub luts: LutStore,
+}
+
+/// Projects saved before working spaces existed processed sRGB-encoded values
+fn legacy_working_space() -> WorkingSpace {
+    WorkingSpace::Srgb
+}
+
+/// Projects saved before the LUT store kept each table inside its node.
+/// Move those tables into a store, leaving references behind.
+fn migrate_inline_luts(value: &mut serde_json::Value) -> Result<LutStore, serde_json::Error> {
+    let mut store = LutStore::default();
+    let Some(nodes) = value.get_mut("nodes").and_then(|n| n.as_object_mut()) else {
+        return Ok(store);
+    };
+    for node in nodes.values_mut() {
+        let Some(lut) = node.pointer_mut("/properties/Lut/lut") else {
+            continue;
+        };
+        if lut.get("table").is_some() {
+            let table: Lut = serde_json::from_value(lut.take())?;
+            let reference: LutRef = store.insert(table);
+            *lut = serde_json::to_value(reference)?;
+        }
+    }
+    Ok(store)
+}
+
+impl NodeGraph {
+    /// An empty graph. New projects work in linear sRGB.
+    pub fn new() -> Self {
+        Self {
+            working_space: WorkingSpace::LinearSrgb,
+            ..Self::default()
+        }
+    }
+    
+    /// Create graph from parts (for loading)
+    pub fn from_parts(nodes: HashMap<Uuid, Node>, connections: Vec<Connection>) -> Self {
+        Self {
+            nodes,
+            connections,
+            working_space: WorkingSpace::default(),
+            luts: LutStore::default(),
+        }
+    }
+    
+    pub fn node_count(&self) -> usize {
+        self.nodes.len()
+    }
+    
+    /// Insert a node directly (for cloud loading)
+    pub fn insert_node(&mut self, node: Node) {
+        let node_id = node.id;
+        self.nodes.insert(node_id, node);
+    }
+    
+    /// Delete a node and all its connections
+    pub fn delete_node(&mut self, node_id: Uuid) {
+        // Remove all connections involving this node
+        self.connections.retain(|c| c.from_node != node_id && c.to_node != node_id);
+        
+        // Remove the node
+        self.nodes.remove(&node_id);
+        
+        log::info!("Deleted node {:?}", node_id);
+    }
+    
+    /// Check whether an output can be wired to an input: both slots exist,
+    /// the types are compatible and the wire wouldn't close a cycle
+    pub fn can_connect(&self, from_node: Uuid, from_slot: usize, to_node: Uuid, to_slot: usize) -> Result<(), String> {
+        let (Some(from), Some(to)) = (self.nodes.get(&from_node), self.nodes.get(&to_node)) else {
+            return Err("Unknown node".to_string());
+        };
+        if from_node == to_node {
+            return Err("A node can't be connected to itself".to_string());
+        }
+        
+        let output = from.node_type.outputs().get(from_slot).map(|s| s.slot_type)
+            .ok_or_else(|| format!("{} has no output {}", from.node_type.name(), from_slot))?;
+        let input = to.node_type.inputs().get(to_slot).map(|s| s.slot_type)
+            .ok_or_else(|| format!("{} has no input {}", to.node_type.name(), to_slot))?;
+        if !input.accepts(output, from.node_type.batch_item()) {
+            return Err(format!(
+                "{} outputs {}, but this {} input takes {}",
+                from.node_type.name(), output.name(), to.node_type.name(), input.name()
+            ));
+        }
+        
+        if self.is_downstream(to_node, from_node) {
+            return Err("This connection would create a cycle".to_string());
+        }
+        
+        Ok(())
+    }
+    
+    /// Whether `target` can be reached from `start` by following connections
+    fn is_downstream(&self, start: Uuid, target: Uuid) -> bool {
+        let mut stack = vec![start];
+        let mut seen = HashSet::new();
+        while let Some(id) = stack.pop() {
+            if id == target {
+                return true;
+            }
+            if seen.insert(id) {
+                stack.extend(self.connections.iter().filter(|c| c.from_node == id).map(|c| c.to_node));
+            }
+        }
+        false
+    }
+    
+    /// Add a connection between nodes. An input takes a single wire, so any
+    /// existing connection to it is replaced.
+    pub fn add_connection(&mut self, from_node: Uuid, from_slot: usize, to_node: Uuid, to_slot: usize) -> Result<(), String> {
+        self.can_connect(from_node, from_slot, to_node, to_slot)?;
+        
+        self.connections.retain(|c| !(c.to_node == to_node && c.to_slot == to_slot));
+        self.connections.push(Connection {
+            from_node,
+            from_slot,
+            to_node,
+            to_slot,
+        });
+        Ok(())
+    }
+    
+    /// Delete a specific connection
+    pub fn delete_connection(&mut self, from_node: Uuid, from_slot: usize, to_node: Uuid, to_slot: usize) {
+        self.connections.retain(|c| {
+            !(c.from_node == from_node && c.from_slot == from_slot && 
+              c.to_node == to_node && c.to_slot == to_slot)
+        });
+    }
+    
+    /// Serialize the graph to JSON
+    pub fn to_json(&self) -> Result<String, serde_json::Error> {
+        serde_json::to_string_pretty(self)
+    }
+    
+    /// Deserialize the graph from JSON. Graphs saved before Output nodes
+    /// existed get one on each terminal image node, and LUT tables saved
+    /// inside their nodes move to the store.
+    pub fn from_json(json: &str) -> Result<Self, serde_json::Error> {
+        let mut value: serde_json::Value = serde_json::from_str(json)?;
+        let luts = migrate_inline_luts(&mut value)?;
+        let mut graph: Self = serde_json::from_value(value)?;
+        graph.luts.extend(&luts);
+        graph.add_missing_outputs();
+        Ok(graph)
+    }
+    
+    /// If the graph has no Output node, connect a new one to every node with
+    /// an image output that feeds nothing, so graphs from before Output nodes
+    /// (and React Flow workflows, which have none) still produce results.
+    /// Returns the number of Output nodes added.
+    pub fn add_missing_outputs(&mut self) -> usize {
+        if self.nodes.values().any(|n| n.node_type == NodeType::Output) {
+            return 0;
+        }
+        
+        let mut terminals: Vec<(Uuid, usize, Vec2, NodeType)> = self.nodes.values()
+            .filter(|node| !self.connections.iter().any(|c| c.from_node == node.id))
+            .filter_map(|node| {
+                let slot = node.node_type.outputs().iter().position(|s| s.slot_type == SlotType::Content)?;
+                Some((node.id, slot, node.position, node.node_type))
+            })
+            .collect();
+        // Stable names whatever the map order
+        terminals.sort_by(|a, b| a.2.y.total_cmp(&b.2.y).then(a.2.x.total_cmp(&b.2.x)).then(a.0.cmp(&b.0)));
+        
+        let mut names: HashMap<String, usize> = HashMap::new();
+        for (source, slot, position, node_type) in &terminals {
+            let base = node_type.name().to_lowercase().replace(' ', "-");
+            let count = names.entry(base.clone()).or_insert(0);
+            *count += 1;
+            let name = if *count == 1 { base } else { format!("{}-{}", base, count) };
+            
+            let mut output = Node::new(NodeType::Output, *position + Vec2::new(OUTPUT_OFFSET, 0.0));
+            if let NodeProperties::Output { name: output_name, .. } = &mut output.properties {
+                *output_name = name;
+            }
+            let output_id = output.id;
+            self.insert_node(output);
+            if let Err(e) = self.add_connection(*source, *slot, output_id, 0) {
+                log::warn!("Couldn't connect an Output node: {}", e);
+            }
+        }
+        terminals.len()
+    }
+    
+    /// Hash of everything that affects execution: node types, properties, loaded
+    /// images and connections. Node positions are ignored.
+    pub fn fingerprint(&self) -> u64 {
+        use std::collections::hash_map::DefaultHasher;
+        use std::hash::{Hash, Hasher};
+        
+        let mut hasher = DefaultHasher::new();
+        
+        let mut node_ids: Vec<&Uuid> = self.nodes.keys().collect();
+        node_ids.sort_unstable();
+        for id in node_ids {
+            let node = &self.nodes[id];
+            id.hash(&mut hasher);
+            node.node_type.name().hash(&mut hasher);
+            serde_json::to_string(&node.properties).unwrap_or_default().hash(&mut hasher);
+            match &node.properties {
+                NodeProperties::Image { texture_id, .. } => texture_id.hash(&mut hasher),
+                NodeProperties::Bucket { image_ids, .. } => image_ids.hash(&mut hasher),
+                _ => {}
+            }
+        }
+        
+        let mut connections: Vec<(Uuid, usize, Uuid, usize)> = self.connections.iter()
+            .map(|c| (c.from_node, c.from_slot, c.to_node, c.to_slot))
+            .collect();
+        connections.sort_unstable();
+        connections.hash(&mut hasher);
+        self.working_space.hash(&mut hasher);
+        
+        hasher.finish()
+    }
+    
+    /// Check if graph has unsaved changes (always true for now, could track dirty state)
+    pub fn is_dirty(&self) -> bool {
+        !self.nodes.is_empty()
+    }
+    
+    /// Get iterator over nodes (for compatibility layer)
+    pub fn nodes_iter(&self) -> impl Iterator<Item = (&Uuid, &Node)> {
+        self.nodes.iter()
+    }
+    
+    /// Get iterator over connections (for compatibility layer)
+    pub fn connections_iter(&self) -> impl Iterator<Item = &Connection> {
+        self.connections.iter()
+    }
+    
+    /// Set the image ID for an Image node, or add it to a Bucket
+    /// Returns true if the node was an Image or Bucket node and was updated
+    pub fn set_node_image(&mut self, node_id: Uuid, image_id: u64) -> bool {
+        match self.nodes.get_mut(&node_id).map(|node| &mut node.properties) {
+            Some(NodeProperties::Image { texture_id, .. }) => {
+                *texture_id = Some(image_id);
+                true
+            }
+            Some(NodeProperties::Bucket { image_ids, .. }) => {
+                image_ids.push(image_id);
+                true
+            }
+            _ => false,
+        }
+    }
+    
+    /// Load a table into a LUT node
+    /// Returns true if the node was a LUT node and was updated
+    pub fn set_node_lut(&mut self, node_id: Uuid, name: &str, value: Lut) -> bool {
+        if let Some(node) = self.nodes.get_mut(&node_id) {
+            if let NodeProperties::Lut { file_name, lut, .. } = &mut node.properties {
+                *file_name = name.to_string();
+                *lut = Some(self.luts.insert(value));
+                return true;
+            }
+        }
+        false
+    }
+    
+    /// The table a LUT node refers to, if it has one loaded
+    pub fn node_lut(&self, node_id: Uuid) -> Option<&Lut> {
+        match &self.nodes.get(&node_id)?.properties {
+            NodeProperties::Lut { lut: Some(r), .. } => self.luts.get(r.hash).map(|lut| lut.as_ref()),
+            _ => None,
+        }
+    }
+    
+    /// Store the metrics measured by a Compare node's last run
+    pub fn set_compare_metrics(&mut self, node_id: Uuid, value: Option<CompareMetrics>) {
+        if let Some(node) = self.nodes.get_mut(&node_id) {
+            if let NodeProperties::Compare { metrics, .. } = &mut node.properties {
+                *metrics = value;
+            }
+        }
+    }
+    
+    /// Get the image ID for a node (if it has one)
+    pub fn get_node_image(&self, node_id: Uuid) -> Option<u64> {
+        self.nodes.get(&node_id).and_then(|node| {
+            if let NodeProperties::Image { texture_id, .. } = &node.properties {
+                *texture_id
+            } else {
+                None
+            }
+        })
+    }
+}
@@ -0,0 +1,452 @@
+//! Image data handling for the node editor
+//!
+//! Images keep the bit depth they were loaded with. Processing happens in
+//! 32-bit float; 8-bit copies are only made for display textures and 8-bit exports.
+//! Each image is tagged with the colour space its values are encoded in.
+
+use std::sync::Arc;
+
+use crate::color::{self, ColorSpace};
+use crate::nodes::{ExportDepth, ExportFormat};
+
+/// Storage format of an image's pixels (always RGBA, straight alpha)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PixelFormat {
+    Rgba8,
+    Rgba16,
+    Rgba32F,
+}
+
+impl PixelFormat {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Rgba8 => "8-bit",
+            Self::Rgba16 => "16-bit",
+            Self::Rgba32F => "32-bit float",
+        }
+    }
+}
+
+/// RGBA pixel buffer in one of the supported formats
+#[derive(Debug, Clone)]
+pub enum PixelBuffer {
+    Rgba8(Vec<u8>),
+    Rgba16(Vec<u16>),
+    /// Nominally 0-1. Values outside it survive between nodes until Output.
+    Rgba32F(Vec<f32>),
+}
+
+/// Raw image data that can be shared between nodes
+#[derive(Debug, Clone)]
+pub struct ImageData {
+    /// Raw RGBA pixel data
+    pub pixels: Arc<PixelBuffer>,
+    /// Width in pixels
+    pub width: u32,
+    /// Height in pixels
+    pub height: u32,
+    /// Colour space of the pixel values
+    pub color_space: ColorSpace,
+}
+
+impl ImageData {
+    /// Create new image data from raw RGBA pixels
+    pub fn new(pixels: Vec<u8>, width: u32, height: u32) -> Self {
+        debug_assert_eq!(pixels.len(), (width * height * 4) as usize);
+        Self::from_buffer(PixelBuffer::Rgba8(pixels), width, height)
+    }
+    
+    /// Create new image data from 16-bit RGBA pixels
+    pub fn new_rgba16(pixels: Vec<u16>, width: u32, height: u32) -> Self {
+        debug_assert_eq!(pixels.len(), (width * height * 4) as usize);
+        Self::from_buffer(PixelBuffer::Rgba16(pixels), width, height)
+    }
+    
+    /// Create new image data from float RGBA pixels (0-1)
+    pub fn from_f32(pixels: Vec<f32>, width: u32, height: u32) -> Self {
+        debug_assert_eq!(pixels.len(), (width * height * 4) as usize);
+        Self::from_buffer(PixelBuffer::Rgba32F(pixels), width, height)
+    }
+    
+    fn from_buffer(pixels: PixelBuffer, width: u32, height: u32) -> Self {
+        Self {
+            pixels: Arc::new(pixels),
+            width,
+            height,
+            color_space: ColorSpace::Srgb,
+        }
+    }
+    
+    /// Tag the pixels with a colour space (no conversion)
+    pub fn with_color_space(mut self, color_space: ColorSpace) -> Self {
+        self.color_space = color_space;
+        self
+    }
+    
+    /// Storage format of the pixels
+    pub fn format(&self) -> PixelFormat {
+        match self.pixels.as_ref() {
+            PixelBuffer::Rgba8(_) => PixelFormat::Rgba8,
+            PixelBuffer::Rgba16(_) => PixelFormat::Rgba16,
+            PixelBuffer::Rgba32F(_) => PixelFormat::Rgba32F,
+        }
+    }
+    
+    /// All channels as floats (0-1)
+    pub fn to_f32(&self) -> Vec<f32> {
+        match self.pixels.as_ref() {
+            PixelBuffer::Rgba8(p) => p.iter().map(|&v| v as f32 / 255.0).collect(),
+            PixelBuffer::Rgba16(p) => p.iter().map(|&v| v as f32 / 65535.0).collect(),
+            PixelBuffer::Rgba32F(p) => p.clone(),
+        }
+    }
+    
+    /// Float pixels clamped to 0-1. Integer buffers are already in range.
+    pub fn clamped(&self) -> ImageData {
+        match self.pixels.as_ref() {
+            PixelBuffer::Rgba32F(p) => {
+                let pixels = p.iter().map(|v| v.clamp(0.0, 1.0)).collect();
+                ImageData::from_f32(pixels, self.width, self.height).with_color_space(self.color_space)
+            }
+            _ => self.clone(),
+        }
+    }
+    
+    /// All channels quantised to 8 bits
+    pub fn to_rgba8(&self) -> Vec<u8> {
+        match self.pixels.as_ref() {
+            PixelBuffer::Rgba8(p) => p.clone(),
+            PixelBuffer::Rgba16(p) => p.iter().map(|&v| ((v as u32 + 128) / 257) as u8).collect(),
+            PixelBuffer::Rgba32F(p) => p.iter().map(|&v| (v.clamp(0.0, 1.0) * 255.0).round() as u8).collect(),
+        }
+    }
+    
+    /// All channels at 16 bits
+    pub fn to_rgba16(&self) -> Vec<u16> {
+        match self.pixels.as_ref() {
+            PixelBuffer::Rgba8(p) => p.iter().map(|&v| v as u16 * 257).collect(),
+            PixelBuffer::Rgba16(p) => p.clone(),
+            PixelBuffer::Rgba32F(p) => p.iter().map(|&v| (v.clamp(0.0, 1.0) * 65535.0).round() as u16).collect(),
+        }
+    }
+    
+    /// Create a solid color image
+    pub fn solid(width: u32, height: u32, color: [u8; 4]) -> Self {
+        let pixels: Vec<u8> = (0..width * height)
+            .flat_map(|_| color)
+            .collect();
+        Self::new(pixels, width, height)
+    }
+    
+    /// Create a checkerboard pattern (for transparency visualization)
+    pub fn checkerboard(width: u32, height: u32, size: u32) -> Self {
+        let pixels: Vec<u8> = (0..height)
+            .flat_map(|y| {
+                (0..width).flat_map(move |x| {
+                    let is_light = ((x / size) + (y / size)) % 2 == 0;
+                    if is_light {
+                        [200u8, 200, 200, 255]
+                    } else {
+                        [150u8, 150, 150, 255]
+                    }
+                })
+            })
+            .collect();
+        Self::new(pixels, width, height)
+    }
+    
+    /// Get pixel at (x, y) as floats (0-1)
+    pub fn get_pixel(&self, x: u32, y: u32) -> [f32; 4] {
+        let idx = ((y * self.width + x) * 4) as usize;
+        let mut out = [0.0f32; 4];
+        for (c, v) in out.iter_mut().enumerate() {
+            *v = match self.pixels.as_ref() {
+                PixelBuffer::Rgba8(p) => p[idx + c] as f32 / 255.0,
+                PixelBuffer::Rgba16(p) => p[idx + c] as f32 / 65535.0,
+                PixelBuffer::Rgba32F(p) => p[idx + c],
+            };
+        }
+        out
+    }
+    
+    /// Total number of pixels
+    pub fn pixel_count(&self) -> usize {
+        (self.width * self.height) as usize
+    }
+    
+    /// Size in bytes
+    pub fn byte_size(&self) -> usize {
+        match self.pixels.as_ref() {
+            PixelBuffer::Rgba8(p) => p.len(),
+            PixelBuffer::Rgba16(p) => p.len() * 2,
+            PixelBuffer::Rgba32F(p) => p.len() * 4,
+        }
+    }
+    
+    /// Copy out a rectangle (must lie inside the image), keeping the format
+    pub fn crop(&self, x: u32, y: u32, width: u32, height: u32) -> ImageData {
+        debug_assert!(x + width <= self.width && y + height <= self.height);
+        
+        fn rows<T: Copy>(src: &[T], src_width: u32, x: u32, y: u32, width: u32, height: u32) -> Vec<T> {
+            let row_len = (width * 4) as usize;
+            let mut out = Vec::with_capacity(row_len * height as usize);
+            for row in y..y + height {
+                let start = ((row * src_width + x) * 4) as usize;
+                out.extend_from_slice(&src[start..start + row_len]);
+            }
+            out
+        }
+        
+        let pixels = match self.pixels.as_ref() {
+            PixelBuffer::Rgba8(p) => PixelBuffer::Rgba8(rows(p, self.width, x, y, width, height)),
+            PixelBuffer::Rgba16(p) => PixelBuffer::Rgba16(rows(p, self.width, x, y, width, height)),
+            PixelBuffer::Rgba32F(p) => PixelBuffer::Rgba32F(rows(p, self.width, x, y, width, height)),
+        };
+        Self::from_buffer(pixels, width, height).with_color_space(self.color_space)
+    }
+}
+
+/// Size and memory limits for loading and processing images
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ImageLimits {
+    /// Largest width or height accepted when decoding
+    pub max_dimension: u32,
+    /// Largest image accepted when decoding, in megapixels
+    pub max_megapixels: f32,
+    /// Longest side of the textures used to display images on screen
+    pub display_max_dimension: u32,
+    /// Neighbourhood filters run in tiles of this size on larger images
+    pub tile_size: u32,
+}
+
+impl Default for ImageLimits {
+    fn default() -> Self {
+        // The browser gets far less memory than a native process
+        if cfg!(target_arch = "wasm32") {
+            Self {
+                max_dimension: 16384,
+                max_megapixels: 64.0,
+                display_max_dimension: 2048,
+                tile_size: 1024,
+            }
+        } else {
+            Self {
+                max_dimension: 32768,
+                max_megapixels: 400.0,
+                display_max_dimension: 4096,
+                tile_size: 2048,
+            }
+        }
+    }
+}
+
+/// Decode image from bytes (PNG, JPEG, etc.) at full resolution.
+/// Images beyond `limits` are rejected rather than silently resized.
+pub fn decode_image(bytes: &[u8], limits: &ImageLimits) -> Result<ImageData, String> {
+    use std::io::Cursor;
+    
+    let reader = || image::ImageReader::new(Cursor::new(bytes))
+        .with_guessed_format()
+        .map_err(|e| format!("Failed to decode image: {}", e));
+    
+    let (width, height) = reader()?.into_dimensions()
+        .map_err(|e| format!("Failed to decode image: {}", e))?;
+    let megapixels = width as f32 * height as f32 / 1_000_000.0;
+    if width > limits.max_dimension || height > limits.max_dimension || megapixels > limits.max_megapixels {
+        return Err(format!(
+            "Image is {}×{} ({:.0} MP), limit is {} px per side and {:.0} MP",
+            width, height, megapixels, limits.max_dimension, limits.max_megapixels
+        ));
+    }
+    
+    let mut decode_limits = image::Limits::default();
+    decode_limits.max_image_width = Some(limits.max_dimension);
+    decode_limits.max_image_height = Some(limits.max_dimension);
+    decode_limits.max_alloc = Some((limits.max_megapixels as f64 * 1_000_000.0 * 8.0) as u64);
+    
+    let mut reader = reader()?;
+    reader.limits(decode_limits);
+    
+    use image::{DynamicImage, ImageDecoder};
+    let mut decoder = reader.into_decoder()
+        .map_err(|e| format!("Failed to decode image: {}", e))?;
+    
+    // Untagged images are assumed to be sRGB
+    let color_space = match decoder.icc_profile().ok().flatten() {
+        Some(profile) => color::detect_icc(&profile).unwrap_or_else(|| {
+            log::warn!("Unrecognised ICC profile, treating image as sRGB");
+            ColorSpace::Srgb
+        }),
+        None => ColorSpace::Srgb,
+    };
+    
+    let img = DynamicImage::from_decoder(decoder)
+        .map_err(|e| format!("Failed to decode image: {}", e))?;
+    
+    // Keep high bit depth sources (16-bit PNG/TIFF, float) at full precision
+    let data = match img {
+        DynamicImage::ImageRgba8(_) | DynamicImage::ImageRgb8(_) |
+        DynamicImage::ImageLuma8(_) | DynamicImage::ImageLumaA8(_) => {
+            let rgba = img.to_rgba8();
+            let (width, height) = rgba.dimensions();
+            ImageData::new(rgba.into_raw(), width, height)
+        }
+        DynamicImage::ImageRgb32F(_) | DynamicImage::ImageRgba32F(_) => {
+            let rgba = img.to_rgba32f();
+            let (width, height) = rgba.dimensions();
+            ImageData::from_f32(rgba.into_raw(), width, height)
+        }
+        _ => {
+            let rgba = img.to_rgba16();
+            let (width, height) = rgba.dimensions();
+            ImageData::new_rgba16(rgba.into_raw(), width, height)
+        }
+    };
+    Ok(data.with_color_space(color_space))
+}
+
+/// Downscale an image so its longest side is at most `max_dimension`.
+/// Images that already fit are returned as-is (sharing the pixel buffer).
+pub fn downscale(data: &ImageData, max_dimension: u32) -> ImageData {
+    if data.width <= max_dimension && data.height <= max_dimension {
+        return data.clone();
+    }
+    
+    let scale = max_dimension as f32 / data.width.max(data.height) as f32;
+    let new_width = ((data.width as f32 * scale) as u32).max(1);
+    let new_height = ((data.height as f32 * scale) as u32).max(1);
+    
+    resize(data, new_width, new_height)
+}
+
+/// Resample an image to exactly `width` × `height`
+pub fn resize(data: &ImageData, width: u32, height: u32) -> ImageData {
+    if data.width == width && data.height == height {
+        return data.clone();
+    }
+    
+    use image::imageops::{resize, FilterType};
+    
+    let resized = match data.pixels.as_ref() {
+        PixelBuffer::Rgba8(p) => {
+            let Some(buffer) = image::RgbaImage::from_raw(data.width, data.height, p.clone()) else {
+                return data.clone();
+            };
+            ImageData::new(resize(&buffer, width, height, FilterType::Triangle).into_raw(), width, height)
+        }
+        PixelBuffer::Rgba16(p) => {
+            let Some(buffer) = image::ImageBuffer::<image::Rgba<u16>, _>::from_raw(data.width, data.height, p.clone()) else {
+                return data.clone();
+            };
+            ImageData::new_rgba16(resize(&buffer, width, height, FilterType::Triangle).into_raw(), width, height)
+        }
+        PixelBuffer::Rgba32F(p) => {
+            let Some(buffer) = image::Rgba32FImage::from_raw(data.width, data.height, p.clone()) else {
+                return data.clone();
+            };
+            ImageData::from_f32(resize(&buffer, width, height, FilterType::Triangle).into_raw(), width, height)
+        }
+    };
+    resized.with_color_space(data.color_space)
+}
+
+/// Encode image to PNG bytes with its colour profile embedded
+pub fn encode_png(data: &ImageData, depth: ExportDepth) -> Result<Vec<u8>, String> {
+    use image::ImageEncoder;
+    use std::io::Cursor;
+    
+    let data = &for_export(data);
+    let mut buffer = Cursor::new(Vec::new());
+    let (bytes, color_type) = encoder_bytes(data, depth);
+    
+    let mut encoder = image::codecs::png::PngEncoder::new(&mut buffer);
+    encoder.set_icc_profile(data.color_space.icc_profile())
+        .map_err(|e| format!("Failed to embed colour profile: {}", e))?;
+    encoder.write_image(
+        &bytes,
+        data.width,
+        data.height,
+        color_type,
+    ).map_err(|e| format!("Failed to encode PNG: {}", e))?;
+    
+    Ok(buffer.into_inner())
+}
+
+/// Linear images are written with their space's usual transfer curve
+fn for_export(data: &ImageData) -> ImageData {
+    color::convert(data, data.color_space.encoded())
+}
+
+/// Pixel bytes in the layout the `image` encoders expect
+/// (native-endian for 16-bit)
+fn encoder_bytes(data: &ImageData, depth: ExportDepth) -> (Vec<u8>, image::ExtendedColorType) {
+    match depth {
+        ExportDepth::Eight => (data.to_rgba8(), image::ExtendedColorType::Rgba8),
+        ExportDepth::Sixteen => (
+            data.to_rgba16().iter().flat_map(|v| v.to_ne_bytes()).collect(),
+            image::ExtendedColorType::Rgba16,
+        ),
+    }
+}
+
+/// Encode image in the given export format, embedding its colour profile
+/// where the format allows. `depth` is ignored by formats that only support
+/// 8 bits per channel.
+pub fn encode_image(data: &ImageData, format: ExportFormat, depth: ExportDepth) -> Result<Vec<u8>, String> {
+    use image::ImageEncoder;
+    use std::io::Cursor;
+    
+    let data = &for_export(data);
+    let profile = data.color_space.icc_profile();
+    let profile_error = |e| format!("Failed to embed colour profile: {}", e);
+    
+    match format {
+        ExportFormat::Png => encode_png(data, depth),
+        ExportFormat::Tiff => {
+            let mut buffer = Cursor::new(Vec::new());
+            let (bytes, color_type) = encoder_bytes(data, depth);
+            let mut encoder = image::codecs::tiff::TiffEncoder::new(&mut buffer);
+            encoder.set_icc_profile(profile).map_err(profile_error)?;
+            encoder.write_image(
+                &bytes,
+                data.width,
+                data.height,
+                color_type,
+            ).map_err(|e| format!("Failed to encode TIFF: {}", e))?;
+            
+            Ok(buffer.into_inner())
+        }
+        ExportFormat::Jpeg => {
+            // JPEG has no alpha channel
+            let rgb: Vec<u8> = data.to_rgba8()
+                .chunks_exact(4)
+                .flat_map(|p| [p[0], p[1], p[2]])
+                .collect();
+            
+            let mut buffer = Cursor::new(Vec::new());
+            let mut encoder = image::codecs::jpeg::JpegEncoder::new_with_quality(&mut buffer, 90);
+            encoder.set_icc_profile(profile).map_err(profile_error)?;
+            encoder.write_image(
+                &rgb,
+                data.width,
+                data.height,
+                image::ExtendedColorType::Rgb8,
+            ).map_err(|e| format!("Failed to encode JPEG: {}", e))?;
+            
+            Ok(buffer.into_inner())
+        }
+        ExportFormat::Webp => {
+            let mut buffer = Cursor::new(Vec::new());
+            let mut encoder = image::codecs::webp::WebPEncoder::new_lossless(&mut buffer);
+            encoder.set_icc_profile(profile).map_err(profile_error)?;
+            encoder.write_image(
+                &data.to_rgba8(),
+                data.width,
+                data.height,
+                image::ExtendedColorType::Rgba8,
+            ).map_err(|e| format!("Failed to encode WebP: {}", e))?;
+            
+            Ok(buffer.into_inner())
+        }
+    }
+}
@@ -0,0 +1,105 @@
+//! Building blocks for the pixel kernels
+//!
+//! Kernels work on RGBA `f32` buffers in bands of whole rows. With the
+//! `parallel` feature the bands run on rayon's thread pool; without it, or
+//! where no threads can be spawned (wasm without the canvas's `wasm-threads`
+//! pool), they run one after another on the calling thread. A pixel fits one
+//! 4-lane SIMD vector, which compiles to SSE on x86 and simd128 on wasm32.
+
+pub use wide::{f32x4, f64x4};
+
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
+
+/// Rows per band: enough work to outweigh scheduling, small enough to balance
+const BAND_ROWS: usize = 16;
+
+/// Pixels per band for kernels that don't care about rows
+const BAND_PIXELS: usize = 16 * 1024;
+
+/// Run `f(y, row)` on every row of an RGBA buffer `width` pixels wide
+pub fn for_each_row(pixels: &mut [f32], width: usize, f: impl Fn(usize, &mut [f32]) + Send + Sync) {
+    let row_len = width * 4;
+    if row_len == 0 {
+        return;
+    }
+    let band = |(b, rows): (usize, &mut [f32])| {
+        for (i, row) in rows.chunks_exact_mut(row_len).enumerate() {
+            f(b * BAND_ROWS + i, row);
+        }
+    };
+
+    #[cfg(feature = "parallel")]
+    pixels.par_chunks_mut(row_len * BAND_ROWS).enumerate().for_each(band);
+    #[cfg(not(feature = "parallel"))]
+    pixels.chunks_mut(row_len * BAND_ROWS).enumerate().for_each(band);
+}
+
+/// Run `f` on every RGBA pixel, in no particular order
+pub fn for_each_pixel(pixels: &mut [f32], f: impl Fn(&mut [f32]) + Send + Sync) {
+    let band = |pixels: &mut [f32]| pixels.chunks_exact_mut(4).for_each(&f);
+
+    #[cfg(feature = "parallel")]
+    pixels.par_chunks_mut(BAND_PIXELS * 4).for_each(band);
+    #[cfg(not(feature = "parallel"))]
+    pixels.chunks_mut(BAND_PIXELS * 4).for_each(band);
+}
+
+/// Load one RGBA pixel
+#[inline]
+pub fn load(pixel: &[f32]) -> f32x4 {
+    f32x4::new([pixel[0], pixel[1], pixel[2], pixel[3]])
+}
+
+/// Store one RGBA pixel
+#[inline]
+pub fn store(pixel: &mut [f32], value: f32x4) {
+    pixel[..4].copy_from_slice(&value.to_array());
+}
+
+/// Widen a pixel for sums that must not drift
+#[inline]
+pub fn widen(value: f32x4) -> f64x4 {
+    let [r, g, b, a] = value.to_array();
+    f64x4::new([r as f64, g as f64, b as f64, a as f64])
+}
+
+/// Narrow a widened pixel back
+#[inline]
+pub fn narrow(value: f64x4) -> f32x4 {
+    let [r, g, b, a] = value.to_array();
+    f32x4::new([r as f32, g as f32, b as f32, a as f32])
+}
+
+/// Multiply colour by alpha
+pub fn premultiply(pixels: &mut [f32]) {
+    for_each_pixel(pixels, |p| {
+        let a = p[3];
+        store(p, load(p) * f32x4::new([a, a, a, 1.0]));
+    });
+}
+
+/// Divide colour by alpha; fully transparent pixels become transparent black
+pub fn unpremultiply(pixels: &mut [f32]) {
+    for_each_pixel(pixels, |p| {
+        let a = p[3];
+        if a > 1e-6 {
+            let inv = 1.0 / a;
+            store(p, load(p) * f32x4::new([inv, inv, inv, 1.0]));
+        } else {
+            p[..3].fill(0.0);
+        }
+    });
+}
+
+/// Swap rows and columns of an RGBA buffer, so column passes can run as rows
+pub fn transpose(pixels: &[f32], width: usize, height: usize) -> Vec<f32> {
+    let mut output = vec![0.0f32; pixels.len()];
+    for_each_row(&mut output, height, |x, row| {
+        for (y, pixel) in row.chunks_exact_mut(4).enumerate() {
+            let idx = (y * width + x) * 4;
+            pixel.copy_from_slice(&pixels[idx..idx + 4]);
+        }
+    });
+    output
+}
@@ -0,0 +1,23 @@
+//! FlowNode Core - the node graph engine without a GUI
+//!
+//! Node definitions, graph data, the executor, image buffers and the React
+//! Flow compatibility layer. The canvas editor, `flownode-cli` and other
+//! services all depend on this crate so graphs run the same way everywhere.
+
+#![warn(clippy::all)]
+
+pub mod nodes;
+pub mod graph;
+pub mod compat;
+pub mod image_data;
+pub mod executor;
+pub mod backend;
+pub mod cpu;
+#[cfg(feature = "gpu")]
+pub mod gpu;
+pub mod blur;
+pub mod kernel;
+pub mod lut;
+pub mod mask;
+pub mod color;
+pub mod zip;
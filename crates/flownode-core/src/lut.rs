@@ -0,0 +1,405 @@
+//! Color lookup tables in the Adobe/Resolve `.cube` format
+//!
+//! Supports 1D and 3D tables with custom input domains. A 3D table is stored
+//! with red changing fastest, exactly as it appears in the file.
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
+use std::sync::Arc;
+use uuid::Uuid;
+
+use crate::executor::Executor;
+use crate::graph::{Connection, NodeGraph};
+use crate::image_data::ImageData;
+use crate::nodes::{LutInterpolation, Node, NodeProperties, NodeType, Vec2};
+
+/// Lattice size used when baking a grade into a 3D LUT
+pub const BAKE_SIZE: usize = 33;
+
+/// Largest table sizes we accept from a file
+const MAX_1D_SIZE: usize = 65536;
+const MAX_3D_SIZE: usize = 256;
+
+/// Whether a table maps each channel independently or RGB as a whole
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum LutKind {
+    OneD,
+    ThreeD,
+}
+
+/// A parsed lookup table
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Lut {
+    pub title: String,
+    pub kind: LutKind,
+    /// Entries per axis
+    pub size: usize,
+    pub domain_min: [f32; 3],
+    pub domain_max: [f32; 3],
+    /// `size` entries for 1D, `size³` for 3D (red fastest)
+    pub table: Vec<[f32; 3]>,
+}
+
+/// What a LUT node keeps in its properties: the table itself lives in the
+/// graph's [`LutStore`] so that hashing, snapshots and jobs stay small
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LutRef {
+    /// [`Lut::content_hash`] of the table
+    pub hash: u64,
+    pub title: String,
+}
+
+/// Loaded tables, keyed by content hash
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct LutStore {
+    tables: HashMap<u64, Arc<Lut>>,
+}
+
+impl LutStore {
+    /// Add a table, returning the reference a node stores
+    pub fn insert(&mut self, lut: Lut) -> LutRef {
+        let hash = lut.content_hash();
+        let title = lut.title.clone();
+        self.tables.entry(hash).or_insert_with(|| Arc::new(lut));
+        LutRef { hash, title }
+    }
+
+    /// Add a table that is already shared, under its known hash
+    pub fn insert_shared(&mut self, hash: u64, lut: Arc<Lut>) {
+        self.tables.entry(hash).or_insert(lut);
+    }
+
+    pub fn get(&self, hash: u64) -> Option<&Arc<Lut>> {
+        self.tables.get(&hash)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.tables.is_empty()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (u64, &Arc<Lut>)> {
+        self.tables.iter().map(|(hash, lut)| (*hash, lut))
+    }
+
+    /// Add every table from `other` that isn't here yet
+    pub fn extend(&mut self, other: &LutStore) {
+        for (hash, lut) in other.iter() {
+            self.insert_shared(hash, lut.clone());
+        }
+    }
+
+    /// Only the tables `refs` point at
+    pub fn subset<'a>(&self, refs: impl IntoIterator<Item = &'a LutRef>) -> LutStore {
+        let mut store = LutStore::default();
+        for r in refs {
+            if let Some(lut) = self.get(r.hash) {
+                store.insert_shared(r.hash, lut.clone());
+            }
+        }
+        store
+    }
+}
+
+impl Lut {
+    /// Parse the text of a `.cube` file
+    pub fn parse_cube(text: &str) -> Result<Self, String> {
+        let mut title = String::new();
+        let mut size_1d = None;
+        let mut size_3d = None;
+        let mut domain_min = [0.0f32; 3];
+        let mut domain_max = [1.0f32; 3];
+        let mut table = Vec::new();
+
+        for (line_no, line) in text.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let err = |msg: &str| format!("Line {}: {}", line_no + 1, msg);
+            let mut parts = line.split_whitespace();
+            let keyword = parts.next().unwrap_or_default();
+            let parse_floats = |parts: std::str::SplitWhitespace| -> Result<Vec<f32>, String> {
+                parts.map(|p| p.parse::<f32>().map_err(|_| err(&format!("invalid number '{}'", p))))
+                    .collect()
+            };
+
+            match keyword {
+                "TITLE" => {
+                    title = line["TITLE".len()..].trim().trim_matches('"').to_string();
+                }
+                "LUT_1D_SIZE" | "LUT_3D_SIZE" => {
+                    let size: usize = parts.next()
+                        .and_then(|s| s.parse().ok())
+                        .ok_or_else(|| err("invalid size"))?;
+                    if keyword == "LUT_1D_SIZE" {
+                        size_1d = Some(size);
+                    } else {
+                        size_3d = Some(size);
+                    }
+                }
+                "DOMAIN_MIN" | "DOMAIN_MAX" => {
+                    let values = parse_floats(parts)?;
+                    if values.len() != 3 {
+                        return Err(err("expected three values"));
+                    }
+                    let target = if keyword == "DOMAIN_MIN" { &mut domain_min } else { &mut domain_max };
+                    target.copy_from_slice(&values);
+                }
+                // Resolve's single-range variant of DOMAIN_MIN/MAX
+                "LUT_1D_INPUT_RANGE" | "LUT_3D_INPUT_RANGE" => {
+                    let values = parse_floats(parts)?;
+                    if values.len() != 2 {
+                        return Err(err("expected two values"));
+                    }
+                    domain_min = [values[0]; 3];
+                    domain_max = [values[1]; 3];
+                }
+                _ if keyword.starts_with(|c: char| c.is_ascii_digit() || c == '-' || c == '.') => {
+                    let values = parse_floats(line.split_whitespace())?;
+                    if values.len() != 3 {
+                        return Err(err("expected three values"));
+                    }
+                    table.push([values[0], values[1], values[2]]);
+                }
+                // Unknown keywords are allowed by the spec
+                _ => {}
+            }
+        }
+
+        let (kind, size, expected) = match (size_1d, size_3d) {
+            (Some(_), Some(_)) => return Err("Combined 1D shaper + 3D LUTs are not supported".to_string()),
+            (Some(n), None) if (2..=MAX_1D_SIZE).contains(&n) => (LutKind::OneD, n, n),
+            (None, Some(n)) if (2..=MAX_3D_SIZE).contains(&n) => (LutKind::ThreeD, n, n * n * n),
+            (None, None) => return Err("Missing LUT_1D_SIZE or LUT_3D_SIZE".to_string()),
+            _ => return Err("Unsupported LUT size".to_string()),
+        };
+
+        if table.len() != expected {
+            return Err(format!("Expected {} table entries, found {}", expected, table.len()));
+        }
+        for c in 0..3 {
+            if domain_max[c] <= domain_min[c] {
+                return Err("DOMAIN_MAX must be greater than DOMAIN_MIN".to_string());
+            }
+        }
+
+        Ok(Self { title, kind, size, domain_min, domain_max, table })
+    }
+
+    /// Hash of everything but the title, so the same table loaded twice is
+    /// stored once
+    pub fn content_hash(&self) -> u64 {
+        let mut hasher = std::collections::hash_map::DefaultHasher::new();
+        self.kind.hash(&mut hasher);
+        self.size.hash(&mut hasher);
+        for v in self.domain_min.iter().chain(&self.domain_max).chain(self.table.iter().flatten()) {
+            v.to_bits().hash(&mut hasher);
+        }
+        hasher.finish()
+    }
+
+    /// Write the table as `.cube` text
+    pub fn to_cube(&self) -> String {
+        let mut out = String::new();
+        if !self.title.is_empty() {
+            out.push_str(&format!("TITLE \"{}\"\n", self.title.replace('"', "'")));
+        }
+        match self.kind {
+            LutKind::OneD => out.push_str(&format!("LUT_1D_SIZE {}\n", self.size)),
+            LutKind::ThreeD => out.push_str(&format!("LUT_3D_SIZE {}\n", self.size)),
+        }
+        if self.domain_min != [0.0; 3] || self.domain_max != [1.0; 3] {
+            let [r, g, b] = self.domain_min;
+            out.push_str(&format!("DOMAIN_MIN {} {} {}\n", r, g, b));
+            let [r, g, b] = self.domain_max;
+            out.push_str(&format!("DOMAIN_MAX {} {} {}\n", r, g, b));
+        }
+        out.push('\n');
+        for [r, g, b] in &self.table {
+            out.push_str(&format!("{:.6} {:.6} {:.6}\n", r, g, b));
+        }
+        out
+    }
+
+    /// Map one RGB value (0-1)
+    pub fn apply(&self, rgb: [f32; 3], interpolation: LutInterpolation) -> [f32; 3] {
+        // Normalise into table coordinates 0..size-1
+        let max_index = (self.size - 1) as f32;
+        let mut p = [0.0f32; 3];
+        for c in 0..3 {
+            let t = (rgb[c] - self.domain_min[c]) / (self.domain_max[c] - self.domain_min[c]);
+            p[c] = t.clamp(0.0, 1.0) * max_index;
+        }
+
+        match self.kind {
+            LutKind::OneD => {
+                let mut out = [0.0f32; 3];
+                for c in 0..3 {
+                    let i0 = (p[c].floor() as usize).min(self.size - 2);
+                    let f = p[c] - i0 as f32;
+                    out[c] = self.table[i0][c] + (self.table[i0 + 1][c] - self.table[i0][c]) * f;
+                }
+                out
+            }
+            LutKind::ThreeD => match interpolation {
+                LutInterpolation::Trilinear => self.trilinear(p),
+                LutInterpolation::Tetrahedral => self.tetrahedral(p),
+            },
+        }
+    }
+
+    fn at(&self, r: usize, g: usize, b: usize) -> [f32; 3] {
+        self.table[r + g * self.size + b * self.size * self.size]
+    }
+
+    /// Lower lattice corner and fractional offsets for a point
+    fn cell(&self, p: [f32; 3]) -> ([usize; 3], [f32; 3]) {
+        let mut base = [0usize; 3];
+        let mut frac = [0.0f32; 3];
+        for c in 0..3 {
+            base[c] = (p[c].floor() as usize).min(self.size - 2);
+            frac[c] = p[c] - base[c] as f32;
+        }
+        (base, frac)
+    }
+
+    fn trilinear(&self, p: [f32; 3]) -> [f32; 3] {
+        let ([r, g, b], [fr, fg, fb]) = self.cell(p);
+        let lerp = |a: [f32; 3], b: [f32; 3], t: f32| -> [f32; 3] {
+            [a[0] + (b[0] - a[0]) * t, a[1] + (b[1] - a[1]) * t, a[2] + (b[2] - a[2]) * t]
+        };
+
+        let c00 = lerp(self.at(r, g, b), self.at(r + 1, g, b), fr);
+        let c10 = lerp(self.at(r, g + 1, b), self.at(r + 1, g + 1, b), fr);
+        let c01 = lerp(self.at(r, g, b + 1), self.at(r + 1, g, b + 1), fr);
+        let c11 = lerp(self.at(r, g + 1, b + 1), self.at(r + 1, g + 1, b + 1), fr);
+        lerp(lerp(c00, c10, fg), lerp(c01, c11, fg), fb)
+    }
+
+    /// Split the cube cell into six tetrahedra along the main diagonal
+    fn tetrahedral(&self, p: [f32; 3]) -> [f32; 3] {
+        let ([r, g, b], [fr, fg, fb]) = self.cell(p);
+        let c000 = self.at(r, g, b);
+        let c111 = self.at(r + 1, g + 1, b + 1);
+
+        // Walk from c000 to c111 along the axes in order of largest fraction
+        let (w, v1, v2) = if fr > fg {
+            if fg > fb {
+                ([fr, fg, fb], self.at(r + 1, g, b), self.at(r + 1, g + 1, b))
+            } else if fr > fb {
+                ([fr, fb, fg], self.at(r + 1, g, b), self.at(r + 1, g, b + 1))
+            } else {
+                ([fb, fr, fg], self.at(r, g, b + 1), self.at(r + 1, g, b + 1))
+            }
+        } else if fb > fg {
+            ([fb, fg, fr], self.at(r, g, b + 1), self.at(r, g + 1, b + 1))
+        } else if fb > fr {
+            ([fg, fb, fr], self.at(r, g + 1, b), self.at(r, g + 1, b + 1))
+        } else {
+            ([fg, fr, fb], self.at(r, g + 1, b), self.at(r + 1, g + 1, b))
+        };
+
+        let mut out = [0.0f32; 3];
+        for c in 0..3 {
+            out[c] = (1.0 - w[0]) * c000[c]
+                + (w[0] - w[1]) * v1[c]
+                + (w[1] - w[2]) * v2[c]
+                + w[2] * c111[c];
+        }
+        out
+    }
+}
+
+/// An image holding every point of a `size³` identity lattice, laid out so
+/// pixel index matches `.cube` table order (`size²` wide, `size` tall)
+pub fn lattice_image(size: usize) -> ImageData {
+    let max = (size - 1) as f32;
+    let mut pixels = Vec::with_capacity(size * size * size * 4);
+    for b in 0..size {
+        for g in 0..size {
+            for r in 0..size {
+                for v in [r, g, b] {
+                    pixels.push((v as f32 / max * 255.0).round() as u8);
+                }
+                pixels.push(255);
+            }
+        }
+    }
+    ImageData::new(pixels, (size * size) as u32, size as u32)
+}
+
+/// Bake the chain of Adjust/LUT nodes ending at `node_id` into a 3D LUT.
+/// The chain is followed upstream until the first node of any other type.
+pub fn bake(graph: &NodeGraph, node_id: Uuid, size: usize, title: &str) -> Result<Lut, String> {
+    let is_bakeable = |id: &Uuid| graph.nodes.get(id)
+        .is_some_and(|n| matches!(n.node_type, NodeType::Adjust | NodeType::Lut));
+
+    if !is_bakeable(&node_id) {
+        return Err("Select an Adjust or LUT node to export".to_string());
+    }
+
+    // Collect the chain from the selected node upstream
+    let mut chain = vec![node_id];
+    while let Some(upstream) = graph.connections_iter()
+        .find(|c| c.to_node == *chain.last().unwrap() && c.to_slot == 0)
+        .map(|c| c.from_node)
+        .filter(is_bakeable)
+    {
+        if chain.contains(&upstream) {
+            return Err("Cycle detected in graph".to_string());
+        }
+        chain.push(upstream);
+    }
+    chain.reverse();
+
+    // Run the chain on its own, fed by the identity lattice
+    let mut nodes = HashMap::new();
+    let mut connections = Vec::new();
+
+    let mut source = Node::new(NodeType::Image, Vec2::ZERO);
+    if let NodeProperties::Image { texture_id, .. } = &mut source.properties {
+        *texture_id = Some(0);
+    }
+    let mut previous = source.id;
+    nodes.insert(source.id, source);
+
+    for id in &chain {
+        connections.push(Connection { from_node: previous, from_slot: 0, to_node: *id, to_slot: 0 });
+        nodes.insert(*id, graph.nodes[id].clone());
+        previous = *id;
+    }
+
+    let output = Node::new(NodeType::Output, Vec2::ZERO);
+    let output_id = output.id;
+    connections.push(Connection { from_node: previous, from_slot: 0, to_node: output_id, to_slot: 0 });
+    nodes.insert(output_id, output);
+
+    let mut images = HashMap::new();
+    images.insert(0, lattice_image(size));
+
+    // Same working space as the project; the lattice and result are sRGB-encoded
+    let mut chain_graph = NodeGraph::from_parts(nodes, connections);
+    chain_graph.working_space = graph.working_space;
+    chain_graph.luts = graph.luts.clone();
+    let results = Executor::new().execute(&chain_graph, &images)?;
+    let baked = results.get(&output_id)
+        .and_then(|items| items.first())
+        .ok_or("Baking produced no output")?;
+
+    let table = baked.to_f32()
+        .chunks_exact(4)
+        .map(|p| [p[0], p[1], p[2]])
+        .collect();
+
+    Ok(Lut {
+        title: title.to_string(),
+        kind: LutKind::ThreeD,
+        size,
+        domain_min: [0.0; 3],
+        domain_max: [1.0; 3],
+        table,
+    })
+}
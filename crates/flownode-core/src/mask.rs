@@ -0,0 +1,142 @@
+//! Single-channel masks
+//!
+//! A mask says how strongly an operation applies at each pixel, from 0
+//! (untouched) to 1 (fully applied). Mask nodes build one from a channel of
+//! their input image or from a shape the size of it; Adjust, Effects and
+//! Composition take one on their optional mask input.
+
+use std::sync::Arc;
+
+use crate::image_data::{self, ImageData};
+use crate::kernel;
+use crate::nodes::MaskSource;
+
+/// Colour of masked areas in overlay previews, as sRGB
+pub const OVERLAY_COLOR: [f32; 3] = [1.0, 0.2, 0.2];
+
+/// Coverage values, one per pixel, row by row
+#[derive(Debug, Clone)]
+pub struct Mask {
+    values: Arc<Vec<f32>>,
+    pub width: u32,
+    pub height: u32,
+}
+
+impl Mask {
+    pub fn new(values: Vec<f32>, width: u32, height: u32) -> Self {
+        debug_assert_eq!(values.len(), (width * height) as usize);
+        Self { values: Arc::new(values), width, height }
+    }
+
+    /// The same value everywhere
+    pub fn solid(width: u32, height: u32, value: f32) -> Self {
+        Self::new(vec![value; (width * height) as usize], width, height)
+    }
+
+    pub fn values(&self) -> &[f32] {
+        &self.values
+    }
+
+    pub fn get(&self, x: u32, y: u32) -> f32 {
+        self.values[(y * self.width + x) as usize]
+    }
+
+    /// Read one channel of an image. Shape sources read nothing and give an
+    /// empty mask.
+    pub fn from_channel(img: &ImageData, source: MaskSource) -> Self {
+        let values = img.to_f32().chunks_exact(4)
+            .map(|p| match source {
+                MaskSource::Luminance => 0.299 * p[0] + 0.587 * p[1] + 0.114 * p[2],
+                MaskSource::Alpha => p[3],
+                MaskSource::Red => p[0],
+                MaskSource::Green => p[1],
+                MaskSource::Blue => p[2],
+                MaskSource::Ellipse | MaskSource::Rectangle | MaskSource::Gradient => 0.0,
+            }.clamp(0.0, 1.0))
+            .collect();
+        Self::new(values, img.width, img.height)
+    }
+
+    /// Draw a shape. `center` and `size` are fractions of the image width and
+    /// height; `angle` turns the shape (or the gradient direction) in degrees.
+    /// Shape edges are antialiased over about a pixel. A gradient runs from 0
+    /// to 1 over `size[0]` of the longer side, 0° going left to right.
+    pub fn shape(width: u32, height: u32, source: MaskSource, center: [f32; 2], size: [f32; 2], angle: f32) -> Self {
+        let (w, h) = (width as f32, height as f32);
+        let (cx, cy) = (center[0] * w, center[1] * h);
+        let (sin, cos) = angle.to_radians().sin_cos();
+        let rx = (size[0] * w * 0.5).max(0.5);
+        let ry = (size[1] * h * 0.5).max(0.5);
+        let ramp = (size[0] * w.max(h)).max(1.0);
+
+        let mut values = Vec::with_capacity((width * height) as usize);
+        for y in 0..height {
+            for x in 0..width {
+                // Pixel centre in the shape's rotated frame
+                let dx = x as f32 + 0.5 - cx;
+                let dy = y as f32 + 0.5 - cy;
+                let u = dx * cos + dy * sin;
+                let v = -dx * sin + dy * cos;
+
+                let value = match source {
+                    MaskSource::Ellipse => {
+                        let d = ((u / rx).powi(2) + (v / ry).powi(2)).sqrt();
+                        (1.0 - d) * rx.min(ry) + 0.5
+                    }
+                    MaskSource::Rectangle => (rx - u.abs()).min(ry - v.abs()) + 0.5,
+                    MaskSource::Gradient => u / ramp + 0.5,
+                    _ => 0.0,
+                };
+                values.push(value.clamp(0.0, 1.0));
+            }
+        }
+        Self::new(values, width, height)
+    }
+
+    pub fn inverted(&self) -> Self {
+        Self::new(self.values.iter().map(|v| 1.0 - v).collect(), self.width, self.height)
+    }
+
+    /// Scale every value by `density` (0-1), weakening the whole mask
+    pub fn with_density(&self, density: f32) -> Self {
+        let density = density.clamp(0.0, 1.0);
+        Self::new(self.values.iter().map(|v| v * density).collect(), self.width, self.height)
+    }
+
+    /// Resample to exactly `width` × `height`, for masks made from an image of
+    /// another size
+    pub fn resize(&self, width: u32, height: u32) -> Self {
+        if self.width == width && self.height == height {
+            return self.clone();
+        }
+        Self::from_channel(&image_data::resize(&self.to_image(), width, height), MaskSource::Red)
+    }
+
+    /// Opaque grey image of the mask, white where it is 1
+    pub fn to_image(&self) -> ImageData {
+        let pixels = self.values.iter().flat_map(|&v| [v, v, v, 1.0]).collect();
+        ImageData::from_f32(pixels, self.width, self.height)
+    }
+}
+
+/// Tint the masked areas of `img` with [`OVERLAY_COLOR`] for previews. The
+/// mask is resampled to the image if their sizes differ.
+pub fn overlay(img: &ImageData, mask: &Mask) -> ImageData {
+    let mask = mask.resize(img.width, img.height);
+    let tint = crate::color::convert(&ImageData::from_f32(vec![OVERLAY_COLOR[0], OVERLAY_COLOR[1], OVERLAY_COLOR[2], 1.0], 1, 1), img.color_space)
+        .to_f32();
+
+    let mut output = img.to_f32();
+    let values = mask.values();
+    kernel::for_each_row(&mut output, img.width as usize, |y, row| {
+        let values = &values[y * img.width as usize..(y + 1) * img.width as usize];
+        for (pixel, &m) in row.chunks_exact_mut(4).zip(values) {
+            let t = m * 0.5;
+            for c in 0..3 {
+                pixel[c] += (tint[c] - pixel[c]) * t;
+            }
+            pixel[3] = pixel[3].max(t);
+        }
+    });
+    ImageData::from_f32(output, img.width, img.height).with_color_space(img.color_space)
+}
@@ -0,0 +1,1244 @@
+//! Node types and their definitions
+//! Matches FlowNode React specification exactly.
+
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::color::ColorSpace;
+use crate::lut::LutRef;
+
+/// All available node types in FlowNode
+/// Names match React app exactly for compatibility
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum NodeType {
+    // Content nodes
+    Image,      // Image display with history
+    Content,    // Universal content node
+    Bucket,     // Multi-image container
+    
+    // Editing nodes (Phase 1 focus)
+    Adjust,     // Full color grading (10 sliders + wheels + curves)
+    Effects,    // FX (blur, grain, vignette, sharpen)
+    Lut,        // .cube lookup table
+    Mask,       // Region for masked edits
+    
+    // Text nodes
+    Text,       // Text input
+    Concat,     // Join text
+    Splitter,   // Split text
+    Postit,     // Sticky note
+    
+    // Utility nodes
+    Compare,    // Side-by-side comparison
+    Composition,// Layer-based editor
+    Router,     // Signal routing
+    Batch,      // Batch processing
+    Title,      // Labels
+    Group,      // Group container
+    Folder,     // Folder container
+    Convertor,  // Format conversion
+    
+    // Output nodes
+    Output,     // Named export target
+    
+    // AI Generation nodes (Phase 3)
+    Omni,       // Multi-model image gen
+    Llm,        // Text generation
+    Video,      // Video generation
+    Upscaler,   // Image upscaling
+    Vector,     // SVG conversion
+    Rodin3d,    // 3D generation
+    MindMap,    // AI mind mapping
+}
+
+impl NodeType {
+    /// Get the display name for this node type
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Image => "Image",
+            Self::Content => "Content",
+            Self::Bucket => "Bucket",
+            Self::Adjust => "Adjust",
+            Self::Effects => "Effects",
+            Self::Lut => "LUT",
+            Self::Mask => "Mask",
+            Self::Text => "Text",
+            Self::Concat => "Concat",
+            Self::Splitter => "Splitter",
+            Self::Postit => "Post-It",
+            Self::Compare => "Compare",
+            Self::Composition => "Composition",
+            Self::Router => "Router",
+            Self::Batch => "Batch",
+            Self::Title => "Title",
+            Self::Group => "Group",
+            Self::Folder => "Folder",
+            Self::Convertor => "Convertor",
+            Self::Output => "Output",
+            Self::Omni => "Omni",
+            Self::Llm => "LLM",
+            Self::Video => "Video",
+            Self::Upscaler => "Upscaler",
+            Self::Vector => "Vector",
+            Self::Rodin3d => "3D",
+            Self::MindMap => "Mind Map",
+        }
+    }
+    
+    /// Get the keyboard shortcut for this node type
+    pub fn shortcut(&self) -> Option<char> {
+        match self {
+            Self::Text => Some('T'),
+            Self::Postit => Some('N'),
+            Self::Image => Some('I'),
+            Self::Bucket => Some('B'),
+            Self::Concat => Some('J'),
+            Self::Splitter => Some('S'),
+            Self::Compare => Some('C'),
+            Self::Composition => Some('F'),
+            Self::Omni => Some('O'),
+            Self::Llm => Some('L'),
+            Self::Upscaler => Some('U'),
+            Self::Vector => Some('V'),
+            Self::Rodin3d => Some('3'),
+            Self::Title => Some('H'),
+            Self::MindMap => Some('M'),
+            Self::Content => Some('K'),
+            Self::Video => Some('D'),
+            Self::Batch => Some('Q'),
+            Self::Router => Some('R'),
+            Self::Adjust => Some('A'),
+            Self::Effects => Some('E'),
+            Self::Output => Some('X'),
+            _ => None,
+        }
+    }
+    
+    /// Get the category color for this node type as sRGB
+    pub fn color(&self) -> [u8; 3] {
+        match self {
+            // Content - Green
+            Self::Image | Self::Content | Self::Bucket => 
+                [76, 175, 80],
+            
+            // Editing - Orange
+            Self::Adjust | Self::Effects | Self::Lut | Self::Mask | Self::Composition | Self::Compare => 
+                [255, 152, 0],
+            
+            // Text - Cyan
+            Self::Text | Self::Concat | Self::Splitter | Self::Postit => 
+                [0, 188, 212],
+            
+            // AI Generation - Purple
+            Self::Omni | Self::Llm | Self::Video | Self::Upscaler | 
+            Self::Vector | Self::Rodin3d | Self::MindMap => 
+                [156, 39, 176],
+            
+            // Utility - Blue
+            Self::Router | Self::Batch | Self::Title | Self::Group | 
+            Self::Folder | Self::Convertor => 
+                [33, 150, 243],
+            
+            // Output - Red
+            Self::Output => [244, 67, 54],
+        }
+    }
+    
+    /// Get the input slots for this node type
+    pub fn inputs(&self) -> Vec<SlotInfo> {
+        match self {
+            // No inputs
+            Self::Image | Self::Text | Self::Postit | Self::Title | 
+            Self::Batch | Self::Group | Self::Folder => vec![],
+            
+            // Single content input
+            Self::Lut | Self::Mask | Self::Upscaler | Self::Vector |
+            Self::Convertor | Self::Content | Self::Output => vec![
+                SlotInfo::new("content-in", SlotType::Content),
+            ],
+            
+            // Content and an optional mask limiting where the edit applies
+            Self::Adjust | Self::Effects => vec![
+                SlotInfo::new("content-in", SlotType::Content),
+                SlotInfo::new("mask-in", SlotType::Mask),
+            ],
+            
+            // Multiple inputs
+            Self::Compare => vec![
+                SlotInfo::new("content-in-1", SlotType::Content),
+                SlotInfo::new("content-in-2", SlotType::Content),
+            ],
+            Self::Composition => vec![
+                SlotInfo::new("content-in", SlotType::Content), // Bottom layer
+                SlotInfo::new("content-in-2", SlotType::Content),
+                SlotInfo::new("content-in-3", SlotType::Content),
+                SlotInfo::new("content-in-4", SlotType::Content),
+                SlotInfo::new("content-in-5", SlotType::Content),
+                SlotInfo::new("content-in-6", SlotType::Content),
+                SlotInfo::new("content-in-7", SlotType::Content),
+                SlotInfo::new("content-in-8", SlotType::Content),
+                SlotInfo::new("content-in-9", SlotType::Content),
+                SlotInfo::new("content-in-10", SlotType::Content), // Top layer
+                SlotInfo::new("mask-in", SlotType::Mask), // Where layers above the bottom show
+            ],
+            Self::Bucket => vec![
+                SlotInfo::new("content-in", SlotType::Content),
+            ],
+            
+            // Text inputs
+            Self::Concat => vec![
+                SlotInfo::new("text-in-1", SlotType::Text),
+                SlotInfo::new("text-in-2", SlotType::Text),
+            ],
+            Self::Splitter => vec![
+                SlotInfo::new("text-in", SlotType::Text),
+            ],
+            
+            // AI nodes
+            Self::Omni => vec![
+                SlotInfo::new("prompts-in", SlotType::Text),
+                SlotInfo::new("images-in", SlotType::Content),
+            ],
+            Self::Llm => vec![
+                SlotInfo::new("prompts-in", SlotType::Text),
+            ],
+            Self::Video => vec![
+                SlotInfo::new("prompts-in", SlotType::Text),
+                SlotInfo::new("images-in", SlotType::Content),
+            ],
+            Self::Rodin3d => vec![
+                SlotInfo::new("images-in", SlotType::Content),
+            ],
+            Self::MindMap => vec![
+                SlotInfo::new("prompts-in", SlotType::Text),
+            ],
+            
+            // Router
+            Self::Router => vec![
+                SlotInfo::new("router-in", SlotType::Content),
+            ],
+        }
+    }
+    
+    /// Get the output slots for this node type
+    pub fn outputs(&self) -> Vec<SlotInfo> {
+        match self {
+            // Content output
+            Self::Image | Self::Adjust | Self::Effects | Self::Lut | Self::Compare |
+            Self::Composition | Self::Content | Self::Upscaler |
+            Self::Vector | Self::Convertor | Self::Omni | Self::Video |
+            Self::Rodin3d => vec![
+                SlotInfo::new("content-out", SlotType::Content),
+            ],
+            
+            // Text output
+            Self::Text | Self::Concat | Self::Llm | Self::MindMap => vec![
+                SlotInfo::new("text-out", SlotType::Text),
+            ],
+            
+            // Multiple text outputs
+            Self::Splitter => vec![
+                SlotInfo::new("text-out", SlotType::Text), // Array of lines
+            ],
+            
+            // Mask output
+            Self::Mask => vec![
+                SlotInfo::new("mask-out", SlotType::Mask),
+            ],
+            
+            // Batch output
+            Self::Bucket | Self::Batch => vec![
+                SlotInfo::new("batch-out", SlotType::Batch),
+            ],
+            
+            // Router outputs (multiple)
+            Self::Router => vec![
+                SlotInfo::new("router-out-1", SlotType::Content),
+                SlotInfo::new("router-out-2", SlotType::Content),
+                SlotInfo::new("router-out-3", SlotType::Content),
+            ],
+            
+            // No outputs
+            Self::Postit | Self::Title | Self::Group | Self::Folder |
+            Self::Output => vec![],
+        }
+    }
+    
+    /// Type of each item on the node's batch output: Buckets collect images
+    /// and Batch nodes list text
+    pub fn batch_item(&self) -> Option<SlotType> {
+        match self {
+            Self::Bucket => Some(SlotType::Content),
+            Self::Batch => Some(SlotType::Text),
+            _ => None,
+        }
+    }
+    
+    /// Input slot taking a mask, if the node has one
+    pub fn mask_slot(&self) -> Option<usize> {
+        self.inputs().iter().position(|slot| slot.slot_type == SlotType::Mask)
+    }
+}
+
+/// Type of data a slot can carry
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SlotType {
+    Content,    // Images, videos, documents
+    Text,       // Text data
+    Batch,      // Batch of items
+    Mask,       // Single-channel coverage
+}
+
+impl SlotType {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Content => "content",
+            Self::Text => "text",
+            Self::Batch => "batch",
+            Self::Mask => "mask",
+        }
+    }
+    
+    /// Whether an input of this type can take an output of type `output`,
+    /// whose items are of type `item` if it's a batch. An input also takes a
+    /// batch of its own type; the node then runs once per item.
+    pub fn accepts(&self, output: SlotType, item: Option<SlotType>) -> bool {
+        *self == output || (output == Self::Batch && item == Some(*self))
+    }
+    
+    /// Wire and slot color as sRGB
+    pub fn color(&self) -> [u8; 3] {
+        match self {
+            Self::Content => [255, 193, 7],  // Amber
+            Self::Text => [0, 188, 212],     // Cyan
+            Self::Batch => [156, 39, 176],   // Purple
+            Self::Mask => [224, 224, 224],   // Light grey
+        }
+    }
+}
+
+/// Information about an input/output slot
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SlotInfo {
+    pub name: &'static str,
+    pub slot_type: SlotType,
+}
+
+impl SlotInfo {
+    pub fn new(name: &'static str, slot_type: SlotType) -> Self {
+        Self { name, slot_type }
+    }
+}
+
+/// A position on the canvas, in graph units. Serializes as `{"x", "y"}`
+/// like the editor's own vectors, so saved graphs stay compatible.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct Vec2 {
+    pub x: f32,
+    pub y: f32,
+}
+
+impl Vec2 {
+    pub const ZERO: Self = Self { x: 0.0, y: 0.0 };
+
+    pub const fn new(x: f32, y: f32) -> Self {
+        Self { x, y }
+    }
+}
+
+impl std::ops::Add for Vec2 {
+    type Output = Self;
+
+    fn add(self, other: Self) -> Self {
+        Self::new(self.x + other.x, self.y + other.y)
+    }
+}
+
+impl std::ops::AddAssign for Vec2 {
+    fn add_assign(&mut self, other: Self) {
+        self.x += other.x;
+        self.y += other.y;
+    }
+}
+
+/// A node instance in the graph
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Node {
+    pub id: Uuid,
+    pub node_type: NodeType,
+    pub position: Vec2,
+    pub properties: NodeProperties,
+    #[serde(default)]
+    pub label: Option<String>,
+}
+
+impl Node {
+    pub fn new(node_type: NodeType, position: Vec2) -> Self {
+        Self {
+            id: Uuid::new_v4(),
+            node_type,
+            position,
+            properties: NodeProperties::for_type(node_type),
+            label: None,
+        }
+    }
+}
+
+/// Node-specific properties and settings
+/// Matches React app data structures exactly
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum NodeProperties {
+    // === Content Nodes ===
+    Image {
+        image: Option<String>,          // URL or base64
+        thumbnail: Option<String>,
+        history: Vec<String>,           // Up to 10 entries
+        #[serde(skip)]
+        texture_id: Option<u64>,
+    },
+    
+    Content {
+        content: Option<String>,
+    },
+    
+    Bucket {
+        images: Vec<String>,            // Multiple images
+        #[serde(skip)]
+        image_ids: Vec<u64>,            // Loaded images, in order
+    },
+    
+    // === Editing Nodes (Phase 1 Focus) ===
+    
+    /// Full color grading - matches React ImageAdjustNode exactly
+    Adjust {
+        // Basic adjustments (-100 to 100)
+        brightness: f32,
+        contrast: f32,
+        saturation: f32,
+        exposure: f32,
+        highlights: f32,
+        shadows: f32,
+        temperature: f32,   // Cool to warm
+        tint: f32,          // Green to magenta
+        vibrance: f32,
+        gamma: f32,         // Maps to 0.1-3.0 internally
+        
+        // Color grading wheels
+        lift: ColorWheel,   // Shadows
+        gamma_wheel: ColorWheel,  // Midtones (renamed to avoid conflict)
+        gain: ColorWheel,   // Highlights
+        offset: ColorWheel, // Master
+        
+        // Additional
+        color_boost: f32,       // -100 to 100
+        hue_rotation: f32,      // -180 to 180
+        luminance_mix: f32,     // 0 to 100
+        
+        // RGB Curves
+        curves_enabled: bool,
+        #[serde(default)]
+        curves: Curves,
+    },
+    
+    /// Effects - matches React EffectsNode exactly
+    Effects {
+        // Gaussian blur
+        gaussian_blur: f32,             // 0-100
+        
+        /// What the blurs see past the image edges
+        #[serde(default)]
+        blur_edge_mode: EdgeMode,
+        
+        // Directional blur
+        directional_blur: f32,          // 0-100
+        directional_blur_angle: f32,    // 0-360
+        
+        // Progressive blur
+        progressive_blur: f32,          // 0-100
+        progressive_blur_direction: BlurDirection,
+        progressive_blur_falloff: f32,  // 0-100
+        
+        // Glass blinds
+        glass_blinds: f32,              // 0-100
+        glass_blinds_frequency: f32,    // 1-50
+        glass_blinds_angle: f32,        // 0-360
+        glass_blinds_phase: f32,        // 0-100
+        
+        // Grain
+        grain: f32,                     // 0-100
+        grain_size: f32,                // 1-10
+        grain_monochrome: bool,
+        grain_seed: u32,
+        
+        // Sharpen
+        sharpen: f32,                   // 0-100
+        
+        // Vignette
+        vignette: f32,                  // 0-100
+        vignette_roundness: f32,        // 0-100
+        vignette_smoothness: f32,       // 0-100
+    },
+    
+    /// Lookup table loaded from a .cube file
+    Lut {
+        file_name: String,
+        lut: Option<LutRef>,
+        interpolation: LutInterpolation,
+        intensity: f32,                 // 0-100, mix with the original
+    },
+    
+    /// Single-channel mask from an image channel or a shape
+    Mask {
+        source: MaskSource,
+        // Shapes, as fractions of the image size
+        center: [f32; 2],
+        size: [f32; 2],
+        angle: f32,                     // 0-360
+        invert: bool,
+        feather: f32,                   // 0-100
+        density: f32,                   // 0-100
+    },
+    
+    // === Text Nodes ===
+    Text {
+        text: String,
+    },
+    
+    Concat {
+        separator: String,
+    },
+    
+    Splitter {
+        delimiter: String,
+    },
+    
+    Postit {
+        text: String,
+        color: [f32; 4],
+    },
+    
+    // === Utility Nodes ===
+    Compare {
+        #[serde(default)]
+        mode: CompareMode,
+        #[serde(default = "default_compare_split")]
+        split: f32,                     // 0-1, divider position for split modes
+        #[serde(skip)]
+        metrics: Option<CompareMetrics>,
+    },
+    
+    Composition {
+        layers: Vec<CompositionLayer>,
+    },
+    
+    Router {
+        active_output: usize,
+    },
+    
+    Batch {
+        items: Vec<String>,
+    },
+    
+    Title {
+        text: String,
+    },
+    
+    Group {},
+    Folder {},
+    Convertor {},
+    
+    // === Output Nodes ===
+    Output {
+        name: String,                   // Used as the export file name
+        format: ExportFormat,
+        max_size: u32,                  // Longest side in pixels, 0 = original
+        #[serde(default)]
+        bit_depth: ExportDepth,
+        #[serde(default)]
+        color_space: ColorSpace,       // Encoded space written to the file
+    },
+    
+    // === AI Generation Nodes (Phase 3) ===
+    Omni {
+        model: String,
+        prompt: String,
+        negative_prompt: String,
+        seed: Option<u32>,
+    },
+    
+    Llm {
+        model: String,
+        system_prompt: String,
+    },
+    
+    Video {
+        model: String,
+        duration: u32,          // 4-10 seconds
+        aspect_ratio: String,
+    },
+    
+    Upscaler {
+        model: String,
+        scale: u32,
+    },
+    
+    Vector {},
+    Rodin3d {},
+    MindMap {},
+}
+
+/// Color wheel for color grading (lift/gamma/gain/offset)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ColorWheel {
+    pub x: f32,         // -1 to 1 (hue position)
+    pub y: f32,         // -1 to 1 (hue position)
+    pub luminance: f32, // -100 to 100
+}
+
+impl Default for ColorWheel {
+    fn default() -> Self {
+        Self { x: 0.0, y: 0.0, luminance: 0.0 }
+    }
+}
+
+/// Tone curve through control points (x, y in 0-1), evaluated as a
+/// monotone cubic spline so it never overshoots between points
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Curve {
+    /// Control points sorted by x
+    pub points: Vec<[f32; 2]>,
+}
+
+impl Default for Curve {
+    fn default() -> Self {
+        Self { points: vec![[0.0, 0.0], [1.0, 1.0]] }
+    }
+}
+
+impl Curve {
+    /// True if the curve maps every value to itself
+    pub fn is_identity(&self) -> bool {
+        self.points.iter().all(|p| (p[0] - p[1]).abs() < 1e-4)
+            && self.points.first().is_some_and(|p| p[0] <= 0.0)
+            && self.points.last().is_some_and(|p| p[0] >= 1.0)
+    }
+    
+    /// Fritsch–Carlson tangents for each control point
+    fn tangents(&self) -> Vec<f32> {
+        let p = &self.points;
+        let n = p.len();
+        if n < 2 {
+            return vec![0.0; n];
+        }
+        
+        // Secant slopes between neighbouring points
+        let secants: Vec<f32> = p.windows(2)
+            .map(|w| {
+                let dx = w[1][0] - w[0][0];
+                if dx.abs() < 1e-6 { 0.0 } else { (w[1][1] - w[0][1]) / dx }
+            })
+            .collect();
+        
+        let mut m = vec![0.0; n];
+        m[0] = secants[0];
+        m[n - 1] = secants[n - 2];
+        for k in 1..n - 1 {
+            m[k] = if secants[k - 1] * secants[k] <= 0.0 {
+                0.0
+            } else {
+                (secants[k - 1] + secants[k]) / 2.0
+            };
+        }
+        
+        // Limit tangents to keep each segment monotone
+        for k in 0..n - 1 {
+            if secants[k] == 0.0 {
+                m[k] = 0.0;
+                m[k + 1] = 0.0;
+                continue;
+            }
+            let a = m[k] / secants[k];
+            let b = m[k + 1] / secants[k];
+            let s = a * a + b * b;
+            if s > 9.0 {
+                let t = 3.0 / s.sqrt();
+                m[k] = t * a * secants[k];
+                m[k + 1] = t * b * secants[k];
+            }
+        }
+        
+        m
+    }
+    
+    /// Evaluate the curve at x (0-1). Flat beyond the first and last points.
+    pub fn evaluate(&self, x: f32) -> f32 {
+        self.evaluate_with(x, &self.tangents())
+    }
+    
+    fn evaluate_with(&self, x: f32, tangents: &[f32]) -> f32 {
+        let p = &self.points;
+        match p.len() {
+            0 => return x,
+            1 => return p[0][1],
+            _ => {}
+        }
+        
+        if x <= p[0][0] {
+            return p[0][1].clamp(0.0, 1.0);
+        }
+        if x >= p[p.len() - 1][0] {
+            return p[p.len() - 1][1].clamp(0.0, 1.0);
+        }
+        
+        let k = p.windows(2).position(|w| x < w[1][0]).unwrap_or(p.len() - 2);
+        let h = p[k + 1][0] - p[k][0];
+        if h <= 0.0 {
+            return p[k][1];
+        }
+        
+        // Cubic Hermite basis
+        let t = (x - p[k][0]) / h;
+        let t2 = t * t;
+        let t3 = t2 * t;
+        let y = (2.0 * t3 - 3.0 * t2 + 1.0) * p[k][1]
+            + (t3 - 2.0 * t2 + t) * h * tangents[k]
+            + (-2.0 * t3 + 3.0 * t2) * p[k + 1][1]
+            + (t3 - t2) * h * tangents[k + 1];
+        
+        y.clamp(0.0, 1.0)
+    }
+    
+    /// Sample the curve into a 256-entry lookup table (values 0-1)
+    pub fn to_lut(&self) -> Vec<f32> {
+        let tangents = self.tangents();
+        (0..256)
+            .map(|i| self.evaluate_with(i as f32 / 255.0, &tangents))
+            .collect()
+    }
+}
+
+/// Master and per-channel curves for the Adjust node
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Curves {
+    pub master: Curve,
+    pub red: Curve,
+    pub green: Curve,
+    pub blue: Curve,
+}
+
+impl Curves {
+    /// True if no curve changes anything
+    pub fn is_identity(&self) -> bool {
+        self.master.is_identity() && self.red.is_identity()
+            && self.green.is_identity() && self.blue.is_identity()
+    }
+}
+
+/// How a 3D LUT is sampled between lattice points
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum LutInterpolation {
+    Trilinear,
+    #[default]
+    Tetrahedral,
+}
+
+impl LutInterpolation {
+    pub const ALL: [LutInterpolation; 2] = [Self::Trilinear, Self::Tetrahedral];
+    
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Trilinear => "Trilinear",
+            Self::Tetrahedral => "Tetrahedral",
+        }
+    }
+    
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Trilinear => "trilinear",
+            Self::Tetrahedral => "tetrahedral",
+        }
+    }
+    
+    pub fn parse(s: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|i| i.as_str() == s)
+    }
+}
+
+/// How blurs sample pixels beyond the image edges
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum EdgeMode {
+    /// Repeat the edge pixel
+    #[default]
+    Clamp,
+    /// Reflect the image back from the edge
+    Mirror,
+    /// Continue from the opposite edge, for tiling textures
+    Wrap,
+}
+
+impl EdgeMode {
+    pub const ALL: [EdgeMode; 3] = [Self::Clamp, Self::Mirror, Self::Wrap];
+    
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Clamp => "Clamp",
+            Self::Mirror => "Mirror",
+            Self::Wrap => "Wrap",
+        }
+    }
+    
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Clamp => "clamp",
+            Self::Mirror => "mirror",
+            Self::Wrap => "wrap",
+        }
+    }
+    
+    pub fn parse(s: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|m| m.as_str() == s)
+    }
+}
+
+/// What a Mask node reads its mask from
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum MaskSource {
+    /// Perceptual brightness of the input
+    #[default]
+    Luminance,
+    Alpha,
+    Red,
+    Green,
+    Blue,
+    /// Shapes drawn at the size of the input
+    Ellipse,
+    Rectangle,
+    Gradient,
+}
+
+impl MaskSource {
+    pub const ALL: [MaskSource; 8] = [
+        Self::Luminance, Self::Alpha, Self::Red, Self::Green, Self::Blue,
+        Self::Ellipse, Self::Rectangle, Self::Gradient,
+    ];
+    
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Luminance => "Luminance",
+            Self::Alpha => "Alpha",
+            Self::Red => "Red",
+            Self::Green => "Green",
+            Self::Blue => "Blue",
+            Self::Ellipse => "Ellipse",
+            Self::Rectangle => "Rectangle",
+            Self::Gradient => "Gradient",
+        }
+    }
+    
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Luminance => "luminance",
+            Self::Alpha => "alpha",
+            Self::Red => "red",
+            Self::Green => "green",
+            Self::Blue => "blue",
+            Self::Ellipse => "ellipse",
+            Self::Rectangle => "rectangle",
+            Self::Gradient => "gradient",
+        }
+    }
+    
+    pub fn parse(s: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|m| m.as_str() == s)
+    }
+    
+    /// Whether the mask is drawn rather than read from the image
+    pub fn is_shape(&self) -> bool {
+        matches!(self, Self::Ellipse | Self::Rectangle | Self::Gradient)
+    }
+}
+
+/// Blur direction for progressive blur
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum BlurDirection {
+    Top,
+    Bottom,
+    Left,
+    Right,
+}
+
+impl Default for BlurDirection {
+    fn default() -> Self {
+        Self::Bottom
+    }
+}
+
+/// File format for Output nodes
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum ExportFormat {
+    #[default]
+    Png,
+    Jpeg,
+    Webp,
+    Tiff,
+}
+
+impl ExportFormat {
+    /// File extension (without dot)
+    pub fn extension(&self) -> &'static str {
+        match self {
+            Self::Png => "png",
+            Self::Jpeg => "jpg",
+            Self::Webp => "webp",
+            Self::Tiff => "tiff",
+        }
+    }
+    
+    /// MIME type for downloads
+    pub fn mime_type(&self) -> &'static str {
+        match self {
+            Self::Png => "image/png",
+            Self::Jpeg => "image/jpeg",
+            Self::Webp => "image/webp",
+            Self::Tiff => "image/tiff",
+        }
+    }
+    
+    /// Name used by the React app's `output_format` setting
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Png => "png",
+            Self::Jpeg => "jpeg",
+            Self::Webp => "webp",
+            Self::Tiff => "tiff",
+        }
+    }
+    
+    /// Whether the format can store 16 bits per channel
+    pub fn supports_16_bit(&self) -> bool {
+        matches!(self, Self::Png | Self::Tiff)
+    }
+    
+    /// Parse from the React app's `output_format` string
+    pub fn parse(s: &str) -> Option<Self> {
+        match s {
+            "png" => Some(Self::Png),
+            "jpeg" | "jpg" => Some(Self::Jpeg),
+            "webp" => Some(Self::Webp),
+            "tiff" | "tif" => Some(Self::Tiff),
+            _ => None,
+        }
+    }
+}
+
+/// Bits per channel written by Output nodes (for formats that support 16)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum ExportDepth {
+    #[default]
+    Eight,
+    Sixteen,
+}
+
+impl ExportDepth {
+    pub fn bits(&self) -> u32 {
+        match self {
+            Self::Eight => 8,
+            Self::Sixteen => 16,
+        }
+    }
+    
+    pub fn from_bits(bits: u64) -> Option<Self> {
+        match bits {
+            8 => Some(Self::Eight),
+            16 => Some(Self::Sixteen),
+            _ => None,
+        }
+    }
+}
+
+/// How the Compare node visualises its two inputs
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum CompareMode {
+    #[default]
+    SplitVertical,      // Left: input 1, right: input 2
+    SplitHorizontal,    // Top: input 1, bottom: input 2
+    OnionSkin,          // 50% mix
+    Difference,         // Absolute difference heat-map
+    SideBySide,
+}
+
+impl CompareMode {
+    /// All compare modes, in menu order
+    pub const ALL: [CompareMode; 5] = [
+        Self::SplitVertical,
+        Self::SplitHorizontal,
+        Self::OnionSkin,
+        Self::Difference,
+        Self::SideBySide,
+    ];
+    
+    /// Display name
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::SplitVertical => "Split (vertical)",
+            Self::SplitHorizontal => "Split (horizontal)",
+            Self::OnionSkin => "Onion skin",
+            Self::Difference => "Difference",
+            Self::SideBySide => "Side by side",
+        }
+    }
+    
+    /// Identifier used in React Flow JSON
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::SplitVertical => "split-vertical",
+            Self::SplitHorizontal => "split-horizontal",
+            Self::OnionSkin => "onion-skin",
+            Self::Difference => "difference",
+            Self::SideBySide => "side-by-side",
+        }
+    }
+    
+    /// Parse from React Flow JSON
+    pub fn parse(s: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|mode| mode.as_str() == s)
+    }
+}
+
+fn default_compare_split() -> f32 {
+    0.5
+}
+
+/// Image similarity measured by the Compare node
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CompareMetrics {
+    /// Peak signal-to-noise ratio in dB (infinite for identical images)
+    pub psnr: f32,
+    /// Structural similarity index, 1.0 = identical
+    pub ssim: f32,
+}
+
+/// Layer in composition node
+/// Layer N is drawn from the node's Nth content input.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CompositionLayer {
+    pub image: String,
+    pub opacity: f32,               // 0-1
+    pub blend_mode: BlendMode,
+    pub position: (f32, f32),       // Offset in pixels from the top-left
+    pub scale: f32,                 // 1.0 = original size
+}
+
+impl Default for CompositionLayer {
+    fn default() -> Self {
+        Self {
+            image: String::new(),
+            opacity: 1.0,
+            blend_mode: BlendMode::Normal,
+            position: (0.0, 0.0),
+            scale: 1.0,
+        }
+    }
+}
+
+/// Blend modes for composition
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum BlendMode {
+    Normal,
+    Multiply,
+    Screen,
+    Overlay,
+    SoftLight,
+    HardLight,
+    ColorDodge,
+    ColorBurn,
+    Difference,
+    Exclusion,
+}
+
+impl Default for BlendMode {
+    fn default() -> Self {
+        Self::Normal
+    }
+}
+
+impl BlendMode {
+    /// All blend modes, in menu order
+    pub const ALL: [BlendMode; 10] = [
+        Self::Normal,
+        Self::Multiply,
+        Self::Screen,
+        Self::Overlay,
+        Self::SoftLight,
+        Self::HardLight,
+        Self::ColorDodge,
+        Self::ColorBurn,
+        Self::Difference,
+        Self::Exclusion,
+    ];
+    
+    /// Display name
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Normal => "Normal",
+            Self::Multiply => "Multiply",
+            Self::Screen => "Screen",
+            Self::Overlay => "Overlay",
+            Self::SoftLight => "Soft Light",
+            Self::HardLight => "Hard Light",
+            Self::ColorDodge => "Color Dodge",
+            Self::ColorBurn => "Color Burn",
+            Self::Difference => "Difference",
+            Self::Exclusion => "Exclusion",
+        }
+    }
+    
+    /// CSS `mix-blend-mode` name used by the React app
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Normal => "normal",
+            Self::Multiply => "multiply",
+            Self::Screen => "screen",
+            Self::Overlay => "overlay",
+            Self::SoftLight => "soft-light",
+            Self::HardLight => "hard-light",
+            Self::ColorDodge => "color-dodge",
+            Self::ColorBurn => "color-burn",
+            Self::Difference => "difference",
+            Self::Exclusion => "exclusion",
+        }
+    }
+    
+    /// Parse a CSS `mix-blend-mode` name
+    pub fn parse(s: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|mode| mode.as_str() == s)
+    }
+}
+
+impl NodeProperties {
+    pub fn for_type(node_type: NodeType) -> Self {
+        match node_type {
+            NodeType::Image => Self::Image {
+                image: None,
+                thumbnail: None,
+                history: Vec::new(),
+                texture_id: None,
+            },
+            NodeType::Content => Self::Content { content: None },
+            NodeType::Bucket => Self::Bucket { images: Vec::new(), image_ids: Vec::new() },
+            
+            NodeType::Adjust => Self::Adjust {
+                brightness: 0.0,
+                contrast: 0.0,
+                saturation: 0.0,
+                exposure: 0.0,
+                highlights: 0.0,
+                shadows: 0.0,
+                temperature: 0.0,
+                tint: 0.0,
+                vibrance: 0.0,
+                gamma: 0.0,
+                lift: ColorWheel::default(),
+                gamma_wheel: ColorWheel::default(),
+                gain: ColorWheel::default(),
+                offset: ColorWheel::default(),
+                color_boost: 0.0,
+                hue_rotation: 0.0,
+                luminance_mix: 100.0,
+                curves_enabled: false,
+                curves: Curves::default(),
+            },
+            
+            NodeType::Effects => Self::Effects {
+                gaussian_blur: 0.0,
+                blur_edge_mode: EdgeMode::Clamp,
+                directional_blur: 0.0,
+                directional_blur_angle: 0.0,
+                progressive_blur: 0.0,
+                progressive_blur_direction: BlurDirection::Bottom,
+                progressive_blur_falloff: 50.0,
+                glass_blinds: 0.0,
+                glass_blinds_frequency: 10.0,
+                glass_blinds_angle: 0.0,
+                glass_blinds_phase: 0.0,
+                grain: 0.0,
+                grain_size: 2.0,
+                grain_monochrome: true,
+                grain_seed: 0,
+                sharpen: 0.0,
+                vignette: 0.0,
+                vignette_roundness: 50.0,
+                vignette_smoothness: 50.0,
+            },
+            
+            NodeType::Lut => Self::Lut {
+                file_name: String::new(),
+                lut: None,
+                interpolation: LutInterpolation::Tetrahedral,
+                intensity: 100.0,
+            },
+            
+            NodeType::Mask => Self::Mask {
+                source: MaskSource::Luminance,
+                center: [0.5, 0.5],
+                size: [0.5, 0.5],
+                angle: 0.0,
+                invert: false,
+                feather: 0.0,
+                density: 100.0,
+            },
+            
+            NodeType::Text => Self::Text { text: String::new() },
+            NodeType::Concat => Self::Concat { separator: String::new() },
+            NodeType::Splitter => Self::Splitter { delimiter: "\n".to_string() },
+            NodeType::Postit => Self::Postit { 
+                text: String::new(), 
+                color: [1.0, 0.95, 0.6, 1.0] // Yellow
+            },
+            
+            NodeType::Compare => Self::Compare {
+                mode: CompareMode::SplitVertical,
+                split: default_compare_split(),
+                metrics: None,
+            },
+            NodeType::Composition => Self::Composition { layers: Vec::new() },
+            NodeType::Router => Self::Router { active_output: 0 },
+            NodeType::Batch => Self::Batch { items: Vec::new() },
+            NodeType::Title => Self::Title { text: "Title".to_string() },
+            NodeType::Group => Self::Group {},
+            NodeType::Folder => Self::Folder {},
+            NodeType::Convertor => Self::Convertor {},
+            
+            NodeType::Output => Self::Output {
+                name: "output".to_string(),
+                format: ExportFormat::Png,
+                max_size: 0,
+                bit_depth: ExportDepth::Eight,
+                color_space: ColorSpace::Srgb,
+            },
+            
+            NodeType::Omni => Self::Omni {
+                model: "flux-1.1-pro".to_string(),
+                prompt: String::new(),
+                negative_prompt: String::new(),
+                seed: None,
+            },
+            NodeType::Llm => Self::Llm {
+                model: "claude-3-5-sonnet".to_string(),
+                system_prompt: String::new(),
+            },
+            NodeType::Video => Self::Video {
+                model: "veo-3.1-gemini".to_string(),
+                duration: 4,
+                aspect_ratio: "16:9".to_string(),
+            },
+            NodeType::Upscaler => Self::Upscaler {
+                model: "freepik-precision-v2".to_string(),
+                scale: 2,
+            },
+            NodeType::Vector => Self::Vector {},
+            NodeType::Rodin3d => Self::Rodin3d {},
+            NodeType::MindMap => Self::MindMap {},
+        }
+    }
+}
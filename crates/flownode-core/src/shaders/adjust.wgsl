@@ -0,0 +1,75 @@
+// Basic Adjust node settings, factors worked out on the host
+
+struct Params {
+    width: u32,
+    height: u32,
+    brightness: f32,
+    contrast: f32,
+    saturation: f32,
+    exposure: f32,
+    gamma: f32,
+    temperature: f32,
+    tint: f32,
+    vibrance: f32,
+    boost: f32,
+    highlights: f32,
+    shadows: f32,
+    rotate_hue: u32,
+    m00: f32, m01: f32, m02: f32,
+    m10: f32, m11: f32, m12: f32,
+    m20: f32, m21: f32, m22: f32,
+}
+
+@group(0) @binding(0) var<uniform> p: Params;
+
+// Scale a colour's distance from `l`
+fn saturate_by(c: vec3<f32>, l: f32, amount: f32) -> vec3<f32> {
+    return vec3<f32>(l) + (c - vec3<f32>(l)) * amount;
+}
+
+fn saturation_of(c: vec3<f32>) -> f32 {
+    let max_rgb = max(max(c.r, c.g), c.b);
+    let min_rgb = min(min(c.r, c.g), c.b);
+    return select(0.0, (max_rgb - min_rgb) / max_rgb, max_rgb > 0.0);
+}
+
+@compute @workgroup_size(16, 16)
+fn main(@builtin(global_invocation_id) id: vec3<u32>) {
+    if id.x >= p.width || id.y >= p.height {
+        return;
+    }
+    let i = id.y * p.width + id.x;
+    let pixel = src[i];
+    var c = pixel.rgb * p.exposure;
+
+    c.r += p.temperature * 0.1;
+    c.b -= p.temperature * 0.1;
+    c.g += p.tint * 0.05;
+    c += vec3<f32>(p.brightness);
+    c = (c - vec3<f32>(0.5)) * p.contrast + vec3<f32>(0.5);
+
+    let l = luma(c);
+    c = saturate_by(c, l, p.saturation);
+    c = saturate_by(c, l, 1.0 + p.vibrance * (1.0 - saturation_of(c)));
+    if p.boost != 0.0 {
+        c = saturate_by(c, l, max(1.0 + p.boost * clamp(saturation_of(c), 0.0, 1.0), 0.0));
+    }
+
+    if p.rotate_hue != 0u {
+        c = vec3<f32>(
+            p.m00 * c.r + p.m01 * c.g + p.m02 * c.b,
+            p.m10 * c.r + p.m11 * c.g + p.m12 * c.b,
+            p.m20 * c.r + p.m21 * c.g + p.m22 * c.b,
+        );
+    }
+
+    if l > 0.5 {
+        c += vec3<f32>((l - 0.5) * 2.0 * p.highlights);
+    } else {
+        c += vec3<f32>((0.5 - l) * 2.0 * p.shadows);
+    }
+
+    // Gamma, mirrored below zero. Output clamps.
+    c = sign(c) * vec3<f32>(pow0(abs(c.r), p.gamma), pow0(abs(c.g), p.gamma), pow0(abs(c.b), p.gamma));
+    dst[i] = vec4<f32>(c, pixel.a);
+}
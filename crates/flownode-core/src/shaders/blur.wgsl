@@ -0,0 +1,50 @@
+// One axis of a separable blur. The weights are centred, `reach` either side.
+// The first pass premultiplies what it reads, the last unpremultiplies what
+// it writes.
+
+struct Params {
+    width: u32,
+    height: u32,
+    vertical: u32,
+    edge: u32,
+    reach: i32,
+    premultiply: u32,
+    unpremultiply: u32,
+}
+
+@group(0) @binding(0) var<uniform> p: Params;
+@group(0) @binding(3) var<storage, read> weights: array<f32>;
+
+fn read(x: i32, y: i32) -> vec4<f32> {
+    let c = src[u32(y) * p.width + u32(x)];
+    if p.premultiply != 0u {
+        return vec4<f32>(c.rgb * c.a, c.a);
+    }
+    return c;
+}
+
+@compute @workgroup_size(16, 16)
+fn main(@builtin(global_invocation_id) id: vec3<u32>) {
+    if id.x >= p.width || id.y >= p.height {
+        return;
+    }
+    let x = i32(id.x);
+    let y = i32(id.y);
+    var sum = vec4<f32>(0.0);
+    for (var k = -p.reach; k <= p.reach; k++) {
+        let w = weights[u32(k + p.reach)];
+        if p.vertical != 0u {
+            sum += read(x, edge_index(p.edge, y + k, i32(p.height))) * w;
+        } else {
+            sum += read(edge_index(p.edge, x + k, i32(p.width)), y) * w;
+        }
+    }
+    if p.unpremultiply != 0u {
+        if sum.a > 1e-6 {
+            sum = vec4<f32>(sum.rgb / sum.a, sum.a);
+        } else {
+            sum = vec4<f32>(0.0, 0.0, 0.0, sum.a);
+        }
+    }
+    dst[id.y * p.width + id.x] = sum;
+}
@@ -0,0 +1,30 @@
+// Lift/gamma/gain/offset; wheel colours worked out on the host
+
+struct Params {
+    width: u32,
+    height: u32,
+    lift_r: f32, lift_g: f32, lift_b: f32,
+    gamma_r: f32, gamma_g: f32, gamma_b: f32,
+    gain_r: f32, gain_g: f32, gain_b: f32,
+    offset_r: f32, offset_g: f32, offset_b: f32,
+}
+
+@group(0) @binding(0) var<uniform> p: Params;
+
+@compute @workgroup_size(16, 16)
+fn main(@builtin(global_invocation_id) id: vec3<u32>) {
+    if id.x >= p.width || id.y >= p.height {
+        return;
+    }
+    let i = id.y * p.width + id.x;
+    let pixel = src[i];
+    var c = pixel.rgb;
+
+    c += vec3<f32>(p.lift_r, p.lift_g, p.lift_b) * (vec3<f32>(1.0) - c) * 0.5;
+    let mid_weight = vec3<f32>(1.0) - abs(c - vec3<f32>(0.5)) * 2.0;
+    c += vec3<f32>(p.gamma_r, p.gamma_g, p.gamma_b) * max(mid_weight, vec3<f32>(0.0)) * 0.5;
+    c += vec3<f32>(p.gain_r, p.gain_g, p.gain_b) * c * 0.5;
+    c += vec3<f32>(p.offset_r, p.offset_g, p.offset_b) * 0.2;
+
+    dst[i] = vec4<f32>(c, pixel.a);
+}
@@ -0,0 +1,49 @@
+// Shared by every kernel; each kernel file declares its own `Params` at
+// binding 0 and, if it needs one, an extra buffer at binding 3.
+
+@group(0) @binding(1) var<storage, read> src: array<vec4<f32>>;
+@group(0) @binding(2) var<storage, read_write> dst: array<vec4<f32>>;
+
+const EDGE_CLAMP: u32 = 0u;
+const EDGE_MIRROR: u32 = 1u;
+const EDGE_WRAP: u32 = 2u;
+
+// Euclidean remainder, kept to non-negative operands: `%` on negative
+// integers isn't portable across backends
+fn rem_euclid(i: i32, n: i32) -> i32 {
+    let r = abs(i) % n;
+    return select(r, (n - r) % n, i < 0);
+}
+
+// Index of the pixel a filter sees at `i` on a line of `len` pixels
+fn edge_index(edge: u32, i: i32, len: i32) -> i32 {
+    if i >= 0 && i < len {
+        return i;
+    }
+    if edge == EDGE_WRAP {
+        return rem_euclid(i, len);
+    }
+    if edge == EDGE_MIRROR {
+        if len == 1 {
+            return 0;
+        }
+        let period = 2 * (len - 1);
+        let j = rem_euclid(i, period);
+        return select(period - j, j, j < len);
+    }
+    return clamp(i, 0, len - 1);
+}
+
+// Rust's `f32::round`: halves away from zero
+fn round_away(v: f32) -> f32 {
+    return sign(v) * floor(abs(v) + 0.5);
+}
+
+// `powf` for the non-negative bases the kernels use, defined at zero
+fn pow0(x: f32, y: f32) -> f32 {
+    return select(pow(x, y), 0.0, x <= 0.0);
+}
+
+fn luma(c: vec3<f32>) -> f32 {
+    return dot(c, vec3<f32>(0.299, 0.587, 0.114));
+}
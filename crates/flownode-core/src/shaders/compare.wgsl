@@ -0,0 +1,70 @@
+// Two same-sized images (src and `other`) shown for comparison. `width` is
+// the output width, twice the input for side by side.
+
+struct Params {
+    width: u32,
+    height: u32,
+    mode: u32,
+    divider: u32,
+}
+
+@group(0) @binding(0) var<uniform> p: Params;
+@group(0) @binding(3) var<storage, read> other: array<vec4<f32>>;
+
+const SPLIT_VERTICAL: u32 = 0u;
+const SPLIT_HORIZONTAL: u32 = 1u;
+const ONION_SKIN: u32 = 2u;
+const DIFFERENCE: u32 = 3u;
+const SIDE_BY_SIDE: u32 = 4u;
+
+// Black -> blue -> red -> yellow -> white
+fn heat_map(t: f32) -> vec3<f32> {
+    var stops = array<vec3<f32>, 5>(
+        vec3<f32>(0.0, 0.0, 0.0),
+        vec3<f32>(0.0, 0.0, 1.0),
+        vec3<f32>(1.0, 0.0, 0.0),
+        vec3<f32>(1.0, 1.0, 0.0),
+        vec3<f32>(1.0, 1.0, 1.0),
+    );
+    let scaled = clamp(t, 0.0, 1.0) * 4.0;
+    let i = min(u32(scaled), 3u);
+    let f = scaled - f32(i);
+    return stops[i] * (1.0 - f) + stops[i + 1u] * f;
+}
+
+@compute @workgroup_size(16, 16)
+fn main(@builtin(global_invocation_id) id: vec3<u32>) {
+    if id.x >= p.width || id.y >= p.height {
+        return;
+    }
+    let out = id.y * p.width + id.x;
+
+    if p.mode == SIDE_BY_SIDE {
+        let half = p.width / 2u;
+        let i = id.y * half + id.x % half;
+        dst[out] = select(src[i], other[i], id.x >= half);
+        return;
+    }
+
+    let a = src[out];
+    let b = other[out];
+    switch p.mode {
+        case SPLIT_VERTICAL, SPLIT_HORIZONTAL: {
+            let pos = select(id.y, id.x, p.mode == SPLIT_VERTICAL);
+            let distance = max(pos, p.divider) - min(pos, p.divider);
+            if distance <= 1u {
+                dst[out] = vec4<f32>(1.0);
+            } else {
+                dst[out] = select(a, b, pos > p.divider);
+            }
+        }
+        case ONION_SKIN: {
+            dst[out] = (a + b) * 0.5;
+        }
+        default: {
+            let d = abs(a.rgb - b.rgb);
+            let diff = (d.r + d.g + d.b) / 3.0;
+            dst[out] = vec4<f32>(heat_map(min(diff * 4.0, 1.0)), 1.0);
+        }
+    }
+}
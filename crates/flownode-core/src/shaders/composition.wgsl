@@ -0,0 +1,153 @@
+// Composite one layer (`layer`) onto the canvas (src): source-over with a
+// blend mode from the W3C compositing spec
+
+struct Params {
+    width: u32,
+    height: u32,
+    layer_width: u32,
+    layer_height: u32,
+    x0: u32,
+    y0: u32,
+    x1: u32,
+    y1: u32,
+    offset_x: f32,
+    offset_y: f32,
+    scale: f32,
+    opacity: f32,
+    mode: u32,
+}
+
+@group(0) @binding(0) var<uniform> p: Params;
+@group(0) @binding(3) var<storage, read> layer: array<vec4<f32>>;
+
+const NORMAL: u32 = 0u;
+const MULTIPLY: u32 = 1u;
+const SCREEN: u32 = 2u;
+const OVERLAY: u32 = 3u;
+const SOFT_LIGHT: u32 = 4u;
+const HARD_LIGHT: u32 = 5u;
+const COLOR_DODGE: u32 = 6u;
+const COLOR_BURN: u32 = 7u;
+const DIFFERENCE: u32 = 8u;
+const EXCLUSION: u32 = 9u;
+
+fn screen(cb: f32, cs: f32) -> f32 {
+    return cb + cs - cb * cs;
+}
+
+fn hard_light(cb: f32, cs: f32) -> f32 {
+    if cs <= 0.5 {
+        return cb * 2.0 * cs;
+    }
+    return screen(cb, 2.0 * cs - 1.0);
+}
+
+fn blend_channel(mode: u32, cb: f32, cs: f32) -> f32 {
+    switch mode {
+        case MULTIPLY: {
+            return cb * cs;
+        }
+        case SCREEN: {
+            return screen(cb, cs);
+        }
+        case OVERLAY: {
+            return hard_light(cs, cb);
+        }
+        case HARD_LIGHT: {
+            return hard_light(cb, cs);
+        }
+        case SOFT_LIGHT: {
+            if cs <= 0.5 {
+                return cb - (1.0 - 2.0 * cs) * cb * (1.0 - cb);
+            }
+            var d = sqrt(cb);
+            if cb <= 0.25 {
+                d = ((16.0 * cb - 12.0) * cb + 4.0) * cb;
+            }
+            return cb + (2.0 * cs - 1.0) * (d - cb);
+        }
+        case COLOR_DODGE: {
+            if cb <= 0.0 {
+                return 0.0;
+            }
+            if cs >= 1.0 {
+                return 1.0;
+            }
+            return min(cb / (1.0 - cs), 1.0);
+        }
+        case COLOR_BURN: {
+            if cb >= 1.0 {
+                return 1.0;
+            }
+            if cs <= 0.0 {
+                return 0.0;
+            }
+            return 1.0 - min((1.0 - cb) / cs, 1.0);
+        }
+        case DIFFERENCE: {
+            return abs(cb - cs);
+        }
+        case EXCLUSION: {
+            return cb + cs - 2.0 * cb * cs;
+        }
+        default: {
+            return cs;
+        }
+    }
+}
+
+fn layer_pixel(x: u32, y: u32) -> vec4<f32> {
+    return layer[y * p.layer_width + x];
+}
+
+@compute @workgroup_size(16, 16)
+fn main(@builtin(global_invocation_id) id: vec3<u32>) {
+    if id.x >= p.width || id.y >= p.height {
+        return;
+    }
+    let i = id.y * p.width + id.x;
+    let backdrop = src[i];
+    dst[i] = backdrop;
+    if id.x < p.x0 || id.x >= p.x1 || id.y < p.y0 || id.y >= p.y1 {
+        return;
+    }
+
+    // Map the pixel centre back into the layer and sample it bilinearly,
+    // pixel centres at integer positions
+    let max_x = f32(p.layer_width) - 1.0;
+    let max_y = f32(p.layer_height) - 1.0;
+    var sx = (f32(id.x) + 0.5 - p.offset_x) / p.scale - 0.5;
+    var sy = (f32(id.y) + 0.5 - p.offset_y) / p.scale - 0.5;
+    if sx < -0.5 || sy < -0.5 || sx > max_x + 0.5 || sy > max_y + 0.5 {
+        return;
+    }
+    sx = clamp(sx, 0.0, max_x);
+    sy = clamp(sy, 0.0, max_y);
+    let x0 = u32(floor(sx));
+    let y0 = u32(floor(sy));
+    let x1 = min(x0 + 1u, p.layer_width - 1u);
+    let y1 = min(y0 + 1u, p.layer_height - 1u);
+    let fx = sx - f32(x0);
+    let fy = sy - f32(y0);
+    let top = layer_pixel(x0, y0) * (1.0 - fx) + layer_pixel(x1, y0) * fx;
+    let bottom = layer_pixel(x0, y1) * (1.0 - fx) + layer_pixel(x1, y1) * fx;
+    let source = top * (1.0 - fy) + bottom * fy;
+
+    let alpha_s = source.a * p.opacity;
+    let alpha_b = backdrop.a;
+    let alpha_o = alpha_s + alpha_b * (1.0 - alpha_s);
+    if alpha_o <= 0.0 {
+        dst[i] = vec4<f32>(0.0);
+        return;
+    }
+
+    var color: vec3<f32>;
+    for (var c = 0; c < 3; c++) {
+        let cb = backdrop[c];
+        let cs = source[c];
+        // Where the backdrop is transparent the source shows unblended
+        let blended = (1.0 - alpha_b) * cs + alpha_b * blend_channel(p.mode, cb, cs);
+        color[c] = (alpha_s * blended + alpha_b * cb * (1.0 - alpha_s)) / alpha_o;
+    }
+    dst[i] = vec4<f32>(color, alpha_o);
+}
@@ -0,0 +1,35 @@
+// Per-channel curves then the master curve, as tables of `size` entries:
+// master, red, green, blue one after another
+
+struct Params {
+    width: u32,
+    height: u32,
+    size: u32,
+}
+
+@group(0) @binding(0) var<uniform> p: Params;
+@group(0) @binding(3) var<storage, read> tables: array<f32>;
+
+// Look up a 0-1 value in table `t`, interpolating linearly
+fn sample_table(t: u32, v: f32) -> f32 {
+    let pos = clamp(v, 0.0, 1.0) * f32(p.size - 1u);
+    let i = min(u32(pos), p.size - 2u);
+    let f = pos - f32(i);
+    let base = t * p.size + i;
+    return tables[base] + (tables[base + 1u] - tables[base]) * f;
+}
+
+@compute @workgroup_size(16, 16)
+fn main(@builtin(global_invocation_id) id: vec3<u32>) {
+    if id.x >= p.width || id.y >= p.height {
+        return;
+    }
+    let i = id.y * p.width + id.x;
+    let pixel = src[i];
+    dst[i] = vec4<f32>(
+        sample_table(0u, sample_table(1u, pixel.r)),
+        sample_table(0u, sample_table(2u, pixel.g)),
+        sample_table(0u, sample_table(3u, pixel.b)),
+        pixel.a,
+    );
+}
@@ -0,0 +1,32 @@
+// Motion blur: `2 * samples + 1` taps along (dx, dy), colour premultiplied
+
+struct Params {
+    width: u32,
+    height: u32,
+    edge: u32,
+    samples: i32,
+    dx: f32,
+    dy: f32,
+}
+
+@group(0) @binding(0) var<uniform> p: Params;
+
+@compute @workgroup_size(16, 16)
+fn main(@builtin(global_invocation_id) id: vec3<u32>) {
+    if id.x >= p.width || id.y >= p.height {
+        return;
+    }
+    var sum = vec4<f32>(0.0);
+    for (var i = -p.samples; i <= p.samples; i++) {
+        let sx = edge_index(p.edge, i32(round_away(f32(id.x) + p.dx * f32(i))), i32(p.width));
+        let sy = edge_index(p.edge, i32(round_away(f32(id.y) + p.dy * f32(i))), i32(p.height));
+        let c = src[u32(sy) * p.width + u32(sx)];
+        sum += vec4<f32>(c.rgb * c.a, c.a);
+    }
+    let count = f32(2 * p.samples + 1);
+    var out = vec4<f32>(0.0, 0.0, 0.0, sum.a / count);
+    if sum.a > 1e-6 {
+        out = vec4<f32>(sum.rgb / sum.a, sum.a / count);
+    }
+    dst[id.y * p.width + id.x] = out;
+}
@@ -0,0 +1,29 @@
+// Wave distortion across stripes at an angle; alpha stays in place
+
+struct Params {
+    width: u32,
+    height: u32,
+    intensity: f32,
+    frequency: f32,
+    phase: f32,
+    cos_a: f32,
+    sin_a: f32,
+}
+
+@group(0) @binding(0) var<uniform> p: Params;
+
+const TAU: f32 = 6.2831855;
+
+@compute @workgroup_size(16, 16)
+fn main(@builtin(global_invocation_id) id: vec3<u32>) {
+    if id.x >= p.width || id.y >= p.height {
+        return;
+    }
+    let rx = f32(id.x) * p.cos_a + f32(id.y) * p.sin_a;
+    let wave = f32(i32(sin(rx * p.frequency / 100.0 + p.phase * TAU) * p.intensity * 20.0));
+    let sx = clamp(i32(id.x) - i32(wave * p.sin_a), 0, i32(p.width) - 1);
+    let sy = clamp(i32(id.y) + i32(wave * p.cos_a), 0, i32(p.height) - 1);
+
+    let i = id.y * p.width + id.x;
+    dst[i] = vec4<f32>(src[u32(sy) * p.width + u32(sx)].rgb, src[i].a);
+}
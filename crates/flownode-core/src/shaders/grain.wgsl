@@ -0,0 +1,23 @@
+// Add per-block noise drawn on the host, so a seed gives the same grain
+// as on the CPU
+
+struct Params {
+    width: u32,
+    height: u32,
+    block_size: u32,
+    blocks_x: u32,
+}
+
+@group(0) @binding(0) var<uniform> p: Params;
+@group(0) @binding(3) var<storage, read> noise: array<vec4<f32>>;
+
+@compute @workgroup_size(16, 16)
+fn main(@builtin(global_invocation_id) id: vec3<u32>) {
+    if id.x >= p.width || id.y >= p.height {
+        return;
+    }
+    let i = id.y * p.width + id.x;
+    let pixel = src[i];
+    let offset = noise[id.y / p.block_size * p.blocks_x + id.x / p.block_size];
+    dst[i] = vec4<f32>(pixel.rgb + offset.rgb, pixel.a);
+}
@@ -0,0 +1,23 @@
+// Blend the adjusted image's luminance (src) back towards the original's
+
+struct Params {
+    width: u32,
+    height: u32,
+    mix: f32,
+}
+
+@group(0) @binding(0) var<uniform> p: Params;
+@group(0) @binding(3) var<storage, read> original: array<vec4<f32>>;
+
+@compute @workgroup_size(16, 16)
+fn main(@builtin(global_invocation_id) id: vec3<u32>) {
+    if id.x >= p.width || id.y >= p.height {
+        return;
+    }
+    let i = id.y * p.width + id.x;
+    let pixel = src[i];
+    let original_luma = luma(original[i].rgb);
+    let adjusted_luma = luma(pixel.rgb);
+    let shift = (original_luma + (adjusted_luma - original_luma) * p.mix) - adjusted_luma;
+    dst[i] = vec4<f32>(pixel.rgb + vec3<f32>(shift), pixel.a);
+}
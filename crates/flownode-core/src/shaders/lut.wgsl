@@ -0,0 +1,117 @@
+// 1D or 3D lookup table, three floats per entry (red fastest for 3D)
+
+struct Params {
+    width: u32,
+    height: u32,
+    three_d: u32,
+    tetrahedral: u32,
+    size: u32,
+    intensity: f32,
+    min_r: f32, min_g: f32, min_b: f32,
+    max_r: f32, max_g: f32, max_b: f32,
+}
+
+@group(0) @binding(0) var<uniform> p: Params;
+@group(0) @binding(3) var<storage, read> table: array<f32>;
+
+fn entry(i: u32) -> vec3<f32> {
+    return vec3<f32>(table[i * 3u], table[i * 3u + 1u], table[i * 3u + 2u]);
+}
+
+fn at(r: u32, g: u32, b: u32) -> vec3<f32> {
+    return entry(r + g * p.size + b * p.size * p.size);
+}
+
+fn one_d(pos: vec3<f32>) -> vec3<f32> {
+    var out: vec3<f32>;
+    for (var c = 0; c < 3; c++) {
+        let i0 = min(u32(floor(pos[c])), p.size - 2u);
+        let f = pos[c] - f32(i0);
+        let a = entry(i0)[c];
+        out[c] = a + (entry(i0 + 1u)[c] - a) * f;
+    }
+    return out;
+}
+
+fn trilinear(base: vec3<u32>, f: vec3<f32>) -> vec3<f32> {
+    let r = base.x;
+    let g = base.y;
+    let b = base.z;
+    let c00 = mix(at(r, g, b), at(r + 1u, g, b), f.x);
+    let c10 = mix(at(r, g + 1u, b), at(r + 1u, g + 1u, b), f.x);
+    let c01 = mix(at(r, g, b + 1u), at(r + 1u, g, b + 1u), f.x);
+    let c11 = mix(at(r, g + 1u, b + 1u), at(r + 1u, g + 1u, b + 1u), f.x);
+    return mix(mix(c00, c10, f.y), mix(c01, c11, f.y), f.z);
+}
+
+// Split the cube cell into six tetrahedra along the main diagonal
+fn tetrahedral(base: vec3<u32>, f: vec3<f32>) -> vec3<f32> {
+    let r = base.x;
+    let g = base.y;
+    let b = base.z;
+    var w: vec3<f32>;
+    var v1: vec3<f32>;
+    var v2: vec3<f32>;
+    if f.x > f.y {
+        if f.y > f.z {
+            w = vec3<f32>(f.x, f.y, f.z);
+            v1 = at(r + 1u, g, b);
+            v2 = at(r + 1u, g + 1u, b);
+        } else if f.x > f.z {
+            w = vec3<f32>(f.x, f.z, f.y);
+            v1 = at(r + 1u, g, b);
+            v2 = at(r + 1u, g, b + 1u);
+        } else {
+            w = vec3<f32>(f.z, f.x, f.y);
+            v1 = at(r, g, b + 1u);
+            v2 = at(r + 1u, g, b + 1u);
+        }
+    } else if f.z > f.y {
+        w = vec3<f32>(f.z, f.y, f.x);
+        v1 = at(r, g, b + 1u);
+        v2 = at(r, g + 1u, b + 1u);
+    } else if f.z > f.x {
+        w = vec3<f32>(f.y, f.z, f.x);
+        v1 = at(r, g + 1u, b);
+        v2 = at(r, g + 1u, b + 1u);
+    } else {
+        w = vec3<f32>(f.y, f.x, f.z);
+        v1 = at(r, g + 1u, b);
+        v2 = at(r + 1u, g + 1u, b);
+    }
+    return (1.0 - w.x) * at(r, g, b)
+        + (w.x - w.y) * v1
+        + (w.y - w.z) * v2
+        + w.z * at(r + 1u, g + 1u, b + 1u);
+}
+
+@compute @workgroup_size(16, 16)
+fn main(@builtin(global_invocation_id) id: vec3<u32>) {
+    if id.x >= p.width || id.y >= p.height {
+        return;
+    }
+    let i = id.y * p.width + id.x;
+    let pixel = src[i];
+
+    // Table coordinates 0..size-1
+    let domain_min = vec3<f32>(p.min_r, p.min_g, p.min_b);
+    let domain_max = vec3<f32>(p.max_r, p.max_g, p.max_b);
+    let t = (pixel.rgb - domain_min) / (domain_max - domain_min);
+    let pos = clamp(t, vec3<f32>(0.0), vec3<f32>(1.0)) * f32(p.size - 1u);
+
+    var mapped: vec3<f32>;
+    if p.three_d == 0u {
+        mapped = one_d(pos);
+    } else {
+        let base = min(vec3<u32>(floor(pos)), vec3<u32>(p.size - 2u));
+        let f = pos - vec3<f32>(base);
+        if p.tetrahedral != 0u {
+            mapped = tetrahedral(base, f);
+        } else {
+            mapped = trilinear(base, f);
+        }
+    }
+
+    let c = pixel.rgb + (mapped - pixel.rgb) * p.intensity;
+    dst[i] = vec4<f32>(c, pixel.a);
+}
@@ -0,0 +1,22 @@
+// Mix from the original (start of the extra buffer) to the processed image
+// (src) by the mask, packed four values to a vec4 after the original
+
+struct Params {
+    width: u32,
+    height: u32,
+}
+
+@group(0) @binding(0) var<uniform> p: Params;
+@group(0) @binding(3) var<storage, read> extra: array<vec4<f32>>;
+
+@compute @workgroup_size(16, 16)
+fn main(@builtin(global_invocation_id) id: vec3<u32>) {
+    if id.x >= p.width || id.y >= p.height {
+        return;
+    }
+    let i = id.y * p.width + id.x;
+    let pixels = p.width * p.height;
+    let m = extra[pixels + i / 4u][i % 4u];
+    let original = extra[i];
+    dst[i] = original + (src[i] - original) * m;
+}
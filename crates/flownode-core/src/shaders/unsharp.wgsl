@@ -0,0 +1,21 @@
+// Unsharp mask: original + amount * (original - blurred)
+
+struct Params {
+    width: u32,
+    height: u32,
+    amount: f32,
+}
+
+@group(0) @binding(0) var<uniform> p: Params;
+@group(0) @binding(3) var<storage, read> blurred: array<vec4<f32>>;
+
+@compute @workgroup_size(16, 16)
+fn main(@builtin(global_invocation_id) id: vec3<u32>) {
+    if id.x >= p.width || id.y >= p.height {
+        return;
+    }
+    let i = id.y * p.width + id.x;
+    let original = src[i];
+    let sharpened = original + (original - blurred[i]) * p.amount;
+    dst[i] = vec4<f32>(sharpened.rgb, original.a);
+}
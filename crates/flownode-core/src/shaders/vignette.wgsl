@@ -0,0 +1,35 @@
+// Darken with distance from the centre
+
+struct Params {
+    width: u32,
+    height: u32,
+    intensity: f32,
+    smoothness: f32,
+    cx: f32,
+    cy: f32,
+    max_dist: f32,
+    x_scale: f32,
+}
+
+@group(0) @binding(0) var<uniform> p: Params;
+
+@compute @workgroup_size(16, 16)
+fn main(@builtin(global_invocation_id) id: vec3<u32>) {
+    if id.x >= p.width || id.y >= p.height {
+        return;
+    }
+    let dx = (f32(id.x) - p.cx) / p.x_scale;
+    let dy = f32(id.y) - p.cy;
+    let dist = sqrt(dx * dx + dy * dy) / p.max_dist;
+
+    let falloff_start = 0.3 + p.smoothness * 0.4;
+    var vignette = 1.0;
+    if dist >= falloff_start {
+        let t = (dist - falloff_start) / (1.0 - falloff_start);
+        vignette = 1.0 - pow0(t, 2.0 - p.smoothness) * p.intensity;
+    }
+
+    let i = id.y * p.width + id.x;
+    let pixel = src[i];
+    dst[i] = vec4<f32>(pixel.rgb * vignette, pixel.a);
+}
@@ -0,0 +1,94 @@
+//! Minimal zip archive writer for exporting batches of outputs
+//!
+//! Files are stored without compression: the outputs are already compressed
+//! images, so deflating them again would cost time for little gain.
+
+/// Zip "version needed to extract" for stored entries
+const VERSION: u16 = 20;
+
+/// Flag bit 11: file names are UTF-8
+const UTF8_NAMES: u16 = 1 << 11;
+
+/// DOS date for 1980-01-01, the earliest a zip can record
+const DOS_DATE: u16 = (1 << 5) | 1;
+
+/// Build a zip archive holding `files` as (name, contents) pairs
+pub fn write_zip(files: &[(String, Vec<u8>)]) -> Result<Vec<u8>, String> {
+    if files.len() > u16::MAX as usize {
+        return Err(format!("Too many files for a zip archive ({})", files.len()));
+    }
+
+    let mut out = Vec::new();
+    let mut central = Vec::new();
+
+    for (name, data) in files {
+        let offset = u32::try_from(out.len())
+            .map_err(|_| "Zip archive larger than 4 GB".to_string())?;
+        let size = u32::try_from(data.len())
+            .map_err(|_| format!("{} is larger than 4 GB", name))?;
+        let crc = crc32fast::hash(data);
+        let name_len = u16::try_from(name.len())
+            .map_err(|_| format!("File name too long for a zip archive: {}", name))?;
+
+        // Local file header
+        put_u32(&mut out, 0x0403_4b50);
+        put_u16(&mut out, VERSION);
+        put_u16(&mut out, UTF8_NAMES);
+        put_u16(&mut out, 0); // stored
+        put_u16(&mut out, 0); // time
+        put_u16(&mut out, DOS_DATE);
+        put_u32(&mut out, crc);
+        put_u32(&mut out, size);
+        put_u32(&mut out, size);
+        put_u16(&mut out, name_len);
+        put_u16(&mut out, 0); // extra field
+        out.extend_from_slice(name.as_bytes());
+        out.extend_from_slice(data);
+
+        // Central directory entry
+        put_u32(&mut central, 0x0201_4b50);
+        put_u16(&mut central, VERSION);
+        put_u16(&mut central, VERSION);
+        put_u16(&mut central, UTF8_NAMES);
+        put_u16(&mut central, 0);
+        put_u16(&mut central, 0);
+        put_u16(&mut central, DOS_DATE);
+        put_u32(&mut central, crc);
+        put_u32(&mut central, size);
+        put_u32(&mut central, size);
+        put_u16(&mut central, name_len);
+        put_u16(&mut central, 0); // extra field
+        put_u16(&mut central, 0); // comment
+        put_u16(&mut central, 0); // disk
+        put_u16(&mut central, 0); // internal attributes
+        put_u32(&mut central, 0); // external attributes
+        put_u32(&mut central, offset);
+        central.extend_from_slice(name.as_bytes());
+    }
+
+    let central_offset = u32::try_from(out.len())
+        .map_err(|_| "Zip archive larger than 4 GB".to_string())?;
+    let central_size = u32::try_from(central.len())
+        .map_err(|_| "Zip archive larger than 4 GB".to_string())?;
+    out.extend_from_slice(&central);
+
+    // End of central directory
+    put_u32(&mut out, 0x0605_4b50);
+    put_u16(&mut out, 0);
+    put_u16(&mut out, 0);
+    put_u16(&mut out, files.len() as u16);
+    put_u16(&mut out, files.len() as u16);
+    put_u32(&mut out, central_size);
+    put_u32(&mut out, central_offset);
+    put_u16(&mut out, 0); // comment
+
+    Ok(out)
+}
+
+fn put_u16(out: &mut Vec<u8>, value: u16) {
+    out.extend_from_slice(&value.to_le_bytes());
+}
+
+fn put_u32(out: &mut Vec<u8>, value: u32) {
+    out.extend_from_slice(&value.to_le_bytes());
+}
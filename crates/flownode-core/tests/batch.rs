@@ -0,0 +1,118 @@
+//! Batches: nodes fed a batch run once per item, wires only take batches of
+//! the input's type, and batch outputs export as a zip archive
+
+mod common;
+
+use std::collections::HashMap;
+use std::io::{Cursor, Read};
+
+use common::{chain, first_pixel, node};
+use flownode_core::color::WorkingSpace;
+use flownode_core::executor::Executor;
+use flownode_core::graph::NodeGraph;
+use flownode_core::image_data::ImageData;
+use flownode_core::nodes::NodeType;
+use flownode_core::zip::write_zip;
+use uuid::Uuid;
+
+/// Colour of the 1×1 test image with the given ID
+fn colour(id: u64) -> [u8; 4] {
+    [id as u8 * 40, 255 - id as u8 * 40, 100, 255]
+}
+
+fn inputs() -> HashMap<u64, ImageData> {
+    (1..=5).map(|id| (id, ImageData::solid(1, 1, colour(id)))).collect()
+}
+
+fn add(graph: &mut NodeGraph, node_type: NodeType) -> Uuid {
+    common::add(graph, node(node_type))
+}
+
+/// Bucket holding the images with `image_ids`
+fn add_bucket(graph: &mut NodeGraph, image_ids: &[u64]) -> Uuid {
+    let bucket = add(graph, NodeType::Bucket);
+    for image_id in image_ids {
+        graph.set_node_image(bucket, *image_id);
+    }
+    bucket
+}
+
+/// First pixel of each image an Output node produced
+fn run(graph: &NodeGraph, output_id: Uuid) -> Vec<[u8; 4]> {
+    let mut results = Executor::new().execute(graph, &inputs()).unwrap();
+    results.remove(&output_id).unwrap_or_default().iter().map(first_pixel).collect()
+}
+
+#[test]
+fn nodes_run_once_per_bucket_item() {
+    // Bucket of three → Adjust → Output
+    let bucket = node(NodeType::Bucket);
+    let bucket_id = bucket.id;
+    let (mut graph, output) = chain(&[bucket, node(NodeType::Adjust)]);
+    graph.working_space = WorkingSpace::Srgb;
+    for image_id in [1, 2, 3] {
+        graph.set_node_image(bucket_id, image_id);
+    }
+
+    assert_eq!(run(&graph, output), vec![colour(1), colour(2), colour(3)]);
+}
+
+#[test]
+fn two_batches_run_for_the_shorter() {
+    // Buckets of three and two into Compare; only pairs that exist run
+    let mut graph = NodeGraph::new();
+    graph.working_space = WorkingSpace::Srgb;
+    let long = add_bucket(&mut graph, &[1, 2, 3]);
+    let short = add_bucket(&mut graph, &[4, 5]);
+    let compare = add(&mut graph, NodeType::Compare);
+    let output = add(&mut graph, NodeType::Output);
+    graph.add_connection(long, 0, compare, 0).unwrap();
+    graph.add_connection(short, 0, compare, 1).unwrap();
+    graph.add_connection(compare, 0, output, 0).unwrap();
+
+    assert_eq!(run(&graph, output).len(), 2);
+}
+
+#[test]
+fn batches_only_feed_inputs_of_their_item_type() {
+    let mut graph = NodeGraph::new();
+    let bucket = add(&mut graph, NodeType::Bucket);
+    let batch = add(&mut graph, NodeType::Batch);
+    let adjust = add(&mut graph, NodeType::Adjust);
+    let concat = add(&mut graph, NodeType::Concat);
+
+    // Image batches feed content inputs, text batches text inputs
+    assert!(graph.can_connect(bucket, 0, adjust, 0).is_ok());
+    assert!(graph.can_connect(batch, 0, concat, 0).is_ok());
+
+    // And not the other way round, nor a mask input
+    assert!(graph.add_connection(batch, 0, adjust, 0).is_err());
+    assert!(graph.add_connection(bucket, 0, concat, 0).is_err());
+    assert!(graph.add_connection(bucket, 0, adjust, 1).is_err());
+}
+
+#[test]
+fn zip_archives_read_back() {
+    let files = vec![
+        ("output-1.png".to_string(), vec![1, 2, 3]),
+        ("output-2 ✓.png".to_string(), Vec::new()),
+        ("output-3.png".to_string(), (0..=255).collect()),
+    ];
+    let bytes = write_zip(&files).unwrap();
+
+    let mut archive = zip::ZipArchive::new(Cursor::new(bytes)).unwrap();
+    assert_eq!(archive.len(), files.len());
+    for (i, (name, data)) in files.iter().enumerate() {
+        let mut file = archive.by_index(i).unwrap();
+        assert_eq!(file.name(), name);
+        let mut contents = Vec::new();
+        file.read_to_end(&mut contents).unwrap();
+        assert_eq!(&contents, data);
+    }
+}
+
+#[test]
+fn zip_names_too_long_are_rejected() {
+    let name = "a".repeat(u16::MAX as usize + 1);
+    assert!(write_zip(&[(name, vec![0])]).is_err());
+}
@@ -0,0 +1,131 @@
+//! Gaussian blur checked against a direct convolution with a sampled Gaussian
+
+use flownode_core::blur::{edge_index, gaussian_blur};
+use flownode_core::image_data::ImageData;
+use flownode_core::nodes::EdgeMode;
+
+/// Stacked boxes approximate the Gaussian; this is the worst error allowed
+/// next to a hard 0-1 edge
+const TOLERANCE: f32 = 0.03;
+
+/// Opaque image with hard edges and noise, the hardest case for a blur
+fn test_image(width: u32, height: u32) -> ImageData {
+    let mut seed = 0x2545_f491_u32;
+    let mut pixels = Vec::with_capacity((width * height * 4) as usize);
+    for y in 0..height {
+        for x in 0..width {
+            seed ^= seed << 13;
+            seed ^= seed >> 17;
+            seed ^= seed << 5;
+            let noise = (seed % 1000) as f32 / 1000.0;
+            let block = if (x / 7 + y / 5) % 2 == 0 { 1.0 } else { 0.0 };
+            pixels.extend_from_slice(&[block, noise, x as f32 / width as f32, 1.0]);
+        }
+    }
+    ImageData::from_f32(pixels, width, height)
+}
+
+/// Separable convolution with the Gaussian sampled out to 4σ
+fn reference_blur(img: &ImageData, sigma: f32, edge: EdgeMode) -> Vec<f32> {
+    let reach = (sigma * 4.0).ceil() as isize;
+    let mut kernel: Vec<f32> = (-reach..=reach)
+        .map(|k| (-(k * k) as f32 / (2.0 * sigma * sigma)).exp())
+        .collect();
+    let total: f32 = kernel.iter().sum();
+    kernel.iter_mut().for_each(|k| *k /= total);
+
+    let (width, height) = (img.width as usize, img.height as usize);
+    let source = img.to_f32();
+    let mut temp = vec![0.0f32; source.len()];
+    let mut output = vec![0.0f32; source.len()];
+
+    for y in 0..height {
+        for x in 0..width {
+            for (k, weight) in (-reach..=reach).zip(&kernel) {
+                let sx = edge_index(edge, x as isize + k, width);
+                for c in 0..4 {
+                    temp[(y * width + x) * 4 + c] += source[(y * width + sx) * 4 + c] * weight;
+                }
+            }
+        }
+    }
+    for y in 0..height {
+        for x in 0..width {
+            for (k, weight) in (-reach..=reach).zip(&kernel) {
+                let sy = edge_index(edge, y as isize + k, height);
+                for c in 0..4 {
+                    output[(y * width + x) * 4 + c] += temp[(sy * width + x) * 4 + c] * weight;
+                }
+            }
+        }
+    }
+    output
+}
+
+fn max_difference(a: &[f32], b: &[f32]) -> f32 {
+    a.iter().zip(b).map(|(a, b)| (a - b).abs()).fold(0.0, f32::max)
+}
+
+#[test]
+fn matches_reference_convolution() {
+    let img = test_image(48, 32);
+    for edge in EdgeMode::ALL {
+        // Below a pixel the sampled reference isn't a Gaussian either
+        for sigma in [1.0, 1.5, 3.3, 8.0, 20.0] {
+            let blurred = gaussian_blur(&img, sigma, edge).to_f32();
+            let expected = reference_blur(&img, sigma, edge);
+            let error = max_difference(&blurred, &expected);
+            assert!(error < TOLERANCE, "{} σ={}: max error {}", edge.name(), sigma, error);
+        }
+    }
+}
+
+#[test]
+fn sub_pixel_radii_change_smoothly() {
+    let img = test_image(32, 32);
+    let steps: Vec<Vec<f32>> = [1.0, 1.1, 1.2]
+        .iter()
+        .map(|&sigma| gaussian_blur(&img, sigma, EdgeMode::Clamp).to_f32())
+        .collect();
+    let first = max_difference(&steps[0], &steps[1]);
+    let second = max_difference(&steps[1], &steps[2]);
+    assert!(first > 0.0 && second > 0.0, "sub-pixel steps should change the result");
+    assert!(first < 0.05 && second < 0.05, "sub-pixel steps should change it only a little");
+}
+
+#[test]
+fn transparent_pixels_do_not_bleed_colour() {
+    // Left half opaque blue, right half fully transparent red
+    let (width, height) = (16u32, 4u32);
+    let mut pixels = Vec::new();
+    for _ in 0..height {
+        for x in 0..width {
+            let pixel = if x < width / 2 { [0.0, 0.0, 1.0, 1.0] } else { [1.0, 0.0, 0.0, 0.0] };
+            pixels.extend_from_slice(&pixel);
+        }
+    }
+    let img = ImageData::from_f32(pixels, width, height);
+
+    let blurred = gaussian_blur(&img, 3.0, EdgeMode::Clamp);
+    for y in 0..height {
+        for x in 0..width {
+            let [r, _, b, a] = blurred.get_pixel(x, y);
+            if a > 0.01 {
+                assert!(r < 1e-3 && b > 0.999, "pixel ({}, {}) picked up red: {:?}", x, y, [r, b, a]);
+            }
+        }
+    }
+    // Alpha itself is blurred across the edge
+    let [.., a] = blurred.get_pixel(width / 2, 0);
+    assert!(a > 0.1 && a < 0.9);
+}
+
+#[test]
+fn edge_modes_index_as_documented() {
+    let clamp: Vec<usize> = (-3..8).map(|i| edge_index(EdgeMode::Clamp, i, 5)).collect();
+    let mirror: Vec<usize> = (-3..8).map(|i| edge_index(EdgeMode::Mirror, i, 5)).collect();
+    let wrap: Vec<usize> = (-3..8).map(|i| edge_index(EdgeMode::Wrap, i, 5)).collect();
+    assert_eq!(clamp, [0, 0, 0, 0, 1, 2, 3, 4, 4, 4, 4]);
+    assert_eq!(mirror, [3, 2, 1, 0, 1, 2, 3, 4, 3, 2, 1]);
+    assert_eq!(wrap, [2, 3, 4, 0, 1, 2, 3, 4, 0, 1, 2]);
+}
@@ -0,0 +1,149 @@
+//! Outputs are cached across runs; an edit recomputes the edited node and
+//! everything downstream of it, and nothing else
+
+mod common;
+
+use std::collections::HashMap;
+
+use common::{add, node, IMAGE};
+use flownode_core::executor::Executor;
+use flownode_core::graph::NodeGraph;
+use flownode_core::image_data::ImageData;
+use flownode_core::lut::{Lut, LutKind, LutStore};
+use flownode_core::nodes::{NodeProperties, NodeType};
+use uuid::Uuid;
+
+/// Second input image, for the LUT branch
+const OTHER_IMAGE: u64 = 2;
+
+/// Identity 2³ table
+fn identity_lut() -> Lut {
+    let mut table = Vec::new();
+    for b in 0..2 {
+        for g in 0..2 {
+            for r in 0..2 {
+                table.push([r as f32, g as f32, b as f32]);
+            }
+        }
+    }
+    Lut { title: "Identity".to_string(), kind: LutKind::ThreeD, size: 2, domain_min: [0.0; 3], domain_max: [1.0; 3], table }
+}
+
+/// Two independent branches:
+/// Image → Adjust → Effects → Output, and Image → LUT → Output
+struct Graph {
+    graph: NodeGraph,
+    image: Uuid,
+    adjust: Uuid,
+    effects: Uuid,
+    other_image: Uuid,
+    lut: Uuid,
+}
+
+impl Graph {
+    fn new() -> Self {
+        let mut graph = NodeGraph::new();
+        let image = add(&mut graph, node(NodeType::Image));
+        let adjust = add(&mut graph, node(NodeType::Adjust));
+        let effects = add(&mut graph, node(NodeType::Effects));
+        let output = add(&mut graph, node(NodeType::Output));
+        graph.set_node_image(image, IMAGE);
+        for (from, to) in [(image, adjust), (adjust, effects), (effects, output)] {
+            graph.add_connection(from, 0, to, 0).unwrap();
+        }
+
+        let other_image = add(&mut graph, node(NodeType::Image));
+        let lut = add(&mut graph, node(NodeType::Lut));
+        let other_output = add(&mut graph, node(NodeType::Output));
+        graph.set_node_image(other_image, OTHER_IMAGE);
+        assert!(graph.set_node_lut(lut, "identity.cube", identity_lut()));
+        graph.add_connection(other_image, 0, lut, 0).unwrap();
+        graph.add_connection(lut, 0, other_output, 0).unwrap();
+
+        Self { graph, image, adjust, effects, other_image, lut }
+    }
+
+    fn properties(&mut self, node_id: Uuid) -> &mut NodeProperties {
+        &mut self.graph.nodes.get_mut(&node_id).unwrap().properties
+    }
+}
+
+fn inputs() -> HashMap<u64, ImageData> {
+    (1..=3).map(|id| (id, ImageData::solid(4, 4, [id as u8 * 60, 100, 200, 255]))).collect()
+}
+
+/// Run the graph and return how many nodes were (cached, computed)
+fn run(executor: &mut Executor, graph: &Graph) -> (usize, usize) {
+    executor.execute(&graph.graph, &inputs()).unwrap();
+    (executor.stats().cached, executor.stats().computed)
+}
+
+/// Executor that has run `graph` once
+fn warm(graph: &Graph) -> Executor {
+    let mut executor = Executor::new();
+    assert_eq!(run(&mut executor, graph), (0, 7));
+    executor
+}
+
+#[test]
+fn unchanged_graphs_are_all_cached() {
+    let graph = Graph::new();
+    let mut executor = warm(&graph);
+    assert_eq!(run(&mut executor, &graph), (7, 0));
+}
+
+#[test]
+fn editing_a_downstream_node_keeps_upstream_cached() {
+    let mut graph = Graph::new();
+    let mut executor = warm(&graph);
+    if let NodeProperties::Effects { grain, .. } = graph.properties(graph.effects) {
+        *grain = 20.0;
+    }
+    // Effects and its Output
+    assert_eq!(run(&mut executor, &graph), (5, 2));
+}
+
+#[test]
+fn property_changes_recompute_downstream() {
+    let mut graph = Graph::new();
+    let mut executor = warm(&graph);
+    if let NodeProperties::Adjust { brightness, .. } = graph.properties(graph.adjust) {
+        *brightness = 30.0;
+    }
+    // Adjust, Effects and their Output
+    assert_eq!(run(&mut executor, &graph), (4, 3));
+}
+
+#[test]
+fn connection_changes_recompute_downstream() {
+    let mut graph = Graph::new();
+    let mut executor = warm(&graph);
+    graph.graph.delete_connection(graph.adjust, 0, graph.effects, 0);
+    graph.graph.add_connection(graph.image, 0, graph.effects, 0).unwrap();
+    // Effects and its Output; Adjust is unchanged though nothing reads it now
+    assert_eq!(run(&mut executor, &graph), (5, 2));
+}
+
+#[test]
+fn image_changes_recompute_downstream() {
+    let mut graph = Graph::new();
+    let mut executor = warm(&graph);
+    graph.graph.set_node_image(graph.other_image, 3);
+    // The second Image, the LUT and their Output
+    assert_eq!(run(&mut executor, &graph), (4, 3));
+}
+
+#[test]
+fn lut_presence_changes_recompute_downstream() {
+    let mut graph = Graph::new();
+    let mut executor = warm(&graph);
+    let lut = graph.graph.node_lut(graph.lut).unwrap().clone();
+
+    // The table goes missing, as when a project is loaded without it
+    graph.graph.luts = LutStore::default();
+    assert_eq!(run(&mut executor, &graph), (5, 2));
+
+    // And comes back
+    graph.graph.luts.insert(lut);
+    assert_eq!(run(&mut executor, &graph), (5, 2));
+}
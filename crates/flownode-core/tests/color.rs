@@ -0,0 +1,112 @@
+//! Colour management: ICC profile detection, profile embedding on export,
+//! working spaces and keeping out-of-range values until Output
+
+mod common;
+
+use common::{chain, input, node, node_with, run_first};
+use flownode_core::color::{self, ColorSpace, WorkingSpace};
+use flownode_core::graph::NodeGraph;
+use flownode_core::image_data::{decode_image, encode_png, ImageData, ImageLimits};
+use flownode_core::nodes::{ExportDepth, Node, NodeProperties, NodeType};
+
+/// Saturated and mid-tone pixels, with partial alpha
+fn swatch() -> ImageData {
+    ImageData::new(vec![
+        255, 0, 0, 255,
+        0, 200, 40, 255,
+        30, 60, 250, 128,
+        128, 128, 128, 255,
+    ], 2, 2)
+}
+
+#[test]
+fn detect_icc_recognises_every_built_in_profile() {
+    for space in ColorSpace::ALL {
+        assert_eq!(color::detect_icc(&space.icc_profile()), Some(space), "{}", space.name());
+    }
+}
+
+#[test]
+fn detect_icc_rejects_other_data() {
+    assert_eq!(color::detect_icc(&[]), None);
+    assert_eq!(color::detect_icc(&[0; 200]), None);
+
+    // Truncated before the tag table
+    let profile = ColorSpace::DisplayP3.icc_profile();
+    assert_eq!(color::detect_icc(&profile[..128]), None);
+
+    // A greyscale profile
+    let mut grey = profile.clone();
+    grey[16..20].copy_from_slice(b"GRAY");
+    assert_eq!(color::detect_icc(&grey), None);
+}
+
+#[test]
+fn srgb_png_round_trips() {
+    let png = encode_png(&swatch(), ExportDepth::Eight).unwrap();
+    let decoded = decode_image(&png, &ImageLimits::default()).unwrap();
+
+    assert_eq!(decoded.color_space, ColorSpace::Srgb);
+    assert_eq!(decoded.to_rgba8(), swatch().to_rgba8());
+}
+
+#[test]
+fn display_p3_png_round_trips() {
+    let p3 = color::convert(&swatch(), ColorSpace::DisplayP3);
+    let png = encode_png(&p3, ExportDepth::Sixteen).unwrap();
+    let decoded = decode_image(&png, &ImageLimits::default()).unwrap();
+
+    assert_eq!(decoded.color_space, ColorSpace::DisplayP3);
+    assert_eq!(decoded.to_rgba16(), p3.to_rgba16());
+
+    // And back to the original sRGB values
+    let back = color::convert(&decoded, ColorSpace::Srgb);
+    assert_eq!(back.to_rgba8(), swatch().to_rgba8());
+}
+
+#[test]
+fn linear_images_export_encoded() {
+    let linear = color::convert(&swatch(), ColorSpace::LinearSrgb);
+    let png = encode_png(&linear, ExportDepth::Sixteen).unwrap();
+    let decoded = decode_image(&png, &ImageLimits::default()).unwrap();
+
+    assert_eq!(decoded.color_space, ColorSpace::Srgb);
+    assert_eq!(decoded.to_rgba8(), swatch().to_rgba8());
+}
+
+#[test]
+fn new_projects_are_linear_and_old_ones_srgb() {
+    assert_eq!(NodeGraph::new().working_space, WorkingSpace::LinearSrgb);
+
+    // Saved before the field existed
+    let old = NodeGraph::from_json(r#"{ "nodes": {}, "connections": [] }"#).unwrap();
+    assert_eq!(old.working_space, WorkingSpace::Srgb);
+
+    let mut graph = NodeGraph::new();
+    graph.working_space = WorkingSpace::LinearDisplayP3;
+    let saved = NodeGraph::from_json(&graph.to_json().unwrap()).unwrap();
+    assert_eq!(saved.working_space, WorkingSpace::LinearDisplayP3);
+}
+
+fn brightness(value: f32) -> Node {
+    node_with(NodeType::Adjust, |properties| if let NodeProperties::Adjust { brightness, .. } = properties {
+        *brightness = value;
+    })
+}
+
+#[test]
+fn values_above_one_survive_until_output() {
+    // Image → Adjust +50 → Adjust -50 → Output gives the input back only if
+    // the first Adjust doesn't clip the bright pixels
+    let (mut graph, output) = chain(&[node(NodeType::Image), brightness(50.0), brightness(-50.0)]);
+    graph.working_space = WorkingSpace::Srgb;
+    let result = run_first(&graph, output, &input(swatch()));
+    assert_eq!(result.to_rgba8(), swatch().to_rgba8());
+
+    // A single +50 is clipped at Output
+    let (mut graph, output) = chain(&[node(NodeType::Image), brightness(50.0)]);
+    graph.working_space = WorkingSpace::Srgb;
+    let result = run_first(&graph, output, &input(swatch()));
+    assert!(result.to_f32().iter().all(|v| (0.0..=1.0).contains(v)));
+    assert_eq!(result.get_pixel(0, 0)[0], 1.0);
+}
@@ -0,0 +1,68 @@
+//! Graph fixtures shared by the integration tests
+
+// Each test binary uses only some of these
+#![allow(dead_code)]
+
+use std::collections::HashMap;
+
+use flownode_core::executor::Executor;
+use flownode_core::graph::NodeGraph;
+use flownode_core::image_data::ImageData;
+use flownode_core::nodes::{Node, NodeProperties, NodeType, Vec2};
+use uuid::Uuid;
+
+/// ID of the input image that [`chain`] loads into Image nodes
+pub const IMAGE: u64 = 1;
+
+/// Node of `node_type` with its default properties
+pub fn node(node_type: NodeType) -> Node {
+    Node::new(node_type, Vec2::ZERO)
+}
+
+/// Node of `node_type` with `setup` applied to its default properties
+pub fn node_with(node_type: NodeType, setup: impl FnOnce(&mut NodeProperties)) -> Node {
+    let mut node = node(node_type);
+    setup(&mut node.properties);
+    node
+}
+
+/// Add a node to the graph and return its ID
+pub fn add(graph: &mut NodeGraph, node: Node) -> Uuid {
+    let id = node.id;
+    graph.insert_node(node);
+    id
+}
+
+/// `nodes` wired in order, each one's first output into the next one's first
+/// input, ending in a new Output node. Image nodes are loaded with [`IMAGE`].
+/// Returns the graph and the Output node's ID.
+pub fn chain(nodes: &[Node]) -> (NodeGraph, Uuid) {
+    let mut graph = NodeGraph::new();
+    let mut ids: Vec<Uuid> = nodes.iter().map(|n| add(&mut graph, n.clone())).collect();
+    for node in nodes.iter().filter(|n| n.node_type == NodeType::Image) {
+        graph.set_node_image(node.id, IMAGE);
+    }
+    let output = add(&mut graph, node(NodeType::Output));
+    ids.push(output);
+    for pair in ids.windows(2) {
+        graph.add_connection(pair[0], 0, pair[1], 0).unwrap();
+    }
+    (graph, output)
+}
+
+/// `image` as the input [`IMAGE`]
+pub fn input(image: ImageData) -> HashMap<u64, ImageData> {
+    HashMap::from([(IMAGE, image)])
+}
+
+/// Run `graph` and return the first image its Output node `output` produced
+pub fn run_first(graph: &NodeGraph, output: Uuid, inputs: &HashMap<u64, ImageData>) -> ImageData {
+    let mut results = Executor::new().execute(graph, inputs).unwrap();
+    results.remove(&output).expect("Output node produced nothing").remove(0)
+}
+
+/// Top-left pixel of an image as 8-bit RGBA
+pub fn first_pixel(image: &ImageData) -> [u8; 4] {
+    let pixels = image.to_rgba8();
+    [pixels[0], pixels[1], pixels[2], pixels[3]]
+}
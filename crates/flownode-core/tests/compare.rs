@@ -0,0 +1,45 @@
+//! Compare node metrics: measured on display values whatever the working
+//! space, so PSNR matches common tools on the exported images
+
+use flownode_core::color::{self, ColorSpace};
+use flownode_core::executor::compare_metrics;
+use flownode_core::image_data::ImageData;
+
+fn grey(level: u8) -> ImageData {
+    ImageData::solid(16, 16, [level, level, level, 255])
+}
+
+/// PSNR of a uniform error of one 8-bit step: 20·log10(255)
+const ONE_STEP_PSNR: f32 = 48.13;
+
+#[test]
+fn one_step_offset_psnr() {
+    let metrics = compare_metrics(&grey(100), &grey(101));
+    assert!((metrics.psnr - ONE_STEP_PSNR).abs() < 0.01, "PSNR {}", metrics.psnr);
+    assert!(metrics.ssim > 0.99);
+}
+
+#[test]
+fn identical_images_have_infinite_psnr() {
+    let metrics = compare_metrics(&grey(100), &grey(100));
+    assert_eq!(metrics.psnr, f32::INFINITY);
+    assert_eq!(metrics.ssim, 1.0);
+}
+
+#[test]
+fn linear_images_are_measured_encoded() {
+    // The same step in linear light is much smaller than 1/255
+    let a = color::convert(&grey(100), ColorSpace::LinearSrgb);
+    let b = color::convert(&grey(101), ColorSpace::LinearSrgb);
+    let metrics = compare_metrics(&a, &b);
+    assert!((metrics.psnr - ONE_STEP_PSNR).abs() < 0.01, "PSNR {}", metrics.psnr);
+}
+
+#[test]
+fn values_out_of_range_are_clamped() {
+    // Both export as white, give or take float rounding in the transfer curve
+    let bright = ImageData::from_f32(vec![1.5, 1.2, 4.0, 1.0], 1, 1).with_color_space(ColorSpace::LinearSrgb);
+    let white = ImageData::from_f32(vec![1.0; 4], 1, 1).with_color_space(ColorSpace::LinearSrgb);
+    let metrics = compare_metrics(&bright, &white);
+    assert!(metrics.psnr > 100.0, "PSNR {}", metrics.psnr);
+}
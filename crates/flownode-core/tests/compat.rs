@@ -0,0 +1,133 @@
+//! Pasting and loading React Flow JSON as FlowNode.io writes it
+
+use flownode_core::compat::{input_slot, output_slot, ReactFlowWorkflow};
+use flownode_core::graph::{Connection, NodeGraph, Subgraph};
+use flownode_core::nodes::{Node, NodeType, Vec2};
+use uuid::Uuid;
+
+/// A clipboard from the React app: two images compared, one through Adjust,
+/// and a prompt wired to an LLM, using React's handle IDs
+const CLIPBOARD: &str = r#"{
+    "nodes": [
+        { "id": "img", "type": "image", "position": { "x": 0, "y": 0 }, "data": {} },
+        { "id": "adj", "type": "adjust", "position": { "x": 300, "y": 0 }, "data": {} },
+        { "id": "cmp", "type": "compare", "position": { "x": 600, "y": 0 }, "data": {} },
+        { "id": "txt", "type": "text", "position": { "x": 0, "y": 300 }, "data": { "text": "a cat" } },
+        { "id": "llm", "type": "llm", "position": { "x": 300, "y": 300 }, "data": {} },
+        { "id": "cat", "type": "concat", "position": { "x": 600, "y": 300 }, "data": {} }
+    ],
+    "edges": [
+        { "id": "e1", "source": "img", "sourceHandle": "content-out", "target": "adj", "targetHandle": "content-in" },
+        { "id": "e2", "source": "img", "sourceHandle": "content-out", "target": "cmp", "targetHandle": "content-1-in" },
+        { "id": "e3", "source": "adj", "sourceHandle": "content-out", "target": "cmp", "targetHandle": "content-2-in" },
+        { "id": "e4", "source": "txt", "sourceHandle": "text-out", "target": "llm", "targetHandle": "prompt-in" },
+        { "id": "e5", "source": "llm", "sourceHandle": "llm-out", "target": "cat", "targetHandle": "text-2" },
+        { "id": "e6", "source": "img", "sourceHandle": "content-out", "target": "cmp", "targetHandle": "lock-target" },
+        { "id": "e7", "source": "gone", "sourceHandle": "content-out", "target": "adj", "targetHandle": "content-in" }
+    ]
+}"#;
+
+fn node_of(subgraph: &Subgraph, node_type: NodeType) -> Uuid {
+    subgraph.nodes.iter().find(|n| n.node_type == node_type).unwrap().id
+}
+
+#[test]
+fn pasted_react_edges_land_on_the_right_slots() {
+    let workflow: ReactFlowWorkflow = serde_json::from_str(CLIPBOARD).unwrap();
+    let subgraph = workflow.to_subgraph().unwrap();
+
+    let [image, adjust, compare, text, llm, concat] = [
+        NodeType::Image, NodeType::Adjust, NodeType::Compare, NodeType::Text, NodeType::Llm, NodeType::Concat,
+    ].map(|t| node_of(&subgraph, t));
+    let mut wires: Vec<_> = subgraph.connections.iter()
+        .map(|c| (c.from_node, c.from_slot, c.to_node, c.to_slot))
+        .collect();
+    wires.sort();
+    let mut expected = vec![
+        (image, 0, adjust, 0),
+        (image, 0, compare, 0),
+        (adjust, 0, compare, 1),
+        (text, 0, llm, 0),
+        (llm, 0, concat, 1),
+    ];
+    expected.sort();
+    // The lock handle and the edge from an uncopied node are dropped
+    assert_eq!(wires, expected);
+
+    // Every wire is one the graph accepts
+    let mut graph = NodeGraph::new();
+    for node in subgraph.nodes {
+        graph.insert_node(node);
+    }
+    for c in &subgraph.connections {
+        graph.add_connection(c.from_node, c.from_slot, c.to_node, c.to_slot).unwrap();
+    }
+}
+
+#[test]
+fn handles_resolve_by_name_alias_and_index() {
+    // Slot names
+    assert_eq!(input_slot(NodeType::Adjust, Some("mask-in")), Some(1));
+    assert_eq!(input_slot(NodeType::Composition, Some("content-in-3")), Some(2));
+    assert_eq!(output_slot(NodeType::Bucket, Some("batch-out")), Some(0));
+
+    // React aliases
+    assert_eq!(input_slot(NodeType::Omni, Some("content-in")), Some(1));
+    assert_eq!(input_slot(NodeType::Rodin3d, Some("prompt-in")), None, "Rodin takes no prompt here");
+    assert_eq!(output_slot(NodeType::Bucket, Some("content-out-0")), Some(0));
+    assert_eq!(output_slot(NodeType::Router, Some("out-2")), Some(2));
+    assert_eq!(output_slot(NodeType::Router, Some("out-5")), None, "only three router outputs");
+    assert_eq!(output_slot(NodeType::Splitter, Some("line-1")), Some(0));
+
+    // Our own export format, and missing handles
+    assert_eq!(input_slot(NodeType::Compare, Some("input-1")), Some(1));
+    assert_eq!(output_slot(NodeType::Image, None), Some(0));
+    assert_eq!(input_slot(NodeType::Image, None), None, "Image has no inputs");
+}
+
+/// Text into an image input, a loop, and two wires into one input
+const BAD_EDGES: &str = r#"{
+    "nodes": [
+        { "id": "img", "type": "image", "position": { "x": 0, "y": 0 }, "data": {} },
+        { "id": "img2", "type": "image", "position": { "x": 0, "y": 200 }, "data": {} },
+        { "id": "a", "type": "adjust", "position": { "x": 300, "y": 0 }, "data": {} },
+        { "id": "b", "type": "effects", "position": { "x": 600, "y": 0 }, "data": {} },
+        { "id": "txt", "type": "text", "position": { "x": 0, "y": 400 }, "data": {} }
+    ],
+    "edges": [
+        { "id": "ok", "source": "img", "sourceHandle": "content-out", "target": "a", "targetHandle": "content-in" },
+        { "id": "ab", "source": "a", "sourceHandle": "content-out", "target": "b", "targetHandle": "content-in" },
+        { "id": "loop", "source": "b", "sourceHandle": "content-out", "target": "a", "targetHandle": "content-in" },
+        { "id": "text", "source": "txt", "sourceHandle": "text-out", "target": "b", "targetHandle": "content-in" },
+        { "id": "second", "source": "img2", "sourceHandle": "content-out", "target": "b", "targetHandle": "content-in" }
+    ]
+}"#;
+
+/// Wires as (source id, target id) in React Flow IDs, sorted
+fn react_wires(workflow: &ReactFlowWorkflow, nodes: &[Node], connections: &[Connection]) -> Vec<(String, String)> {
+    // Nodes are created in workflow order
+    let react_id = |id: Uuid| nodes.iter().position(|n| n.id == id).and_then(|i| workflow.nodes.get(i)).map(|n| n.id.clone());
+    let mut wires: Vec<_> = connections.iter()
+        .filter_map(|c| Some((react_id(c.from_node)?, react_id(c.to_node)?)))
+        .collect();
+    wires.sort();
+    wires
+}
+
+#[test]
+fn rejected_edges_are_skipped() {
+    let workflow: ReactFlowWorkflow = serde_json::from_str(BAD_EDGES).unwrap();
+    // img2 → b replaces a → b; the loop and the text wire are refused
+    let expected = vec![("img".to_string(), "a".to_string()), ("img2".to_string(), "b".to_string())];
+
+    let subgraph = workflow.to_subgraph().unwrap();
+    assert_eq!(react_wires(&workflow, &subgraph.nodes, &subgraph.connections), expected);
+
+    // Loading also adds Output nodes, which have no React ID; match the rest
+    // up by position
+    let (graph, _, _) = workflow.to_graph().unwrap();
+    let ordered: Vec<Node> = workflow.nodes.iter()
+        .map(|rf| graph.nodes.values().find(|n| n.position == Vec2::new(rf.position.x, rf.position.y)).unwrap().clone())
+        .collect();
+    assert_eq!(react_wires(&workflow, &ordered, &graph.connections), expected);
+}
@@ -0,0 +1,202 @@
+//! Composition blend modes checked against the W3C Compositing and Blending
+//! formulas, worked out by hand for a few backdrop/source pairs
+//!
+//! Every mode is checked on four cases:
+//! 1. opaque backdrop (0.2, 0.5, 0.8) under opaque source (0.6, 0.3, 0.9)
+//! 2. opaque (0.1, 0.7, 0.0) under opaque (0.4, 0.7, 1.0), hitting the 0 and 1
+//!    special cases of dodge and burn
+//! 3. case 1 with the layer at 50% opacity
+//! 4. case 1 with backdrop alpha 0.6, source alpha 0.5 and 80% opacity, so
+//!    αs = 0.4 and αo = 0.4 + 0.6 × 0.6 = 0.76
+
+use flownode_core::backend::ProcessingBackend;
+use flownode_core::cpu::CpuBackend;
+use flownode_core::image_data::ImageData;
+use flownode_core::nodes::{BlendMode, CompositionLayer};
+
+/// Expected values are rounded to 5 decimals
+const TOLERANCE: f32 = 1e-4;
+
+/// (backdrop, source, opacity) for the four cases above
+const CASES: [([f32; 4], [f32; 4], f32); 4] = [
+    ([0.2, 0.5, 0.8, 1.0], [0.6, 0.3, 0.9, 1.0], 1.0),
+    ([0.1, 0.7, 0.0, 1.0], [0.4, 0.7, 1.0, 1.0], 1.0),
+    ([0.2, 0.5, 0.8, 1.0], [0.6, 0.3, 0.9, 1.0], 0.5),
+    ([0.2, 0.5, 0.8, 0.6], [0.6, 0.3, 0.9, 0.5], 0.8),
+];
+
+fn pixel(rgba: [f32; 4]) -> ImageData {
+    ImageData::from_f32(rgba.to_vec(), 1, 1)
+}
+
+/// Composite a one-pixel source onto a one-pixel backdrop
+fn composite(backdrop: [f32; 4], source: [f32; 4], opacity: f32, mode: BlendMode) -> [f32; 4] {
+    let inputs = vec![(0, pixel(backdrop)), (1, pixel(source))];
+    let layers = vec![
+        CompositionLayer::default(),
+        CompositionLayer { opacity, blend_mode: mode, ..Default::default() },
+    ];
+    CpuBackend.composition(&inputs, &layers).get_pixel(0, 0)
+}
+
+/// Check all four cases of a mode against their expected results
+fn check(mode: BlendMode, expected: [[f32; 4]; 4]) {
+    for (i, ((backdrop, source, opacity), expected)) in CASES.into_iter().zip(expected).enumerate() {
+        let actual = composite(backdrop, source, opacity, mode);
+        for c in 0..4 {
+            assert!(
+                (actual[c] - expected[c]).abs() <= TOLERANCE,
+                "{} case {}: got {:?}, expected {:?}",
+                mode.name(), i + 1, actual, expected
+            );
+        }
+    }
+}
+
+#[test]
+fn normal() {
+    // B = Cs. Case 3: 0.5 × 0.6 + 0.5 × 0.2 = 0.4
+    check(BlendMode::Normal, [
+        [0.6, 0.3, 0.9, 1.0],
+        [0.4, 0.7, 1.0, 1.0],
+        [0.4, 0.4, 0.85, 1.0],
+        [0.41053, 0.39474, 0.85263, 0.76],
+    ]);
+}
+
+#[test]
+fn multiply() {
+    // B = Cb × Cs: 0.2 × 0.6 = 0.12
+    check(BlendMode::Multiply, [
+        [0.12, 0.15, 0.72, 1.0],
+        [0.04, 0.49, 0.0, 1.0],
+        [0.16, 0.325, 0.76, 1.0],
+        [0.25895, 0.34737, 0.79579, 0.76],
+    ]);
+}
+
+#[test]
+fn screen() {
+    // B = Cb + Cs − Cb × Cs: 0.2 + 0.6 − 0.12 = 0.68
+    check(BlendMode::Screen, [
+        [0.68, 0.65, 0.98, 1.0],
+        [0.46, 0.91, 1.0, 1.0],
+        [0.44, 0.575, 0.89, 1.0],
+        [0.43579, 0.50526, 0.87789, 0.76],
+    ]);
+}
+
+#[test]
+fn overlay() {
+    // HardLight with the layers swapped: Cb 0.2 ≤ 0.5 gives 2 × 0.2 × 0.6 = 0.24
+    check(BlendMode::Overlay, [
+        [0.24, 0.3, 0.96, 1.0],
+        [0.08, 0.82, 0.0, 1.0],
+        [0.22, 0.4, 0.88, 1.0],
+        [0.29684, 0.39474, 0.87158, 0.76],
+    ]);
+}
+
+#[test]
+fn soft_light() {
+    // Cs 0.6 > 0.5 and Cb 0.2 ≤ 0.25: D = ((16 × 0.2 − 12) × 0.2 + 4) × 0.2 = 0.448,
+    // B = 0.2 + 0.2 × (0.448 − 0.2) = 0.2496
+    check(BlendMode::SoftLight, [
+        [0.2496, 0.4, 0.87554, 1.0],
+        [0.082, 0.75466, 0.0, 1.0],
+        [0.2248, 0.45, 0.83777, 1.0],
+        [0.29987, 0.42632, 0.84491, 0.76],
+    ]);
+}
+
+#[test]
+fn hard_light() {
+    // Cs 0.6 > 0.5: Screen(0.2, 0.2) = 0.36; Cs 0.3 ≤ 0.5: 2 × 0.5 × 0.3 = 0.3
+    check(BlendMode::HardLight, [
+        [0.36, 0.3, 0.96, 1.0],
+        [0.08, 0.82, 1.0, 1.0],
+        [0.28, 0.4, 0.88, 1.0],
+        [0.33474, 0.39474, 0.87158, 0.76],
+    ]);
+}
+
+#[test]
+fn color_dodge() {
+    // min(1, Cb / (1 − Cs)): 0.2 / 0.4 = 0.5; Cb = 0 gives 0, Cs = 1 gives 1
+    check(BlendMode::ColorDodge, [
+        [0.5, 0.71429, 1.0, 1.0],
+        [0.16667, 1.0, 0.0, 1.0],
+        [0.35, 0.60714, 0.9, 1.0],
+        [0.37895, 0.52556, 0.88421, 0.76],
+    ]);
+}
+
+#[test]
+fn color_burn() {
+    // 1 − min(1, (1 − Cb) / Cs): 1 − min(1, 0.2 / 0.9) = 0.77778
+    check(BlendMode::ColorBurn, [
+        [0.0, 0.0, 0.77778, 1.0],
+        [0.0, 0.57143, 0.0, 1.0],
+        [0.1, 0.25, 0.78889, 1.0],
+        [0.22105, 0.3, 0.81404, 0.76],
+    ]);
+}
+
+#[test]
+fn difference() {
+    // |Cb − Cs|
+    check(BlendMode::Difference, [
+        [0.4, 0.2, 0.1, 1.0],
+        [0.3, 0.0, 1.0, 1.0],
+        [0.3, 0.35, 0.45, 1.0],
+        [0.34737, 0.36316, 0.6, 0.76],
+    ]);
+}
+
+#[test]
+fn exclusion() {
+    // Cb + Cs − 2 × Cb × Cs: 0.2 + 0.6 − 0.24 = 0.56
+    check(BlendMode::Exclusion, [
+        [0.56, 0.5, 0.26, 1.0],
+        [0.42, 0.42, 1.0, 1.0],
+        [0.38, 0.5, 0.53, 1.0],
+        [0.39789, 0.45789, 0.65053, 0.76],
+    ]);
+}
+
+#[test]
+fn layer_position_and_scale() {
+    const RED: [f32; 4] = [1.0, 0.0, 0.0, 1.0];
+    const BLUE: [f32; 4] = [0.0, 0.0, 1.0, 1.0];
+    const BLACK: [f32; 4] = [0.0, 0.0, 0.0, 1.0];
+    let canvas = ImageData::from_f32([BLACK; 16].concat(), 4, 4);
+    let strip = ImageData::from_f32([RED, BLUE].concat(), 2, 1);
+
+    // Moved by whole pixels, the layer lands exactly and the rest is untouched
+    let moved = CpuBackend.composition(
+        &[(0, canvas.clone()), (1, strip.clone())],
+        &[CompositionLayer::default(), CompositionLayer { position: (1.0, 2.0), ..Default::default() }],
+    );
+    for y in 0..4 {
+        for x in 0..4 {
+            let expected = match (x, y) {
+                (1, 2) => RED,
+                (2, 2) => BLUE,
+                _ => BLACK,
+            };
+            assert_eq!(moved.get_pixel(x, y), expected, "moved layer at ({}, {})", x, y);
+        }
+    }
+
+    // Scaled 2×, a single pixel covers a 2×2 block at the offset
+    let scaled = CpuBackend.composition(
+        &[(0, canvas), (1, pixel(RED))],
+        &[CompositionLayer::default(), CompositionLayer { position: (2.0, 1.0), scale: 2.0, ..Default::default() }],
+    );
+    for y in 0..4 {
+        for x in 0..4 {
+            let expected = if (2..4).contains(&x) && (1..3).contains(&y) { RED } else { BLACK };
+            assert_eq!(scaled.get_pixel(x, y), expected, "scaled layer at ({}, {})", x, y);
+        }
+    }
+}
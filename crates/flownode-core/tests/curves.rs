@@ -0,0 +1,96 @@
+//! Tone curves: a monotone cubic spline through the control points, sampled
+//! into a 256-entry table for the Adjust node
+
+use flownode_core::nodes::Curve;
+
+fn curve(points: &[[f32; 2]]) -> Curve {
+    Curve { points: points.to_vec() }
+}
+
+fn assert_non_decreasing(lut: &[f32]) {
+    for (i, pair) in lut.windows(2).enumerate() {
+        assert!(pair[1] >= pair[0], "entry {} falls from {} to {}", i + 1, pair[0], pair[1]);
+    }
+}
+
+/// Every value between two control points stays between their y values
+fn assert_no_overshoot(curve: &Curve) {
+    let lut = curve.to_lut();
+    for pair in curve.points.windows(2) {
+        let (low, high) = (pair[0][1].min(pair[1][1]), pair[0][1].max(pair[1][1]));
+        for (i, y) in lut.iter().enumerate() {
+            let x = i as f32 / 255.0;
+            if x >= pair[0][0] && x <= pair[1][0] {
+                assert!(*y >= low - 1e-5 && *y <= high + 1e-5, "x {}: {} outside {}..{}", x, y, low, high);
+            }
+        }
+    }
+}
+
+#[test]
+fn identity() {
+    for identity in [Curve::default(), curve(&[[0.0, 0.0], [0.25, 0.25], [0.6, 0.6], [1.0, 1.0]])] {
+        assert!(identity.is_identity());
+        for (i, y) in identity.to_lut().iter().enumerate() {
+            assert!((y - i as f32 / 255.0).abs() < 1e-5, "entry {}: {}", i, y);
+        }
+    }
+}
+
+#[test]
+fn monotone_points_give_a_monotone_curve() {
+    // Steep then shallow, where an ordinary cubic spline overshoots
+    let steep = curve(&[[0.0, 0.0], [0.2, 0.05], [0.3, 0.8], [0.7, 0.85], [1.0, 1.0]]);
+    assert_non_decreasing(&steep.to_lut());
+    assert_no_overshoot(&steep);
+}
+
+#[test]
+fn peaks_do_not_overshoot() {
+    let peak = curve(&[[0.0, 0.2], [0.5, 0.6], [1.0, 0.2]]);
+    assert_no_overshoot(&peak);
+    assert!((peak.evaluate(0.5) - 0.6).abs() < 1e-5);
+}
+
+#[test]
+fn flat_segments_stay_flat() {
+    let flat = curve(&[[0.0, 0.0], [0.3, 0.5], [0.7, 0.5], [1.0, 1.0]]);
+    let lut = flat.to_lut();
+    assert_non_decreasing(&lut);
+    assert_no_overshoot(&flat);
+    for x in [0.3, 0.4, 0.5, 0.6, 0.7] {
+        assert!((flat.evaluate(x) - 0.5).abs() < 1e-5, "x {}: {}", x, flat.evaluate(x));
+    }
+}
+
+#[test]
+fn duplicate_x_values_step() {
+    // Two points at the same x make a jump rather than a division by zero
+    let step = curve(&[[0.0, 0.0], [0.5, 0.2], [0.5, 0.8], [1.0, 1.0]]);
+    let lut = step.to_lut();
+    assert!(lut.iter().all(|y| y.is_finite()));
+    assert_non_decreasing(&lut);
+    assert!(step.evaluate(0.49) <= 0.2);
+    assert!(step.evaluate(0.51) >= 0.8);
+}
+
+#[test]
+fn flat_beyond_the_end_points() {
+    let inset = curve(&[[0.2, 0.3], [0.8, 0.7]]);
+    assert_eq!(inset.evaluate(0.0), 0.3);
+    assert_eq!(inset.evaluate(1.0), 0.7);
+}
+
+#[test]
+fn one_point_is_constant() {
+    let single = curve(&[[0.4, 0.3]]);
+    assert!(single.to_lut().iter().all(|&y| y == 0.3));
+}
+
+#[test]
+fn no_points_is_identity() {
+    let empty = curve(&[]);
+    for (i, y) in empty.to_lut().iter().enumerate() {
+        assert_eq!(*y, i as f32 / 255.0);
+    }
+}
@@ -0,0 +1,119 @@
+//! Golden-image tests: the Adjust node's colour boost, hue rotation and
+//! luminance mix sliders rendered on a fixed image and compared per pixel
+//! with reference renders in `tests/golden/adjust/`
+//!
+//! React processes sRGB-encoded values, so the graphs run in the sRGB working
+//! space. The references are exports from FlowNode.io's `ImageAdjustNode` and
+//! are never rewritten from this crate's output; see the README in the golden
+//! directory. Until they are committed the comparisons are ignored.
+
+mod common;
+
+use std::path::PathBuf;
+
+use common::{chain, input, node, node_with, run_first};
+use flownode_core::color::WorkingSpace;
+use flownode_core::image_data::{decode_image, ImageData, ImageLimits};
+use flownode_core::nodes::{NodeProperties, NodeType};
+
+/// Largest difference allowed in any 8-bit channel
+const TOLERANCE: u8 = 2;
+
+fn golden_dir() -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/golden/adjust")
+}
+
+fn load(name: &str) -> ImageData {
+    let path = golden_dir().join(name);
+    let bytes = std::fs::read(&path).unwrap_or_else(|e| panic!("{}: {}", path.display(), e));
+    decode_image(&bytes, &ImageLimits::default()).unwrap()
+}
+
+/// Run the test image through one Adjust node with `settings` applied to its
+/// defaults
+fn render(settings: impl FnOnce(&mut NodeProperties)) -> ImageData {
+    let (mut graph, output) = chain(&[node(NodeType::Image), node_with(NodeType::Adjust, settings)]);
+    graph.working_space = WorkingSpace::Srgb;
+    run_first(&graph, output, &input(load("input.png")))
+}
+
+/// Compare a render with its React reference
+fn check(reference: &str, settings: impl FnOnce(&mut NodeProperties)) {
+    let actual = render(settings);
+    let expected = load(reference);
+    assert_eq!((actual.width, actual.height), (expected.width, expected.height), "{}: size", reference);
+    let (actual, expected) = (actual.to_rgba8(), expected.to_rgba8());
+    let (i, diff) = actual.iter().zip(&expected)
+        .map(|(a, e)| a.abs_diff(*e))
+        .enumerate()
+        .fold((0, 0), |worst, (i, d)| if d > worst.1 { (i, d) } else { worst });
+    assert!(
+        diff <= TOLERANCE,
+        "{}: pixel {} channel {} differs by {} (got {}, expected {})",
+        reference, i / 4, i % 4, diff, actual[i], expected[i]
+    );
+}
+
+fn color_boost(value: f32) -> impl FnOnce(&mut NodeProperties) {
+    move |properties| {
+        if let NodeProperties::Adjust { color_boost, .. } = properties {
+            *color_boost = value;
+        }
+    }
+}
+
+fn hue_rotation(value: f32) -> impl FnOnce(&mut NodeProperties) {
+    move |properties| {
+        if let NodeProperties::Adjust { hue_rotation, .. } = properties {
+            *hue_rotation = value;
+        }
+    }
+}
+
+/// Luminance mix only shows next to other adjustments, so brighten and
+/// saturate too
+fn luminance_mix(value: f32) -> impl FnOnce(&mut NodeProperties) {
+    move |properties| {
+        if let NodeProperties::Adjust { brightness, saturation, luminance_mix, .. } = properties {
+            *brightness = 20.0;
+            *saturation = 40.0;
+            *luminance_mix = value;
+        }
+    }
+}
+
+#[test]
+#[ignore = "needs the reference exported from React"]
+fn color_boost_positive() {
+    check("color_boost_50.png", color_boost(50.0));
+}
+
+#[test]
+#[ignore = "needs the reference exported from React"]
+fn color_boost_negative() {
+    check("color_boost_neg50.png", color_boost(-50.0));
+}
+
+#[test]
+#[ignore = "needs the reference exported from React"]
+fn hue_rotation_positive() {
+    check("hue_rotation_90.png", hue_rotation(90.0));
+}
+
+#[test]
+#[ignore = "needs the reference exported from React"]
+fn hue_rotation_negative() {
+    check("hue_rotation_neg45.png", hue_rotation(-45.0));
+}
+
+#[test]
+#[ignore = "needs the reference exported from React"]
+fn luminance_mix_none() {
+    check("luminance_mix_0.png", luminance_mix(0.0));
+}
+
+#[test]
+#[ignore = "needs the reference exported from React"]
+fn luminance_mix_half() {
+    check("luminance_mix_50.png", luminance_mix(50.0));
+}
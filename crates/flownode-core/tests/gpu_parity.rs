@@ -0,0 +1,235 @@
+//! Every GPU kernel checked against the CPU backend on a software adapter
+//!
+//! `cargo test -p flownode-core --features gpu`. Without an adapter (no
+//! llvmpipe/WARP/SwiftShader installed) the tests fail, unless
+//! `FLOWNODE_SKIP_GPU_TESTS` is set to skip them on purpose.
+
+#![cfg(feature = "gpu")]
+
+use std::sync::OnceLock;
+
+use flownode_core::backend::{Adjustments, ProcessingBackend};
+use flownode_core::cpu::CpuBackend;
+use flownode_core::gpu::GpuContext;
+use flownode_core::image_data::ImageData;
+use flownode_core::lut::{Lut, LutKind};
+use flownode_core::mask::Mask;
+use flownode_core::nodes::{BlendMode, BlurDirection, ColorWheel, CompareMode, CompositionLayer, Curves, EdgeMode, LutInterpolation, MaskSource};
+
+/// Largest difference allowed in any channel of any pixel
+const TOLERANCE: f32 = 1e-3;
+
+/// Sizes that don't divide into whole workgroups
+const WIDTH: u32 = 67;
+const HEIGHT: u32 = 41;
+
+/// Set to skip the tests on machines without a software adapter
+const SKIP_VAR: &str = "FLOWNODE_SKIP_GPU_TESTS";
+
+fn gpu() -> Option<&'static GpuContext> {
+    static GPU: OnceLock<Result<GpuContext, String>> = OnceLock::new();
+    match GPU.get_or_init(GpuContext::software) {
+        Ok(gpu) => Some(gpu),
+        Err(e) if std::env::var_os(SKIP_VAR).is_some() => {
+            eprintln!("Skipping GPU parity tests ({} is set): {}", SKIP_VAR, e);
+            None
+        }
+        Err(e) => panic!("No GPU adapter for the parity tests: {}. Set {} to skip them.", e, SKIP_VAR),
+    }
+}
+
+/// Smooth colour ramps with alpha falling off to the right, so small
+/// differences in where a kernel samples stay small
+fn test_image(width: u32, height: u32, phase: f32) -> ImageData {
+    let mut pixels = Vec::with_capacity((width * height * 4) as usize);
+    for y in 0..height {
+        for x in 0..width {
+            let u = x as f32 / width as f32;
+            let v = y as f32 / height as f32;
+            pixels.extend_from_slice(&[
+                u,
+                0.5 + 0.4 * (v * 5.0 + phase).sin(),
+                (u + v) * 0.5,
+                1.0 - 0.8 * u * v,
+            ]);
+        }
+    }
+    ImageData::from_f32(pixels, width, height)
+}
+
+fn image() -> ImageData {
+    test_image(WIDTH, HEIGHT, 0.0)
+}
+
+/// Run `op` on both backends and compare
+fn check(name: &str, tolerance: f32, op: impl Fn(&dyn ProcessingBackend) -> ImageData) {
+    let Some(gpu) = gpu() else {
+        return;
+    };
+    let expected = op(&CpuBackend);
+    let actual = op(gpu);
+    assert_eq!((actual.width, actual.height), (expected.width, expected.height), "{}: size", name);
+
+    let (expected, actual) = (expected.to_f32(), actual.to_f32());
+    let (i, diff) = expected.iter().zip(&actual)
+        .map(|(e, a)| (e - a).abs())
+        .enumerate()
+        .fold((0, 0.0f32), |worst, (i, d)| if d > worst.1 { (i, d) } else { worst });
+    assert!(
+        diff <= tolerance,
+        "{}: pixel {} channel {} differs by {} (CPU {}, GPU {})",
+        name, i / 4, i % 4, diff, expected[i], actual[i]
+    );
+}
+
+#[test]
+fn adjust() {
+    let params = Adjustments {
+        brightness: 5.0,
+        contrast: 20.0,
+        saturation: -10.0,
+        exposure: 8.0,
+        highlights: -30.0,
+        shadows: 25.0,
+        temperature: 15.0,
+        tint: -10.0,
+        vibrance: 30.0,
+        gamma: 10.0,
+        color_boost: 20.0,
+        hue_rotation: 40.0,
+    };
+    check("adjust", TOLERANCE, |b| b.adjust(&image(), &params));
+    check("adjust (defaults)", TOLERANCE, |b| b.adjust(&image(), &Adjustments::default()));
+}
+
+#[test]
+fn color_wheels() {
+    let lift = ColorWheel { x: 0.3, y: -0.2, luminance: 5.0 };
+    let gamma = ColorWheel { x: -0.1, y: 0.25, luminance: 0.0 };
+    let gain = ColorWheel { x: 0.0, y: 0.4, luminance: -10.0 };
+    let offset = ColorWheel { x: -0.2, y: -0.2, luminance: 0.0 };
+    check("color wheels", TOLERANCE, |b| b.color_wheels(&image(), &lift, &gamma, &gain, &offset));
+}
+
+#[test]
+fn curves() {
+    let mut curves = Curves::default();
+    curves.master.points = vec![[0.0, 0.05], [0.25, 0.3], [0.75, 0.7], [1.0, 0.95]];
+    curves.red.points = vec![[0.0, 0.0], [0.5, 0.6], [1.0, 1.0]];
+    curves.blue.points = vec![[0.0, 0.1], [1.0, 0.9]];
+    check("curves", TOLERANCE, |b| b.curves(&image(), &curves));
+}
+
+#[test]
+fn lut() {
+    // A 3D table that swaps red and blue and lifts green, and a 1D curve
+    let size = 9;
+    let v = |i: usize| i as f32 / (size - 1) as f32;
+    let mut table = Vec::new();
+    for b in 0..size {
+        for g in 0..size {
+            for r in 0..size {
+                table.push([v(b), v(g).sqrt(), v(r)]);
+            }
+        }
+    }
+    let cube = Lut { title: String::new(), kind: LutKind::ThreeD, size, domain_min: [0.0; 3], domain_max: [1.0; 3], table };
+    let ramp = Lut {
+        title: String::new(),
+        kind: LutKind::OneD,
+        size: 16,
+        domain_min: [0.0, 0.1, 0.0],
+        domain_max: [1.0, 1.0, 0.8],
+        table: (0..16).map(|i| { let t = i as f32 / 15.0; [t * t, t, 1.0 - t] }).collect(),
+    };
+
+    for interpolation in LutInterpolation::ALL {
+        check(interpolation.name(), TOLERANCE, |b| b.lut(&image(), &cube, interpolation, 0.8));
+    }
+    check("1D LUT", TOLERANCE, |b| b.lut(&image(), &ramp, LutInterpolation::Trilinear, 1.0));
+}
+
+#[test]
+fn luminance_mix() {
+    let adjusted = test_image(WIDTH, HEIGHT, 1.5);
+    check("luminance mix", TOLERANCE, |b| b.luminance_mix(&image(), &adjusted, 40.0));
+}
+
+#[test]
+fn gaussian_blur() {
+    for edge in EdgeMode::ALL {
+        for sigma in [0.4, 2.5, 9.0] {
+            check(&format!("gaussian blur {} σ{}", edge.name(), sigma), TOLERANCE, |b| b.gaussian_blur(&image(), sigma, edge));
+        }
+    }
+}
+
+#[test]
+fn directional_blur() {
+    for edge in EdgeMode::ALL {
+        for angle in [0.0, 30.0, 90.0] {
+            check(&format!("directional blur {} {}°", edge.name(), angle), TOLERANCE, |b| b.directional_blur(&image(), 0.4, angle, edge));
+        }
+    }
+}
+
+#[test]
+fn progressive_blur() {
+    for direction in [BlurDirection::Top, BlurDirection::Right] {
+        check(&format!("progressive blur {:?}", direction), TOLERANCE, |b| {
+            b.progressive_blur(&image(), 0.6, &direction, 0.7, EdgeMode::Mirror)
+        });
+    }
+}
+
+#[test]
+fn glass_blinds() {
+    // Displacements are whole pixels: a rounding difference in the wave moves
+    // a pixel by one, which on these ramps is a step of about 1/WIDTH
+    check("glass blinds", 2.0 / WIDTH as f32, |b| b.glass_blinds(&image(), 0.5, 40.0, 20.0, 0.3));
+}
+
+#[test]
+fn sharpen() {
+    for edge in EdgeMode::ALL {
+        check(&format!("sharpen {}", edge.name()), TOLERANCE, |b| b.sharpen(&image(), 0.8, edge));
+    }
+}
+
+#[test]
+fn grain() {
+    check("grain", TOLERANCE, |b| b.grain(&image(), 0.5, 3.0, false, 42));
+    check("grain (monochrome)", TOLERANCE, |b| b.grain(&image(), 0.8, 1.0, true, 7));
+}
+
+#[test]
+fn vignette() {
+    check("vignette", TOLERANCE, |b| b.vignette(&image(), 0.7, 0.3, 0.5));
+}
+
+#[test]
+fn composition() {
+    let inputs = vec![(0, image()), (1, test_image(30, 50, 2.0))];
+    for mode in BlendMode::ALL {
+        let layers = vec![
+            CompositionLayer::default(),
+            CompositionLayer { opacity: 0.7, blend_mode: mode, position: (12.5, -4.0), scale: 1.3, ..Default::default() },
+        ];
+        check(&format!("composition {}", mode.name()), TOLERANCE, |b| b.composition(&inputs, &layers));
+    }
+}
+
+#[test]
+fn compare() {
+    let other = test_image(WIDTH, HEIGHT, 0.7);
+    for mode in CompareMode::ALL {
+        check(&format!("compare {}", mode.name()), TOLERANCE, |b| b.compare(&image(), &other, mode, 0.4));
+    }
+}
+
+#[test]
+fn mask_mix() {
+    let processed = test_image(WIDTH, HEIGHT, 2.5);
+    let mask = Mask::shape(WIDTH, HEIGHT, MaskSource::Ellipse, [0.4, 0.6], [0.7, 0.5], 30.0);
+    check("mask mix", TOLERANCE, |b| b.mask_mix(&image(), &processed, &mask));
+}
@@ -0,0 +1,114 @@
+//! Loading saved graphs and React Flow workflows made before the features
+//! they now rely on
+
+mod common;
+
+use common::{input, IMAGE};
+use flownode_core::compat::ReactFlowWorkflow;
+use flownode_core::executor::Executor;
+use flownode_core::graph::NodeGraph;
+use flownode_core::image_data::ImageData;
+use flownode_core::nodes::{Node, NodeProperties, NodeType, Vec2};
+
+fn outputs(graph: &NodeGraph) -> Vec<&Node> {
+    graph.nodes.values().filter(|n| n.node_type == NodeType::Output).collect()
+}
+
+/// Name and source node type of each Output node, sorted
+fn output_sources(graph: &NodeGraph) -> Vec<(String, NodeType)> {
+    let mut sources: Vec<(String, NodeType)> = outputs(graph).into_iter()
+        .map(|output| {
+            let NodeProperties::Output { name, .. } = &output.properties else {
+                unreachable!();
+            };
+            let source = graph.connections.iter()
+                .find(|c| c.to_node == output.id)
+                .map(|c| graph.nodes[&c.from_node].node_type)
+                .expect("Output node is connected");
+            (name.clone(), source)
+        })
+        .collect();
+    sources.sort_by(|a, b| a.0.cmp(&b.0));
+    sources
+}
+
+/// Image → Adjust, and Image → Effects on a second branch, with no Output
+fn two_branches() -> NodeGraph {
+    let mut graph = NodeGraph::new();
+    let image = Node::new(NodeType::Image, Vec2::ZERO);
+    let adjust = Node::new(NodeType::Adjust, Vec2::new(250.0, 0.0));
+    let effects = Node::new(NodeType::Effects, Vec2::new(250.0, 200.0));
+    let (image_id, adjust_id, effects_id) = (image.id, adjust.id, effects.id);
+    graph.insert_node(image);
+    graph.insert_node(adjust);
+    graph.insert_node(effects);
+    graph.add_connection(image_id, 0, adjust_id, 0).unwrap();
+    graph.add_connection(image_id, 0, effects_id, 0).unwrap();
+    graph
+}
+
+#[test]
+fn old_projects_get_an_output_per_branch() {
+    let json = two_branches().to_json().unwrap();
+    let mut graph = NodeGraph::from_json(&json).unwrap();
+
+    assert_eq!(output_sources(&graph), vec![
+        ("adjust".to_string(), NodeType::Adjust),
+        ("effects".to_string(), NodeType::Effects),
+    ]);
+
+    // Loaded images aren't saved, so attach one as the app does on load
+    let image_id = graph.nodes.values().find(|n| n.node_type == NodeType::Image).unwrap().id;
+    graph.set_node_image(image_id, IMAGE);
+    let results = Executor::new().execute(&graph, &input(ImageData::solid(4, 4, [200, 100, 50, 255]))).unwrap();
+    assert_eq!(results.len(), 2, "both branches render");
+}
+
+#[test]
+fn projects_with_outputs_are_left_alone() {
+    let mut graph = two_branches();
+    graph.add_missing_outputs();
+    let json = graph.to_json().unwrap();
+
+    let loaded = NodeGraph::from_json(&json).unwrap();
+    assert_eq!(outputs(&loaded).len(), 2);
+    assert_eq!(loaded.nodes.len(), graph.nodes.len());
+}
+
+#[test]
+fn duplicate_node_types_get_numbered_outputs() {
+    let mut graph = NodeGraph::new();
+    let image = Node::new(NodeType::Image, Vec2::ZERO);
+    let first = Node::new(NodeType::Adjust, Vec2::new(250.0, 0.0));
+    let second = Node::new(NodeType::Adjust, Vec2::new(250.0, 200.0));
+    let (image_id, first_id, second_id) = (image.id, first.id, second.id);
+    graph.insert_node(image);
+    graph.insert_node(first);
+    graph.insert_node(second);
+    graph.add_connection(image_id, 0, first_id, 0).unwrap();
+    graph.add_connection(image_id, 0, second_id, 0).unwrap();
+
+    assert_eq!(graph.add_missing_outputs(), 2);
+    let names: Vec<String> = output_sources(&graph).into_iter().map(|(name, _)| name).collect();
+    assert_eq!(names, vec!["adjust", "adjust-2"]);
+}
+
+#[test]
+fn react_workflows_get_outputs() {
+    let json = r#"{
+        "nodes": [
+            { "id": "1", "type": "image", "position": { "x": 0, "y": 0 }, "data": {} },
+            { "id": "2", "type": "adjust", "position": { "x": 300, "y": 0 }, "data": { "settings": { "brightness": 20 } } },
+            { "id": "3", "type": "text", "position": { "x": 0, "y": 300 }, "data": { "text": "notes" } }
+        ],
+        "edges": [
+            { "id": "e1-2", "source": "1", "target": "2", "sourceHandle": "content-out", "targetHandle": "content-in" }
+        ],
+        "viewport": { "x": 0, "y": 0, "zoom": 1 }
+    }"#;
+    let workflow: ReactFlowWorkflow = serde_json::from_str(json).unwrap();
+    let (graph, _, _) = workflow.to_graph().unwrap();
+
+    // Text nodes don't produce images, so only Adjust gets an Output
+    assert_eq!(output_sources(&graph), vec![("adjust".to_string(), NodeType::Adjust)]);
+}
@@ -0,0 +1,109 @@
+//! LUT tables live in the graph's store and nodes refer to them by hash
+
+mod common;
+
+use common::{add, chain, first_pixel, input, node, run_first, IMAGE};
+use flownode_core::color::WorkingSpace;
+use flownode_core::compat::ReactFlowWorkflow;
+use flownode_core::graph::NodeGraph;
+use flownode_core::image_data::ImageData;
+use flownode_core::lut::{Lut, LutKind};
+use flownode_core::nodes::{NodeProperties, NodeType, Vec2};
+use uuid::Uuid;
+
+/// 2³ table that swaps red and blue
+fn swap_lut() -> Lut {
+    let mut table = Vec::new();
+    for b in 0..2 {
+        for g in 0..2 {
+            for r in 0..2 {
+                table.push([b as f32, g as f32, r as f32]);
+            }
+        }
+    }
+    Lut { title: "Swap".to_string(), kind: LutKind::ThreeD, size: 2, domain_min: [0.0; 3], domain_max: [1.0; 3], table }
+}
+
+/// Image → LUT → Output with [`swap_lut`] loaded, and the LUT and Output IDs
+fn lut_graph() -> (NodeGraph, Uuid, Uuid) {
+    let lut = node(NodeType::Lut);
+    let lut_id = lut.id;
+    let (mut graph, output_id) = chain(&[node(NodeType::Image), lut]);
+    graph.working_space = WorkingSpace::Srgb;
+    assert!(graph.set_node_lut(lut_id, "swap.cube", swap_lut()));
+    (graph, lut_id, output_id)
+}
+
+/// Run a graph on a red pixel and return the first Output pixel
+fn run(graph: &NodeGraph, output_id: Uuid) -> [u8; 4] {
+    // Loaded images aren't saved, so attach one as the app does on load
+    let mut graph = graph.clone();
+    let image_id = graph.nodes.values().find(|n| n.node_type == NodeType::Image).unwrap().id;
+    graph.set_node_image(image_id, IMAGE);
+    first_pixel(&run_first(&graph, output_id, &input(ImageData::solid(1, 1, [255, 0, 0, 255]))))
+}
+
+#[test]
+fn properties_hold_only_a_reference() {
+    let (mut graph, lut_id, output_id) = lut_graph();
+    let NodeProperties::Lut { lut: Some(reference), .. } = &graph.nodes[&lut_id].properties else {
+        panic!("LUT node has no table");
+    };
+    assert_eq!(reference.hash, swap_lut().content_hash());
+    assert_eq!(reference.title, "Swap");
+    let properties = serde_json::to_string(&graph.nodes[&lut_id].properties).unwrap();
+    assert!(!properties.contains("table"), "{}", properties);
+
+    // The same table loaded twice, under another title, is stored once
+    let second_id = add(&mut graph, node(NodeType::Lut));
+    let mut renamed = swap_lut();
+    renamed.title = "Copy".to_string();
+    graph.set_node_lut(second_id, "copy.cube", renamed);
+    assert_eq!(graph.luts.iter().count(), 1);
+    assert_eq!(graph.node_lut(second_id), graph.node_lut(lut_id));
+
+    assert_eq!(run(&graph, output_id), [0, 0, 255, 255]);
+}
+
+#[test]
+fn saved_graphs_keep_their_tables() {
+    let (graph, lut_id, output_id) = lut_graph();
+    let loaded = NodeGraph::from_json(&graph.to_json().unwrap()).unwrap();
+
+    assert_eq!(loaded.node_lut(lut_id), Some(&swap_lut()));
+    assert_eq!(run(&loaded, output_id), [0, 0, 255, 255]);
+}
+
+#[test]
+fn old_inline_tables_move_to_the_store() {
+    // Projects used to save the whole table in the node's properties
+    let (graph, lut_id, output_id) = lut_graph();
+    let mut json: serde_json::Value = serde_json::from_str(&graph.to_json().unwrap()).unwrap();
+    json.as_object_mut().unwrap().remove("luts");
+    json["nodes"][lut_id.to_string()]["properties"]["Lut"]["lut"] = serde_json::to_value(swap_lut()).unwrap();
+
+    let loaded = NodeGraph::from_json(&json.to_string()).unwrap();
+    let NodeProperties::Lut { lut: Some(reference), .. } = &loaded.nodes[&lut_id].properties else {
+        panic!("LUT node lost its table");
+    };
+    assert_eq!(reference.hash, swap_lut().content_hash());
+    assert_eq!(loaded.node_lut(lut_id), Some(&swap_lut()));
+    assert_eq!(run(&loaded, output_id), [0, 0, 255, 255]);
+}
+
+#[test]
+fn missing_tables_pass_the_image_through() {
+    let (mut graph, _, output_id) = lut_graph();
+    graph.luts = Default::default();
+    assert_eq!(run(&graph, output_id), [255, 0, 0, 255]);
+}
+
+#[test]
+fn react_workflows_carry_the_cube_text() {
+    let (graph, _, _) = lut_graph();
+    let workflow = ReactFlowWorkflow::from_graph(&graph, Vec2::ZERO, 1.0);
+    let (loaded, _, _) = workflow.to_graph().unwrap();
+
+    let lut_node = loaded.nodes.values().find(|n| n.node_type == NodeType::Lut).unwrap();
+    assert_eq!(loaded.node_lut(lut_node.id).map(|l| l.content_hash()), Some(swap_lut().content_hash()));
+}
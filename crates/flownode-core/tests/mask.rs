@@ -0,0 +1,140 @@
+//! Masks built by Mask nodes and applied by Adjust, Effects and Composition
+
+mod common;
+
+use std::collections::HashMap;
+
+use common::{add, chain, input, node, node_with, run_first, IMAGE};
+use flownode_core::executor::{Executor, NodeOutput};
+use flownode_core::graph::NodeGraph;
+use flownode_core::image_data::ImageData;
+use flownode_core::mask::Mask;
+use flownode_core::nodes::{MaskSource, NodeProperties, NodeType};
+
+const WIDTH: u32 = 40;
+const HEIGHT: u32 = 20;
+
+/// Largest round-trip error through the working space
+const TOLERANCE: f32 = 1e-3;
+
+/// Mid grey, so brightening shows everywhere
+fn grey() -> ImageData {
+    ImageData::solid(WIDTH, HEIGHT, [128, 128, 128, 255])
+}
+
+/// Mask node covering the left half of its input
+fn left_half(invert: bool) -> NodeProperties {
+    NodeProperties::Mask {
+        source: MaskSource::Rectangle,
+        center: [0.25, 0.5],
+        size: [0.5, 1.0],
+        angle: 0.0,
+        invert,
+        feather: 0.0,
+        density: 100.0,
+    }
+}
+
+/// Image → `node` → Output, with a Mask node of `mask` on the node's mask
+/// input. Returns the output image.
+fn run_masked(node_type: NodeType, properties: NodeProperties, mask: NodeProperties) -> ImageData {
+    let (image, masked) = (node(NodeType::Image), node_with(node_type, |p| *p = properties));
+    let (image_id, masked_id) = (image.id, masked.id);
+    let (mut graph, output) = chain(&[image, masked]);
+    let mask_node = add(&mut graph, node_with(NodeType::Mask, |p| *p = mask));
+
+    let mask_slot = node_type.mask_slot().expect("node takes a mask");
+    graph.add_connection(image_id, 0, mask_node, 0).unwrap();
+    graph.add_connection(mask_node, 0, masked_id, mask_slot).unwrap();
+    run_first(&graph, output, &input(grey()))
+}
+
+fn brighten() -> NodeProperties {
+    let mut properties = NodeProperties::for_type(NodeType::Adjust);
+    if let NodeProperties::Adjust { brightness, .. } = &mut properties {
+        *brightness = 50.0;
+    }
+    properties
+}
+
+#[test]
+fn adjust_applies_inside_mask_only() {
+    let result = run_masked(NodeType::Adjust, brighten(), left_half(false));
+    let original = grey().get_pixel(0, 0)[0];
+
+    assert!(result.get_pixel(2, 10)[0] > original + 0.1, "masked side brightened");
+    assert!((result.get_pixel(WIDTH - 3, 10)[0] - original).abs() < TOLERANCE, "unmasked side untouched");
+}
+
+#[test]
+fn invert_swaps_sides() {
+    let result = run_masked(NodeType::Adjust, brighten(), left_half(true));
+    let original = grey().get_pixel(0, 0)[0];
+
+    assert!((result.get_pixel(2, 10)[0] - original).abs() < TOLERANCE);
+    assert!(result.get_pixel(WIDTH - 3, 10)[0] > original + 0.1);
+}
+
+#[test]
+fn effects_are_masked() {
+    let mut properties = NodeProperties::for_type(NodeType::Effects);
+    if let NodeProperties::Effects { vignette, .. } = &mut properties {
+        *vignette = 100.0;
+    }
+    let result = run_masked(NodeType::Effects, properties, left_half(false));
+    let original = grey().get_pixel(0, 0)[0];
+
+    assert!(result.get_pixel(0, 0)[0] < original - 0.05, "masked corner darkened");
+    assert!((result.get_pixel(WIDTH - 1, 0)[0] - original).abs() < TOLERANCE, "unmasked corner untouched");
+}
+
+#[test]
+fn feather_and_density_soften_the_mask() {
+    let hard = Mask::shape(WIDTH, HEIGHT, MaskSource::Rectangle, [0.25, 0.5], [0.5, 1.0], 0.0);
+    assert_eq!(hard.get(5, 10), 1.0);
+    assert_eq!(hard.get(30, 10), 0.0);
+    assert_eq!(hard.with_density(0.4).get(5, 10), 0.4);
+    assert_eq!(hard.inverted().get(30, 10), 1.0);
+
+    // A feathered edge ramps across the boundary at x = 20
+    let mut graph = NodeGraph::new();
+    let image = add(&mut graph, node(NodeType::Image));
+    let mut properties = left_half(false);
+    if let NodeProperties::Mask { feather, .. } = &mut properties {
+        *feather = 8.0;
+    }
+    let mask_node = add(&mut graph, node_with(NodeType::Mask, |p| *p = properties));
+    graph.set_node_image(image, IMAGE);
+    graph.add_connection(image, 0, mask_node, 0).unwrap();
+
+    let mut executor = Executor::new();
+    executor.execute(&graph, &input(grey())).unwrap();
+    let Some(NodeOutput::Mask(mask)) = executor.output(mask_node) else {
+        panic!("Mask node should output a mask");
+    };
+    let (inside, edge, outside) = (mask.get(14, 10), mask.get(20, 10), mask.get(26, 10));
+    assert!(inside > edge && edge > outside, "{} {} {}", inside, edge, outside);
+    assert!(edge > 0.3 && edge < 0.7);
+}
+
+#[test]
+fn masked_composition_shows_bottom_layer_outside() {
+    // The bottom layer is the chain's image, the top one image 2
+    let (bottom, composition) = (node(NodeType::Image), node(NodeType::Composition));
+    let (bottom_id, composition_id) = (bottom.id, composition.id);
+    let (mut graph, output) = chain(&[bottom, composition]);
+    let top = add(&mut graph, node(NodeType::Image));
+    let mask_node = add(&mut graph, node_with(NodeType::Mask, |p| *p = left_half(false)));
+    graph.set_node_image(top, 2);
+
+    graph.add_connection(top, 0, composition_id, 1).unwrap();
+    graph.add_connection(bottom_id, 0, mask_node, 0).unwrap();
+    graph.add_connection(mask_node, 0, composition_id, NodeType::Composition.mask_slot().unwrap()).unwrap();
+
+    let white = ImageData::solid(WIDTH, HEIGHT, [255, 255, 255, 255]);
+    let inputs = HashMap::from([(IMAGE, grey()), (2, white)]);
+    let result = run_first(&graph, output, &inputs);
+
+    assert!((result.get_pixel(2, 10)[0] - 1.0).abs() < TOLERANCE, "top layer inside the mask");
+    assert!((result.get_pixel(WIDTH - 3, 10)[0] - grey().get_pixel(0, 0)[0]).abs() < TOLERANCE, "bottom layer outside");
+}
@@ -0,0 +1,128 @@
+//! Proxy runs: settings measured in pixels are scaled with the image, so a
+//! run on a half-size proxy matches the full-resolution result downscaled
+
+mod common;
+
+use common::{add, chain, input, node, node_with, IMAGE};
+use flownode_core::color::WorkingSpace;
+use flownode_core::executor::Executor;
+use flownode_core::graph::NodeGraph;
+use flownode_core::image_data::{downscale, ImageData};
+use flownode_core::nodes::{CompositionLayer, MaskSource, Node, NodeProperties, NodeType};
+use uuid::Uuid;
+
+/// Full-resolution side of the test images; proxies are half that
+const SIZE: u32 = 128;
+
+/// Worst per-channel difference allowed between the proxy and the
+/// downscaled full render. Downscaling softens hard edges, so an edge made
+/// after downscaling (a layer's border) differs from one made before.
+const TOLERANCE: f32 = 0.1;
+
+/// White square in the middle of a black image
+fn square() -> ImageData {
+    let mut pixels = Vec::with_capacity((SIZE * SIZE * 4) as usize);
+    for y in 0..SIZE {
+        for x in 0..SIZE {
+            let inside = (32..96).contains(&x) && (32..96).contains(&y);
+            let v = if inside { 1.0 } else { 0.0 };
+            pixels.extend_from_slice(&[v, v, v, 1.0]);
+        }
+    }
+    ImageData::from_f32(pixels, SIZE, SIZE)
+}
+
+/// Feed the test image into a node's input `slot`
+fn feed(graph: &mut NodeGraph, node_id: Uuid, slot: usize) {
+    let image = add(graph, node(NodeType::Image));
+    graph.set_node_image(image, IMAGE);
+    graph.add_connection(image, 0, node_id, slot).unwrap();
+}
+
+/// Image → `node_under_test` → Output, with the test image also fed into
+/// `extra_slots`
+fn graph(node_under_test: Node, extra_slots: &[usize]) -> (NodeGraph, Uuid) {
+    let node_id = node_under_test.id;
+    let (mut graph, output_id) = chain(&[node(NodeType::Image), node_under_test]);
+    graph.working_space = WorkingSpace::Srgb;
+    for slot in extra_slots {
+        feed(&mut graph, node_id, *slot);
+    }
+    (graph, output_id)
+}
+
+/// Largest difference between the proxy run and the full run downscaled,
+/// with the proxy run scaled or not
+fn proxy_error((graph, output_id): (NodeGraph, Uuid), scaled: bool) -> f32 {
+    let run = |image: ImageData, scale: f32| {
+        let mut executor = Executor::new();
+        executor.set_pixel_scale(scale);
+        executor.execute(&graph, &input(image)).unwrap().remove(&output_id).unwrap().remove(0)
+    };
+    let full = downscale(&run(square(), 1.0), SIZE / 2);
+    let proxy = run(downscale(&square(), SIZE / 2), if scaled { 0.5 } else { 1.0 });
+    full.to_f32().iter().zip(proxy.to_f32().iter())
+        .map(|(a, b)| (a - b).abs())
+        .fold(0.0, f32::max)
+}
+
+fn check(build: impl Fn() -> (NodeGraph, Uuid)) {
+    let scaled = proxy_error(build(), true);
+    assert!(scaled <= TOLERANCE, "proxy differs by {}", scaled);
+    // Without scaling the proxy would be visibly different
+    let unscaled = proxy_error(build(), false);
+    assert!(unscaled > TOLERANCE * 2.0, "unscaled proxy only differs by {}", unscaled);
+}
+
+fn effects(setup: impl Fn(&mut NodeProperties)) -> impl Fn() -> (NodeGraph, Uuid) {
+    move || {
+        graph(node_with(NodeType::Effects, &setup), &[])
+    }
+}
+
+#[test]
+fn gaussian_blur() {
+    check(effects(|p| if let NodeProperties::Effects { gaussian_blur, .. } = p {
+        *gaussian_blur = 16.0;
+    }));
+}
+
+#[test]
+fn directional_blur() {
+    check(effects(|p| if let NodeProperties::Effects { directional_blur, directional_blur_angle, .. } = p {
+        *directional_blur = 80.0;
+        *directional_blur_angle = 0.0;
+    }));
+}
+
+#[test]
+fn mask_feather() {
+    // Image → Adjust, darkened through a feathered mask of the square
+    check(|| {
+        let adjust = node_with(NodeType::Adjust, |p| if let NodeProperties::Adjust { brightness, .. } = p {
+            *brightness = -100.0;
+        });
+        let adjust_id = adjust.id;
+        let (mut graph, output_id) = graph(adjust, &[]);
+        let mask_id = add(&mut graph, node_with(NodeType::Mask, |p| if let NodeProperties::Mask { source, feather, .. } = p {
+            *source = MaskSource::Luminance;
+            *feather = 16.0;
+        }));
+        feed(&mut graph, mask_id, 0);
+        graph.add_connection(mask_id, 0, adjust_id, 1).unwrap();
+        (graph, output_id)
+    });
+}
+
+#[test]
+fn composition_layer_position() {
+    check(|| {
+        let composition = node_with(NodeType::Composition, |p| if let NodeProperties::Composition { layers } = p {
+            *layers = vec![
+                CompositionLayer::default(),
+                CompositionLayer { position: (40.0, 20.0), opacity: 0.5, ..Default::default() },
+            ];
+        });
+        graph(composition, &[1])
+    });
+}
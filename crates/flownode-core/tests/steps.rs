@@ -0,0 +1,50 @@
+//! Runs advanced a node at a time, as the Web Worker does so that a newer
+//! run or a cancel can stop one between nodes
+
+mod common;
+
+use common::{chain, input, node, run_first};
+use flownode_core::executor::Executor;
+use flownode_core::image_data::ImageData;
+use flownode_core::nodes::NodeType;
+
+fn image() -> ImageData {
+    ImageData::solid(4, 4, [200, 100, 50, 255])
+}
+
+#[test]
+fn each_step_computes_one_node() {
+    let (graph, output) = chain(&[node(NodeType::Image), node(NodeType::Adjust), node(NodeType::Effects)]);
+    let inputs = input(image());
+    let mut executor = Executor::new();
+
+    let mut run = executor.start(&graph).unwrap();
+    let mut steps = 0;
+    while executor.step(&mut run, &graph, &inputs, &mut |_| true).unwrap() {
+        steps += 1;
+        assert_eq!(executor.stats().computed, steps);
+    }
+    assert_eq!(steps, 4, "Image, Adjust, Effects and Output");
+
+    let mut results = executor.finish(run, &graph);
+    let stepped = results.remove(&output).unwrap().remove(0);
+    assert_eq!(stepped.to_rgba8(), run_first(&graph, output, &inputs).to_rgba8());
+}
+
+#[test]
+fn abandoned_runs_keep_the_nodes_they_computed() {
+    let (graph, output) = chain(&[node(NodeType::Image), node(NodeType::Adjust), node(NodeType::Effects)]);
+    let inputs = input(image());
+    let mut executor = Executor::new();
+
+    // Two nodes in, the run is dropped as a cancelled one is
+    let mut run = executor.start(&graph).unwrap();
+    for _ in 0..2 {
+        assert!(executor.step(&mut run, &graph, &inputs, &mut |_| true).unwrap());
+    }
+    drop(run);
+
+    let results = executor.execute(&graph, &inputs).unwrap();
+    assert_eq!((executor.stats().cached, executor.stats().computed), (2, 2));
+    assert_eq!(results[&output].len(), 1);
+}
@@ -0,0 +1,65 @@
+//! Neighbourhood filters run in tiles on large images; the tiles overlap by
+//! each filter's reach, so the result must match an untiled run
+
+mod common;
+
+use common::{chain, input, node, node_with};
+use flownode_core::executor::Executor;
+use flownode_core::image_data::{ImageData, ImageLimits};
+use flownode_core::nodes::{EdgeMode, NodeProperties, NodeType};
+
+/// Rounding differs with where a tile starts, nothing more
+const TOLERANCE: f32 = 1e-4;
+
+/// Several 64 px tiles, ending part way through the last ones
+const WIDTH: u32 = 150;
+const HEIGHT: u32 = 100;
+
+/// Hard-edged blocks over a gradient
+fn test_image() -> ImageData {
+    let mut pixels = Vec::with_capacity((WIDTH * HEIGHT * 4) as usize);
+    for y in 0..HEIGHT {
+        for x in 0..WIDTH {
+            let block = if (x / 9 + y / 7) % 2 == 0 { 1.0 } else { 0.0 };
+            pixels.extend_from_slice(&[block, x as f32 / WIDTH as f32, (x * y % 13) as f32 / 13.0, 1.0]);
+        }
+    }
+    ImageData::from_f32(pixels, WIDTH, HEIGHT)
+}
+
+fn effects(edge: EdgeMode) -> impl FnOnce(&mut NodeProperties) {
+    move |properties| {
+        if let NodeProperties::Effects { gaussian_blur, blur_edge_mode, directional_blur, directional_blur_angle, sharpen, .. } = properties {
+            *gaussian_blur = 12.0;
+            *blur_edge_mode = edge;
+            *directional_blur = 40.0;
+            *directional_blur_angle = 30.0;
+            *sharpen = 60.0;
+        }
+    }
+}
+
+fn run(edge: EdgeMode, limits: ImageLimits) -> Vec<f32> {
+    let (graph, output) = chain(&[node(NodeType::Image), node_with(NodeType::Effects, effects(edge))]);
+    let mut executor = Executor::new();
+    executor.set_limits(limits);
+    let mut results = executor.execute(&graph, &input(test_image())).unwrap();
+    results.remove(&output).unwrap().remove(0).to_f32()
+}
+
+#[test]
+fn tiles_match_an_untiled_run() {
+    for edge in EdgeMode::ALL {
+        let untiled = run(edge, ImageLimits { tile_size: u32::MAX, ..Default::default() });
+        let tiled = run(edge, ImageLimits { tile_size: 64, ..Default::default() });
+        let (i, error) = untiled.iter().zip(&tiled)
+            .map(|(a, b)| (a - b).abs())
+            .enumerate()
+            .fold((0, 0.0), |worst, (i, d)| if d > worst.1 { (i, d) } else { worst });
+        assert!(
+            error < TOLERANCE,
+            "{}: pixel ({}, {}) differs by {}",
+            edge.name(), i / 4 % WIDTH as usize, i / 4 / WIDTH as usize, error
+        );
+    }
+}
@@ -2,17 +2,47 @@
 
 use eframe::egui;
 use std::collections::HashMap;
-use crate::graph::NodeGraph;
-use crate::image_data::{ImageData, TextureHandle};
-use crate::executor::Executor;
+use crate::color::WorkingSpace;
+use crate::compat::ReactFlowWorkflow;
+use crate::graph::{GraphEditor, OutputPreview, Subgraph};
+use crate::history::History;
+use crate::image_data::{ImageData, ImageLimits};
+use crate::executor::{NodeOutput, Progress};
+use crate::nodes::{CompareMode, NodeProperties};
+use crate::texture::TextureHandle;
+use crate::worker::{Job, Response, RunResult, Worker};
 
 #[cfg(target_arch = "wasm32")]
 use js_sys;
 
+/// Longest side of the low-resolution proxies used while dragging in live mode
+const PROXY_MAX_DIMENSION: u32 = 512;
+
+/// How long the graph must stay unchanged before live mode re-runs it
+const LIVE_PREVIEW_DEBOUNCE: std::time::Duration = std::time::Duration::from_millis(120);
+
+/// A run the worker is busy with
+struct RunningJob {
+    id: u64,
+    proxy: bool,
+    started: std::time::Instant,
+    /// Graph fingerprint when the run started; edits after that cancel it
+    fingerprint: u64,
+    progress: Progress,
+}
+
+/// Export waiting for a full-resolution run to finish
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum PendingExport {
+    Output,
+    AllOutputs,
+    Zip,
+}
+
 /// The main FlowNode application
 pub struct FlowNodeApp {
     /// The node graph editor
-    graph: NodeGraph,
+    graph: GraphEditor,
     
     /// Show the properties panel
     show_properties: bool,
@@ -20,29 +50,65 @@ pub struct FlowNodeApp {
     /// Show the node library
     show_library: bool,
     
+    /// Show the undo history panel
+    show_history: bool,
+    
+    /// Show the value each wire carried in the last run
+    show_wire_previews: bool,
+    
+    /// Undo/redo history of graph edits
+    history: History,
+    
     /// Current zoom level (for status bar)
     zoom: f32,
     
     /// Dark mode (always true for now)
     dark_mode: bool,
     
-    /// Loaded images (keyed by a unique ID)
-    images: HashMap<u64, ImageData>,
-    
-    /// Texture cache for rendering
+    /// Texture cache for rendering (display-sized; the worker keeps the full-resolution images)
     textures: HashMap<u64, TextureHandle>,
     
+    /// Proxy size relative to the full image, for each loaded image
+    proxy_scales: HashMap<u64, f32>,
+    
+    /// Decode and processing limits
+    limits: ImageLimits,
+    
+    /// Limits last sent to the worker
+    worker_limits: ImageLimits,
+    
     /// Next image ID
     next_image_id: u64,
     
     /// Pending image load (node ID waiting for image)
     pending_image_load: Option<uuid::Uuid>,
     
-    /// Node graph executor
-    executor: Executor,
+    /// Runs the graph off the UI thread
+    worker: Worker,
+    
+    /// The run in progress, if any
+    running: Option<RunningJob>,
+    
+    /// Export to do once a full-resolution run finishes
+    pending_export: Option<PendingExport>,
+    
+    /// Re-run the graph automatically when properties change
+    live_preview: bool,
+    
+    /// Graph fingerprint seen by live mode on the last frame
+    live_fingerprint: u64,
+    
+    /// When the graph last changed, if live mode hasn't run since
+    live_changed_at: Option<std::time::Instant>,
+    
+    /// A full-resolution run is still owed (after proxy runs during a drag)
+    live_full_pending: bool,
+    
+    /// The output currently shown is a low-resolution proxy
+    output_is_proxy: bool,
     
-    /// Clipboard for copy/paste
-    clipboard: Option<crate::nodes::Node>,
+    /// Last nodes copied or pasted, with the clipboard text they came from
+    clipboard: Option<(String, Subgraph)>,
     
     /// Waiting for cloud load to complete
     cloud_load_pending: bool,
@@ -50,10 +116,16 @@ pub struct FlowNodeApp {
     /// Waiting for cloud save to complete
     cloud_save_pending: bool,
     
-    /// Output image from last execution
-    output_image: Option<ImageData>,
+    /// Results of every Output node from the last execution (one per batch item)
+    output_images: HashMap<uuid::Uuid, Vec<ImageData>>,
     
-    /// Output texture for display
+    /// Output node currently shown in the preview
+    preview_output: Option<uuid::Uuid>,
+    
+    /// Batch item of the previewed output
+    preview_item: usize,
+    
+    /// Texture of the previewed output
     output_texture: Option<TextureHandle>,
     
     /// Status message for user feedback
@@ -74,18 +146,34 @@ impl FlowNodeApp {
         
         cc.egui_ctx.set_style(style);
         
+        let graph = GraphEditor::new();
+        let history = History::new(&graph);
         let mut app = Self {
-            graph: NodeGraph::new(),
+            graph,
             show_properties: true,
             show_library: true,
+            show_history: false,
+            show_wire_previews: false,
+            history,
             zoom: 1.0,
-            images: HashMap::new(),
             textures: HashMap::new(),
+            proxy_scales: HashMap::new(),
+            limits: ImageLimits::default(),
+            worker_limits: ImageLimits::default(),
             next_image_id: 1,
             pending_image_load: None,
             dark_mode: true,
-            executor: Executor::new(),
-            output_image: None,
+            worker: Worker::spawn(&cc.egui_ctx),
+            running: None,
+            pending_export: None,
+            live_preview: false,
+            live_fingerprint: 0,
+            live_changed_at: None,
+            live_full_pending: false,
+            output_is_proxy: false,
+            output_images: HashMap::new(),
+            preview_output: None,
+            preview_item: 0,
             output_texture: None,
             clipboard: None,
             cloud_load_pending: false,
@@ -106,7 +194,7 @@ impl FlowNodeApp {
     
     /// Load image from bytes and assign to selected node or create new node
     fn load_image_bytes(&mut self, ctx: &egui::Context, bytes: &[u8]) {
-        match crate::image_data::decode_image(bytes) {
+        match crate::image_data::decode_image(bytes, &self.limits) {
             Ok(image_data) => {
                 let image_id = self.next_image_id;
                 self.next_image_id += 1;
@@ -116,18 +204,22 @@ impl FlowNodeApp {
                     ctx,
                     &format!("image_{}", image_id),
                     &image_data,
+                    self.limits.display_max_dimension,
                 );
                 
                 let msg = format!("✓ Loaded image {}×{}", image_data.width, image_data.height);
                 log::info!("{}", msg);
                 
-                // Store the image and texture
-                self.images.insert(image_id, image_data);
+                // Hand the image and its live preview proxy to the worker, keep the texture
+                let proxy = crate::image_data::downscale(&image_data, PROXY_MAX_DIMENSION);
+                self.proxy_scales.insert(image_id, proxy.width as f32 / image_data.width as f32);
+                self.worker.add_image(image_id, true, proxy);
+                self.worker.add_image(image_id, false, image_data);
                 self.textures.insert(image_id, texture);
                 
                 self.set_status(&msg);
                 
-                // Assign to selected node if it's an Image node, otherwise create new
+                // Assign to selected node if it's an Image or Bucket node, otherwise create new
                 if let Some(node_id) = self.graph.selected_node() {
                     if self.graph.set_node_image(node_id, image_id) {
                         log::info!("Assigned image to selected node");
@@ -141,52 +233,353 @@ impl FlowNodeApp {
                 log::info!("Created new ImageInput node with image");
             }
             Err(e) => {
+                self.set_status(&format!("✗ {}", e));
                 log::error!("Failed to load image: {}", e);
             }
         }
     }
     
+    /// Load a .cube file into the selected LUT node or a new one
+    fn load_lut_text(&mut self, file_name: &str, text: &str) {
+        let lut = match crate::lut::Lut::parse_cube(text) {
+            Ok(lut) => lut,
+            Err(e) => {
+                self.set_status(&format!("✗ Invalid LUT {}: {}", file_name, e));
+                log::error!("Failed to load LUT {}: {}", file_name, e);
+                return;
+            }
+        };
+        self.set_status(&format!("✓ Loaded LUT {}", file_name));
+        
+        if let Some(node_id) = self.graph.selected_node() {
+            if self.graph.set_node_lut(node_id, file_name, lut.clone()) {
+                return;
+            }
+        }
+        
+        let node_id = self.graph.add_node(crate::nodes::NodeType::Lut);
+        self.graph.set_node_lut(node_id, file_name, lut);
+    }
+    
+    /// Bake the Adjust/LUT chain ending at the selected node into a .cube file
+    fn export_lut(&mut self) {
+        let Some(node_id) = self.graph.selected_node() else {
+            self.set_status("⚠ Select an Adjust or LUT node to export");
+            return;
+        };
+        let title = self.graph.nodes.get(&node_id)
+            .and_then(|n| n.label.clone())
+            .unwrap_or_else(|| "FlowNode Grade".to_string());
+        
+        let lut = match crate::lut::bake(&self.graph, node_id, crate::lut::BAKE_SIZE, &title) {
+            Ok(lut) => lut,
+            Err(e) => {
+                self.set_status(&format!("⚠ {}", e));
+                return;
+            }
+        };
+        let file_name = format!("{}.cube", title.replace(['/', '\\'], "-"));
+        let bytes = lut.to_cube().into_bytes();
+        
+        #[cfg(target_arch = "wasm32")]
+        {
+            download_bytes(&file_name, "text/plain", &bytes);
+            self.set_status(&format!("✓ Exported {}", file_name));
+        }
+        #[cfg(not(target_arch = "wasm32"))]
+        {
+            if let Some(path) = rfd::FileDialog::new()
+                .add_filter("Cube LUT", &["cube"])
+                .set_file_name(&file_name)
+                .save_file()
+            {
+                match std::fs::write(&path, &bytes) {
+                    Ok(()) => self.set_status(&format!("✓ Exported {}", path.display())),
+                    Err(e) => self.set_status(&format!("✗ Export failed: {}", e)),
+                }
+            }
+        }
+    }
+    
     /// Get texture handle by ID
     pub fn get_texture(&self, image_id: u64) -> Option<&TextureHandle> {
         self.textures.get(&image_id)
     }
     
-    /// Export the output image
-    fn export_output(&self) {
-        if let Some(output) = &self.output_image {
-            match crate::image_data::encode_png(output) {
-                Ok(png_bytes) => {
-                    #[cfg(target_arch = "wasm32")]
-                    {
-                        // Download via JavaScript
-                        let encoded = base64::Engine::encode(&base64::engine::general_purpose::STANDARD, &png_bytes);
-                        let js_code = format!(
-                            r#"
-                            const link = document.createElement('a');
-                            link.href = 'data:image/png;base64,{}';
-                            link.download = 'flownode-output.png';
-                            link.click();
-                            "#,
-                            encoded
-                        );
-                        let _ = js_sys::eval(&js_code);
+    /// Name and format of an Output node (for export file names)
+    fn output_settings(&self, node_id: uuid::Uuid) -> (String, crate::nodes::ExportFormat) {
+        match self.graph.nodes.get(&node_id).map(|n| &n.properties) {
+            Some(crate::nodes::NodeProperties::Output { name, format, .. }) => {
+                let name = if name.trim().is_empty() { "output".to_string() } else { name.trim().to_string() };
+                (name, *format)
+            }
+            _ => ("output".to_string(), crate::nodes::ExportFormat::Png),
+        }
+    }
+    
+    /// Output node IDs sorted by name (for stable UI order)
+    fn sorted_outputs(&self) -> Vec<uuid::Uuid> {
+        let mut ids: Vec<uuid::Uuid> = self.output_images.keys().copied().collect();
+        ids.sort_by_key(|id| (self.output_settings(*id).0, *id));
+        ids
+    }
+    
+    /// The split-mode Compare node feeding the previewed output, with its
+    /// mode and divider position, so the divider can be dragged on the preview
+    fn preview_compare_split(&self) -> Option<(uuid::Uuid, CompareMode, f32)> {
+        let output = self.preview_output?;
+        let source = self.graph.connections.iter().find(|c| c.to_node == output)?.from_node;
+        match self.graph.nodes.get(&source)?.properties {
+            NodeProperties::Compare { mode: mode @ (CompareMode::SplitVertical | CompareMode::SplitHorizontal), split, .. } => {
+                Some((source, mode, split))
+            }
+            _ => None,
+        }
+    }
+    
+    /// Encode one item of an output and return (file name, mime type, bytes).
+    /// Batch items are numbered from 1.
+    fn encode_output(&self, node_id: uuid::Uuid, item: usize) -> Option<(String, &'static str, Vec<u8>)> {
+        let items = self.output_images.get(&node_id)?;
+        let image = items.get(item)?;
+        let (mut name, format) = self.output_settings(node_id);
+        if items.len() > 1 {
+            name = format!("{}-{:03}", name, item + 1);
+        }
+        let depth = match self.graph.nodes.get(&node_id).map(|n| &n.properties) {
+            Some(crate::nodes::NodeProperties::Output { bit_depth, .. }) => *bit_depth,
+            _ => crate::nodes::ExportDepth::Eight,
+        };
+        
+        match crate::image_data::encode_image(image, format, depth) {
+            Ok(bytes) => Some((format!("{}.{}", name, format.extension()), format.mime_type(), bytes)),
+            Err(e) => {
+                log::error!("Failed to encode {}: {}", name, e);
+                None
+            }
+        }
+    }
+    
+    /// Whether full-resolution outputs are ready to export. If not, a full run
+    /// is started (unless one is going) and the export waits for it.
+    fn ready_to_export(&mut self, export: PendingExport) -> bool {
+        let running_full = self.running.as_ref().is_some_and(|job| !job.proxy);
+        if !running_full && !self.output_is_proxy && self.running.is_none() {
+            return true;
+        }
+        if !running_full {
+            self.run_graph();
+        }
+        self.pending_export = Some(export);
+        self.set_status("⏳ Exporting when the full-resolution run finishes");
+        false
+    }
+    
+    /// Export the previewed output image
+    fn export_output(&mut self) {
+        if !self.ready_to_export(PendingExport::Output) {
+            return;
+        }
+        let Some(node_id) = self.preview_output else {
+            self.set_status("⚠ Nothing to export (run the graph first)");
+            return;
+        };
+        let Some((file_name, mime, bytes)) = self.encode_output(node_id, self.preview_item) else {
+            return;
+        };
+        
+        #[cfg(target_arch = "wasm32")]
+        {
+            download_bytes(&file_name, mime, &bytes);
+            self.set_status(&format!("✓ Exported {}", file_name));
+        }
+        #[cfg(not(target_arch = "wasm32"))]
+        {
+            let _ = mime;
+            if let Some(path) = rfd::FileDialog::new().set_file_name(&file_name).save_file() {
+                match std::fs::write(&path, &bytes) {
+                    Ok(()) => self.set_status(&format!("✓ Exported {}", path.display())),
+                    Err(e) => self.set_status(&format!("✗ Export failed: {}", e)),
+                }
+            }
+        }
+    }
+    
+    /// Total number of output images, counting every batch item
+    fn output_count(&self) -> usize {
+        self.output_images.values().map(Vec::len).sum()
+    }
+    
+    /// Encode every item of every output, named so nothing gets overwritten
+    fn encode_all_outputs(&self) -> Vec<(String, &'static str, Vec<u8>)> {
+        let mut files: Vec<(String, &'static str, Vec<u8>)> = Vec::new();
+        for node_id in self.sorted_outputs() {
+            for item in 0..self.output_images[&node_id].len() {
+                if let Some((mut file_name, mime, bytes)) = self.encode_output(node_id, item) {
+                    // Outputs may share a name; suffix duplicates
+                    let (stem, extension) = match file_name.rsplit_once('.') {
+                        Some((stem, extension)) => (stem.to_string(), extension.to_string()),
+                        None => (file_name.clone(), String::new()),
+                    };
+                    let mut n = 2;
+                    while files.iter().any(|(f, _, _)| *f == file_name) {
+                        file_name = format!("{}-{}.{}", stem, n, extension);
+                        n += 1;
                     }
-                    #[cfg(not(target_arch = "wasm32"))]
-                    {
-                        log::info!("Export: {} bytes PNG", png_bytes.len());
+                    files.push((file_name, mime, bytes));
+                }
+            }
+        }
+        files
+    }
+    
+    /// Export every output image at once
+    fn export_all_outputs(&mut self) {
+        if !self.ready_to_export(PendingExport::AllOutputs) {
+            return;
+        }
+        if self.output_images.is_empty() {
+            self.set_status("⚠ Nothing to export (run the graph first)");
+            return;
+        }
+        
+        let files = self.encode_all_outputs();
+        
+        #[cfg(target_arch = "wasm32")]
+        {
+            for (file_name, mime, bytes) in &files {
+                download_bytes(file_name, mime, bytes);
+            }
+            self.set_status(&format!("✓ Exported {} outputs", files.len()));
+        }
+        #[cfg(not(target_arch = "wasm32"))]
+        {
+            if let Some(dir) = rfd::FileDialog::new().pick_folder() {
+                for (file_name, _, bytes) in &files {
+                    if let Err(e) = std::fs::write(dir.join(file_name), bytes) {
+                        self.set_status(&format!("✗ Export failed: {}", e));
+                        return;
                     }
                 }
-                Err(e) => {
-                    log::error!("Failed to encode PNG: {}", e);
+                self.set_status(&format!("✓ Exported {} outputs to {}", files.len(), dir.display()));
+            }
+        }
+    }
+    
+    /// Export every output image in a single zip archive
+    fn export_zip(&mut self) {
+        if !self.ready_to_export(PendingExport::Zip) {
+            return;
+        }
+        if self.output_images.is_empty() {
+            self.set_status("⚠ Nothing to export (run the graph first)");
+            return;
+        }
+        
+        let files: Vec<(String, Vec<u8>)> = self.encode_all_outputs()
+            .into_iter()
+            .map(|(file_name, _, bytes)| (file_name, bytes))
+            .collect();
+        let count = files.len();
+        let bytes = match crate::zip::write_zip(&files) {
+            Ok(bytes) => bytes,
+            Err(e) => {
+                self.set_status(&format!("✗ Export failed: {}", e));
+                return;
+            }
+        };
+        let file_name = "flownode-outputs.zip";
+        
+        #[cfg(target_arch = "wasm32")]
+        {
+            download_bytes(file_name, "application/zip", &bytes);
+            self.set_status(&format!("✓ Exported {} outputs to {}", count, file_name));
+        }
+        #[cfg(not(target_arch = "wasm32"))]
+        {
+            if let Some(path) = rfd::FileDialog::new()
+                .add_filter("Zip archive", &["zip"])
+                .set_file_name(file_name)
+                .save_file()
+            {
+                match std::fs::write(&path, &bytes) {
+                    Ok(()) => self.set_status(&format!("✓ Exported {} outputs to {}", count, path.display())),
+                    Err(e) => self.set_status(&format!("✗ Export failed: {}", e)),
                 }
             }
         }
     }
     
+    /// Recreate the preview texture for the selected output
+    fn refresh_output_texture(&mut self, ctx: &egui::Context) {
+        self.output_texture = self.preview_output
+            .and_then(|id| self.output_images.get(&id))
+            .and_then(|items| items.get(self.preview_item.min(items.len().saturating_sub(1))))
+            .map(|image| TextureHandle::from_image_data(ctx, "output", image, self.limits.display_max_dimension));
+    }
+    
+    /// Rebuild the per-node thumbnails and text previews from a run's previews
+    fn refresh_output_previews(&mut self, ctx: &egui::Context, previews: HashMap<uuid::Uuid, NodeOutput>) {
+        let previews = previews.into_iter()
+            .filter_map(|(id, output)| {
+                let preview = match output {
+                    NodeOutput::Image(image) => OutputPreview::Image(TextureHandle::from_image_data(
+                        ctx,
+                        &format!("preview_{}", id),
+                        &image,
+                        crate::ui_components::style::PREVIEW_TEXTURE_SIZE,
+                    )),
+                    NodeOutput::Text(text) => OutputPreview::Text(text),
+                    NodeOutput::Mask(_) | NodeOutput::Batch(_) | NodeOutput::None => return None,
+                };
+                Some((id, preview))
+            })
+            .collect();
+        self.graph.set_output_previews(previews);
+    }
+    
     /// Handle keyboard shortcuts - matches React app
     fn handle_keyboard_shortcuts(&mut self, ctx: &egui::Context) {
         use crate::nodes::NodeType;
         
+        // Text fields keep their own undo and editing keys
+        let typing = ctx.wants_keyboard_input();
+        
+        // Ctrl+Z = Undo, Ctrl+Shift+Z / Ctrl+Y = Redo
+        let (undo, redo) = ctx.input(|i| {
+            let ctrl = i.modifiers.ctrl || i.modifiers.command;
+            let z = ctrl && i.key_pressed(egui::Key::Z);
+            let redo = (z && i.modifiers.shift) || (ctrl && i.key_pressed(egui::Key::Y));
+            (!typing && z && !i.modifiers.shift, !typing && redo)
+        });
+        if undo {
+            self.undo();
+        }
+        if redo {
+            self.redo();
+        }
+        
+        // Copy/cut/paste arrive as events rather than key presses
+        let clipboard_events: Vec<egui::Event> = ctx.input(|i| {
+            i.events.iter()
+                .filter(|e| matches!(e, egui::Event::Copy | egui::Event::Cut | egui::Event::Paste(_)))
+                .cloned()
+                .collect()
+        });
+        if !typing {
+            for event in clipboard_events {
+                match event {
+                    egui::Event::Copy => self.copy_selection(ctx),
+                    egui::Event::Cut => {
+                        self.copy_selection(ctx);
+                        self.graph.delete_selected();
+                    }
+                    egui::Event::Paste(text) => self.paste_text(&text),
+                    _ => {}
+                }
+            }
+        }
+        
         ctx.input(|i| {
             // Only handle shortcuts when no text input is focused
             // Note: Could use i.events to check for text focus, but skipping for now
@@ -201,27 +594,11 @@ impl FlowNodeApp {
                 // Will call run_graph after this closure
             }
             
-            // Ctrl+C = Copy selected node
-            if ctrl && i.key_pressed(egui::Key::C) {
-                if let Some(node) = self.graph.get_selected_node_clone() {
-                    self.clipboard = Some(node);
-                    log::info!("Copied node to clipboard");
-                }
-            }
-            
-            // Ctrl+V = Paste node
-            if ctrl && i.key_pressed(egui::Key::V) {
-                if let Some(ref node) = self.clipboard.clone() {
-                    self.graph.paste_node(node);
-                    log::info!("Pasted node from clipboard");
-                }
-            }
-            
-            // Ctrl+D = Duplicate selected node
-            if ctrl && i.key_pressed(egui::Key::D) {
-                if let Some(node) = self.graph.get_selected_node_clone() {
-                    self.graph.paste_node(&node);
-                    log::info!("Duplicated node");
+            // Ctrl+D = Duplicate selected nodes
+            if !typing && ctrl && i.key_pressed(egui::Key::D) {
+                if let Some(subgraph) = self.graph.copy_selection() {
+                    self.graph.paste_subgraph(&subgraph);
+                    log::info!("Duplicated {} nodes", subgraph.nodes.len());
                 }
             }
             
@@ -231,7 +608,7 @@ impl FlowNodeApp {
             }
             
             // Delete/Backspace = Delete selected
-            if i.key_pressed(egui::Key::Delete) || i.key_pressed(egui::Key::Backspace) {
+            if !typing && (i.key_pressed(egui::Key::Delete) || i.key_pressed(egui::Key::Backspace)) {
                 self.graph.delete_selected();
             }
             
@@ -259,6 +636,9 @@ impl FlowNodeApp {
                 if i.key_pressed(egui::Key::Q) { self.graph.add_node(NodeType::Batch); }
                 if i.key_pressed(egui::Key::H) { self.graph.add_node(NodeType::Title); }
                 
+                // Output nodes
+                if i.key_pressed(egui::Key::X) { self.graph.add_node(NodeType::Output); }
+                
                 // AI nodes
                 if i.key_pressed(egui::Key::O) { self.graph.add_node(NodeType::Omni); }
                 if i.key_pressed(egui::Key::L) { self.graph.add_node(NodeType::Llm); }
@@ -272,7 +652,7 @@ impl FlowNodeApp {
         
         // Run graph with Ctrl+G
         if ctx.input(|i| (i.modifiers.ctrl || i.modifiers.command) && i.key_pressed(egui::Key::G)) {
-            self.run_graph(ctx);
+            self.run_graph();
         }
         
         // Ctrl+S = Save to local storage
@@ -281,6 +661,64 @@ impl FlowNodeApp {
         }
     }
     
+    /// Copy the selected nodes and the edges between them to the system
+    /// clipboard as React Flow JSON, readable by the web app too
+    fn copy_selection(&mut self, ctx: &egui::Context) {
+        let Some(subgraph) = self.graph.copy_selection() else {
+            return;
+        };
+        
+        match serde_json::to_string_pretty(&ReactFlowWorkflow::from_subgraph(&subgraph)) {
+            Ok(json) => {
+                ctx.copy_text(json.clone());
+                log::info!("Copied {} nodes to clipboard", subgraph.nodes.len());
+                self.clipboard = Some((json, subgraph));
+            }
+            Err(e) => log::error!("Failed to copy nodes: {}", e),
+        }
+    }
+    
+    /// Paste nodes from React Flow JSON on the clipboard, with new IDs
+    fn paste_text(&mut self, text: &str) {
+        // Pasting the same text again continues from the previous paste
+        let same = self.clipboard.as_ref().is_some_and(|(copied, _)| copied.trim() == text.trim());
+        if !same {
+            let parsed = serde_json::from_str::<ReactFlowWorkflow>(text)
+                .map_err(|e| e.to_string())
+                .and_then(|workflow| workflow.to_subgraph());
+            match parsed {
+                Ok(subgraph) => self.clipboard = Some((text.to_string(), subgraph)),
+                Err(e) => {
+                    log::info!("Clipboard doesn't hold nodes: {}", e);
+                    return;
+                }
+            }
+        }
+        
+        if let Some((_, subgraph)) = &mut self.clipboard {
+            let pasted = self.graph.paste_subgraph(subgraph);
+            // Cascade repeated pastes instead of stacking them
+            for node in &mut subgraph.nodes {
+                node.position += crate::nodes::Vec2::new(30.0, 30.0);
+            }
+            self.set_status(&format!("Pasted {} nodes", pasted.len()));
+        }
+    }
+    
+    fn undo(&mut self) {
+        if let Some(label) = self.history.undo_label().map(str::to_string) {
+            self.history.undo(&mut self.graph);
+            self.set_status(&format!("Undo: {}", label));
+        }
+    }
+    
+    fn redo(&mut self) {
+        if let Some(label) = self.history.redo_label().map(str::to_string) {
+            self.history.redo(&mut self.graph);
+            self.set_status(&format!("Redo: {}", label));
+        }
+    }
+    
     /// Save workflow to browser's local storage
     fn save_to_local_storage(&mut self) {
         #[cfg(target_arch = "wasm32")]
@@ -392,6 +830,8 @@ impl FlowNodeApp {
                 crate::nodes::NodeType::Bucket => "bucket",
                 crate::nodes::NodeType::Adjust => "adjust",
                 crate::nodes::NodeType::Effects => "effects",
+                crate::nodes::NodeType::Lut => "lut",
+                crate::nodes::NodeType::Mask => "mask",
                 crate::nodes::NodeType::Text => "text",
                 crate::nodes::NodeType::Concat => "concat",
                 crate::nodes::NodeType::Splitter => "splitter",
@@ -401,6 +841,7 @@ impl FlowNodeApp {
                 crate::nodes::NodeType::Router => "router",
                 crate::nodes::NodeType::Batch => "batch",
                 crate::nodes::NodeType::Title => "title",
+                crate::nodes::NodeType::Output => "output",
                 crate::nodes::NodeType::Omni => "omni",
                 crate::nodes::NodeType::Llm => "llm",
                 crate::nodes::NodeType::Video => "video",
@@ -465,7 +906,7 @@ impl FlowNodeApp {
                 })
             }
             crate::nodes::NodeProperties::Effects {
-                gaussian_blur, sharpen, grain, vignette,
+                gaussian_blur, blur_edge_mode, sharpen, grain, vignette,
                 directional_blur, directional_blur_angle,
                 progressive_blur, progressive_blur_direction, progressive_blur_falloff,
                 glass_blinds, glass_blinds_frequency, glass_blinds_angle, glass_blinds_phase,
@@ -475,6 +916,7 @@ impl FlowNodeApp {
                 serde_json::json!({
                     "settings": {
                         "gaussianBlur": gaussian_blur,
+                        "blurEdgeMode": blur_edge_mode.as_str(),
                         "sharpen": sharpen,
                         "grain": grain,
                         "grainSize": grain_size,
@@ -495,6 +937,25 @@ impl FlowNodeApp {
                     }
                 })
             }
+            crate::nodes::NodeProperties::Lut { file_name, lut, interpolation, intensity } => {
+                serde_json::json!({
+                    "fileName": file_name,
+                    "cube": lut.as_ref().and_then(|r| self.graph.luts.get(r.hash)).map(|l| l.to_cube()),
+                    "interpolation": interpolation.as_str(),
+                    "intensity": intensity
+                })
+            }
+            crate::nodes::NodeProperties::Mask { source, center, size, angle, invert, feather, density } => {
+                serde_json::json!({
+                    "source": source.as_str(),
+                    "center": center,
+                    "size": size,
+                    "angle": angle,
+                    "invert": invert,
+                    "feather": feather,
+                    "density": density
+                })
+            }
             crate::nodes::NodeProperties::Text { text } => {
                 serde_json::json!({ "text": text })
             }
@@ -606,6 +1067,8 @@ impl FlowNodeApp {
                         // Parse and convert the cloud data
                         match self.convert_cloud_workflow(&json_str) {
                             Ok((node_count, edge_count, name)) => {
+                                // History starts from the loaded graph, not the empty one it was built on
+                                self.history.reset(&self.graph);
                                 self.set_status(&format!("✓ Loaded: {} ({} nodes, {} edges)", 
                                     name, node_count, edge_count));
                             }
@@ -643,7 +1106,7 @@ impl FlowNodeApp {
             .unwrap_or(&empty_edges);
         
         // Clear current graph
-        self.graph = crate::graph::NodeGraph::new();
+        self.replace_graph(GraphEditor::new());
         
         // Map of old IDs to new UUIDs
         let mut id_map: std::collections::HashMap<String, uuid::Uuid> = std::collections::HashMap::new();
@@ -668,6 +1131,8 @@ impl FlowNodeApp {
                 "bucket" => crate::nodes::NodeType::Bucket,
                 "adjust" => crate::nodes::NodeType::Adjust,
                 "effects" => crate::nodes::NodeType::Effects,
+                "lut" => crate::nodes::NodeType::Lut,
+                "mask" => crate::nodes::NodeType::Mask,
                 "text" => crate::nodes::NodeType::Text,
                 "concat" => crate::nodes::NodeType::Concat,
                 "splitter" => crate::nodes::NodeType::Splitter,
@@ -677,6 +1142,7 @@ impl FlowNodeApp {
                 "router" => crate::nodes::NodeType::Router,
                 "batch" => crate::nodes::NodeType::Batch,
                 "title" => crate::nodes::NodeType::Title,
+                "output" => crate::nodes::NodeType::Output,
                 "omni" => crate::nodes::NodeType::Omni,
                 "llm" => crate::nodes::NodeType::Llm,
                 "video" => crate::nodes::NodeType::Video,
@@ -691,7 +1157,7 @@ impl FlowNodeApp {
             };
             
             // Create node at the correct position
-            let mut new_node = crate::nodes::Node::new(node_type, egui::Vec2::new(x, y));
+            let mut new_node = crate::nodes::Node::new(node_type, crate::nodes::Vec2::new(x, y));
             
             // Try to extract and apply node data/settings
             if let Some(data) = node.get("data") {
@@ -708,21 +1174,21 @@ impl FlowNodeApp {
             let source = edge.get("source").and_then(|s| s.as_str()).unwrap_or("");
             let target = edge.get("target").and_then(|t| t.as_str()).unwrap_or("");
             
-            // Parse handle indices (format: "output-0", "input-0")
-            let source_slot = edge.get("sourceHandle")
-                .and_then(|h| h.as_str())
-                .and_then(|h| h.strip_prefix("output-"))
-                .and_then(|s| s.parse::<usize>().ok())
-                .unwrap_or(0);
-            
-            let target_slot = edge.get("targetHandle")
-                .and_then(|h| h.as_str())
-                .and_then(|h| h.strip_prefix("input-"))
-                .and_then(|s| s.parse::<usize>().ok())
-                .unwrap_or(0);
+            let (Some(&from_id), Some(&to_id)) = (id_map.get(source), id_map.get(target)) else {
+                continue;
+            };
+            let handle = |key: &str| edge.get(key).and_then(|h| h.as_str());
+            let slots = self.graph.nodes.get(&from_id)
+                .and_then(|n| crate::compat::output_slot(n.node_type, handle("sourceHandle")))
+                .zip(self.graph.nodes.get(&to_id)
+                    .and_then(|n| crate::compat::input_slot(n.node_type, handle("targetHandle"))));
+            let Some((source_slot, target_slot)) = slots else {
+                log::warn!("Skipped cloud connection {} -> {}: unknown handle", source, target);
+                continue;
+            };
             
-            if let (Some(&from_id), Some(&to_id)) = (id_map.get(source), id_map.get(target)) {
-                self.graph.add_connection(from_id, source_slot, to_id, target_slot);
+            if let Err(e) = self.graph.add_connection(from_id, source_slot, to_id, target_slot) {
+                log::warn!("Skipped cloud connection {} -> {}: {}", source, target, e);
             }
         }
         
@@ -734,12 +1200,15 @@ impl FlowNodeApp {
             // TODO: Apply viewport to graph
         }
         
+        // React has no Output node type
+        self.graph.add_missing_outputs();
+        
         log::info!("Converted {} nodes and {} edges", nodes.len(), edges.len());
         Ok((nodes.len(), edges.len(), name))
     }
     
     /// Apply React Flow node data to our node properties
-    fn apply_node_data(&self, node: &mut crate::nodes::Node, data: &serde_json::Value) {
+    fn apply_node_data(&mut self, node: &mut crate::nodes::Node, data: &serde_json::Value) {
         match &mut node.properties {
             crate::nodes::NodeProperties::Adjust { 
                 brightness, contrast, saturation, exposure,
@@ -769,6 +1238,40 @@ impl FlowNodeApp {
                     *vignette = settings.get("vignette").and_then(|v| v.as_f64()).unwrap_or(0.0) as f32;
                 }
             }
+            crate::nodes::NodeProperties::Lut { file_name, lut, interpolation, intensity } => {
+                if let Some(name) = data.get("fileName").and_then(|v| v.as_str()) {
+                    *file_name = name.to_string();
+                }
+                if let Some(cube) = data.get("cube").and_then(|v| v.as_str()) {
+                    match crate::lut::Lut::parse_cube(cube) {
+                        Ok(parsed) => *lut = Some(self.graph.luts.insert(parsed)),
+                        Err(e) => log::warn!("Invalid LUT in node data: {}", e),
+                    }
+                }
+                if let Some(i) = data.get("interpolation").and_then(|v| v.as_str()).and_then(crate::nodes::LutInterpolation::parse) {
+                    *interpolation = i;
+                }
+                *intensity = data.get("intensity").and_then(|v| v.as_f64()).unwrap_or(100.0) as f32;
+            }
+            crate::nodes::NodeProperties::Mask { source, center, size, angle, invert, feather, density } => {
+                if let Some(s) = data.get("source").and_then(|v| v.as_str()).and_then(crate::nodes::MaskSource::parse) {
+                    *source = s;
+                }
+                let pair = |key: &str| -> Option<[f32; 2]> {
+                    let v = data.get(key)?.as_array()?;
+                    Some([v.first()?.as_f64()? as f32, v.get(1)?.as_f64()? as f32])
+                };
+                if let Some(c) = pair("center") {
+                    *center = c;
+                }
+                if let Some(s) = pair("size") {
+                    *size = s;
+                }
+                *angle = data.get("angle").and_then(|v| v.as_f64()).unwrap_or(0.0) as f32;
+                *invert = data.get("invert").and_then(|v| v.as_bool()).unwrap_or(false);
+                *feather = data.get("feather").and_then(|v| v.as_f64()).unwrap_or(0.0) as f32;
+                *density = data.get("density").and_then(|v| v.as_f64()).unwrap_or(100.0) as f32;
+            }
             crate::nodes::NodeProperties::Text { text } => {
                 if let Some(t) = data.get("text").and_then(|t| t.as_str()) {
                     *text = t.to_string();
@@ -803,7 +1306,7 @@ impl FlowNodeApp {
                     if !json_str.is_empty() {
                         match crate::graph::NodeGraph::from_json(&json_str) {
                             Ok(graph) => {
-                                self.graph = graph;
+                                self.replace_graph(GraphEditor::from(graph));
                                 self.set_status("✓ Loaded from browser storage");
                                 log::info!("Loaded workflow from localStorage");
                             }
@@ -818,52 +1321,218 @@ impl FlowNodeApp {
         }
     }
     
+    /// Swap in another graph (a new or loaded project). Undo steps belong to
+    /// the old graph, so history starts again from this one.
+    fn replace_graph(&mut self, graph: GraphEditor) {
+        self.graph = graph;
+        self.history.reset(&self.graph);
+    }
+    
     /// Run the node graph and produce output
-    fn run_graph(&mut self, ctx: &egui::Context) {
-        log::info!("Running node graph...");
-        let start = std::time::Instant::now();
+    fn run_graph(&mut self) {
+        self.execute_graph(false);
+    }
+    
+    /// Run the node graph on the low-resolution proxies
+    fn run_graph_proxy(&mut self) {
+        self.execute_graph(true);
+    }
+    
+    /// How much the proxies of the graph's images are downscaled. With images
+    /// of different sizes the most reduced one sets the scale.
+    fn proxy_scale(&self) -> f32 {
+        self.graph.nodes.values()
+            .flat_map(|node| match &node.properties {
+                NodeProperties::Image { texture_id, .. } => texture_id.iter().copied().collect(),
+                NodeProperties::Bucket { image_ids, .. } => image_ids.clone(),
+                _ => Vec::new(),
+            })
+            .filter_map(|id| self.proxy_scales.get(&id).copied())
+            .fold(1.0, f32::min)
+    }
+    
+    /// Start a run on the worker, replacing any run in progress
+    fn execute_graph(&mut self, proxy: bool) {
+        let id = self.worker.run(Job {
+            graph: crate::graph::NodeGraph::clone(&self.graph),
+            proxy,
+            pixel_scale: if proxy { self.proxy_scale() } else { 1.0 },
+            preview_size: crate::ui_components::style::PREVIEW_TEXTURE_SIZE,
+        });
+        self.running = Some(RunningJob {
+            id,
+            proxy,
+            started: std::time::Instant::now(),
+            fingerprint: self.graph.fingerprint(),
+            progress: Progress::default(),
+        });
+    }
+    
+    /// Stop the run in progress
+    fn cancel_run(&mut self) {
+        if self.running.take().is_some() {
+            self.worker.cancel();
+            self.pending_export = None;
+            log::info!("Run cancelled");
+        }
+    }
+    
+    /// Apply whatever the worker has sent since the last frame
+    fn poll_worker(&mut self, ctx: &egui::Context) {
+        // Edits make the run in progress stale
+        if self.running.as_ref().is_some_and(|job| job.fingerprint != self.graph.fingerprint()) {
+            self.cancel_run();
+        }
         
-        match self.executor.execute(&self.graph, &self.images) {
-            Ok(Some(output)) => {
-                let elapsed = start.elapsed();
-                let msg = format!("✓ Processed {}×{} in {:.0}ms", output.width, output.height, elapsed.as_secs_f64() * 1000.0);
-                log::info!("{}", msg);
-                
-                // Create texture for display
-                let texture = TextureHandle::from_image_data(
-                    ctx,
-                    "output",
-                    &output,
-                );
-                
-                self.output_texture = Some(texture);
-                self.output_image = Some(output);
-                self.set_status(&msg);
+        for response in self.worker.poll() {
+            match response {
+                Response::Progress { job, progress } => {
+                    if let Some(running) = self.running.as_mut().filter(|r| r.id == job) {
+                        log::debug!(
+                            "Running node {}/{}, item {}/{} ({:.0}%)",
+                            progress.node + 1, progress.nodes, progress.item + 1, progress.items,
+                            progress.fraction() * 100.0,
+                        );
+                        running.progress = progress;
+                    }
+                }
+                Response::Finished(result) => {
+                    if let Some(running) = self.running.take_if(|r| r.id == result.job) {
+                        self.finish_run(ctx, *result, running.started.elapsed());
+                    }
+                }
+                Response::Failed { job, error } => {
+                    if self.running.take_if(|r| r.id == job).is_some() {
+                        self.pending_export = None;
+                        self.set_status(&format!("✗ Error: {}", error));
+                        log::error!("Execution failed: {}", error);
+                    }
+                }
             }
-            Ok(None) => {
-                self.set_status("⚠ No output (connect nodes to Output)");
-                log::info!("Execution complete, no output (no output node connected)");
+        }
+    }
+    
+    /// Show the results of a run that finished
+    fn finish_run(&mut self, ctx: &egui::Context, result: RunResult, elapsed: std::time::Duration) {
+        let RunResult { proxy, outputs, previews, compare_metrics, stats, .. } = result;
+        if outputs.is_empty() {
+            self.pending_export = None;
+            self.set_status("⚠ No output (connect nodes to Output)");
+            log::info!("Execution complete, no output (no output node connected)");
+            return;
+        }
+        
+        let images: usize = outputs.values().map(Vec::len).sum();
+        let msg = format!(
+            "✓ Rendered {} output{}{} in {:.0}ms ({} computed, {} cached{})",
+            images, if images == 1 { "" } else { "s" },
+            if proxy { " (proxy)" } else { "" },
+            elapsed.as_secs_f64() * 1000.0,
+            stats.computed, stats.cached,
+            if stats.items > 0 { format!(", {} batch items", stats.items) } else { String::new() },
+        );
+        log::info!("{}", msg);
+        
+        self.output_images = outputs;
+        self.output_is_proxy = proxy;
+        
+        // Show Compare node metrics in the properties panel
+        let compare_nodes: Vec<uuid::Uuid> = self.graph.nodes_iter()
+            .filter(|(_, n)| n.node_type == crate::nodes::NodeType::Compare)
+            .map(|(id, _)| *id)
+            .collect();
+        for id in compare_nodes {
+            self.graph.set_compare_metrics(id, compare_metrics.get(&id).copied());
+        }
+        
+        // Keep previewing the same output if it still exists
+        if !self.preview_output.is_some_and(|id| self.output_images.contains_key(&id)) {
+            self.preview_output = self.sorted_outputs().first().copied();
+        }
+        self.refresh_output_texture(ctx);
+        self.refresh_output_previews(ctx, previews);
+        self.set_status(&msg);
+        
+        if !proxy {
+            match self.pending_export.take() {
+                Some(PendingExport::Output) => self.export_output(),
+                Some(PendingExport::AllOutputs) => self.export_all_outputs(),
+                Some(PendingExport::Zip) => self.export_zip(),
+                None => {}
             }
-            Err(e) => {
-                self.set_status(&format!("✗ Error: {}", e));
-                log::error!("Execution failed: {}", e);
+        }
+    }
+    
+    /// Live mode: re-run the graph once edits settle. While the pointer is held
+    /// (e.g. dragging a slider) only the proxies are processed; the full-resolution
+    /// run happens once the drag ends.
+    fn update_live_preview(&mut self, ctx: &egui::Context) {
+        if !self.live_preview {
+            return;
+        }
+        
+        let fingerprint = self.graph.fingerprint();
+        if fingerprint != self.live_fingerprint {
+            self.live_fingerprint = fingerprint;
+            self.live_changed_at = Some(std::time::Instant::now());
+            self.live_full_pending = true;
+        }
+        
+        let dragging = ctx.input(|i| i.pointer.any_down());
+        
+        if let Some(changed_at) = self.live_changed_at {
+            if changed_at.elapsed() < LIVE_PREVIEW_DEBOUNCE {
+                return;
+            }
+            self.live_changed_at = None;
+            if dragging {
+                self.run_graph_proxy();
+                return;
             }
         }
+        
+        if self.live_full_pending && !dragging {
+            self.live_full_pending = false;
+            self.run_graph();
+        }
     }
 }
 
+/// Trigger a browser download of in-memory bytes
+#[cfg(target_arch = "wasm32")]
+fn download_bytes(file_name: &str, mime: &str, bytes: &[u8]) {
+    let encoded = base64::Engine::encode(&base64::engine::general_purpose::STANDARD, bytes);
+    let js_code = format!(
+        r#"
+        const link = document.createElement('a');
+        link.href = 'data:{};base64,{}';
+        link.download = '{}';
+        link.click();
+        "#,
+        mime,
+        encoded,
+        file_name.replace('\\', "\\\\").replace('\'', "\\'")
+    );
+    let _ = js_sys::eval(&js_code);
+}
+
 impl eframe::App for FlowNodeApp {
     fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
         // Check for pending cloud operations
         self.check_cloud_load();
         self.check_cloud_save();
         
+        // Pick up results from the graph worker
+        self.poll_worker(ctx);
+        
         // Top menu bar
         egui::TopBottomPanel::top("menu_bar").show(ctx, |ui| {
             egui::menu::bar(ui, |ui| {
                 ui.menu_button("File", |ui| {
                     if ui.button("New Project").clicked() {
-                        self.graph = NodeGraph::new();
+                        self.replace_graph(GraphEditor::new());
+                        self.cancel_run();
+                        self.worker.invalidate();
                         ui.close_menu();
                     }
                     if ui.button("Open...").clicked() {
@@ -924,16 +1593,80 @@ impl eframe::App for FlowNodeApp {
                         self.export_output();
                         ui.close_menu();
                     }
+                    if ui.button("Export All Outputs...").clicked() {
+                        self.export_all_outputs();
+                        ui.close_menu();
+                    }
+                    if ui.button("Export as LUT...").clicked() {
+                        self.export_lut();
+                        ui.close_menu();
+                    }
+                });
+                
+                ui.menu_button("Edit", |ui| {
+                    let undo_text = match self.history.undo_label() {
+                        Some(label) => format!("Undo {}", label),
+                        None => "Undo".to_string(),
+                    };
+                    if ui.add_enabled(self.history.can_undo(), egui::Button::new(undo_text).shortcut_text("Ctrl+Z")).clicked() {
+                        self.undo();
+                        ui.close_menu();
+                    }
+                    let redo_text = match self.history.redo_label() {
+                        Some(label) => format!("Redo {}", label),
+                        None => "Redo".to_string(),
+                    };
+                    if ui.add_enabled(self.history.can_redo(), egui::Button::new(redo_text).shortcut_text("Ctrl+Shift+Z")).clicked() {
+                        self.redo();
+                        ui.close_menu();
+                    }
                 });
                 
                 ui.menu_button("View", |ui| {
                     ui.checkbox(&mut self.show_library, "Node Library");
                     ui.checkbox(&mut self.show_properties, "Properties");
+                    ui.checkbox(&mut self.show_history, "History");
+                    ui.checkbox(&mut self.show_wire_previews, "Wire Previews");
                     ui.separator();
                     if ui.button("Reset Zoom").clicked() {
                         self.zoom = 1.0;
                         ui.close_menu();
                     }
+                    ui.separator();
+                    ui.menu_button("Image Limits", |ui| {
+                        let limits = &mut self.limits;
+                        ui.add(egui::DragValue::new(&mut limits.max_megapixels)
+                            .range(1.0..=1000.0)
+                            .prefix("Max size: ")
+                            .suffix(" MP"));
+                        ui.add(egui::DragValue::new(&mut limits.max_dimension)
+                            .range(256..=65535)
+                            .prefix("Max side: ")
+                            .suffix(" px"));
+                        ui.add(egui::DragValue::new(&mut limits.display_max_dimension)
+                            .range(256..=8192)
+                            .prefix("Display size: ")
+                            .suffix(" px"))
+                            .on_hover_text("Applies to images loaded after the change");
+                        ui.add(egui::DragValue::new(&mut limits.tile_size)
+                            .range(256..=8192)
+                            .prefix("Tile size: ")
+                            .suffix(" px"));
+                        if ui.button("Reset").clicked() {
+                            *limits = ImageLimits::default();
+                        }
+                    });
+                    if self.limits != self.worker_limits {
+                        self.worker_limits = self.limits;
+                        self.worker.set_limits(self.limits);
+                    }
+                    ui.menu_button("Working Space", |ui| {
+                        for space in WorkingSpace::ALL {
+                            if ui.radio_value(&mut self.graph.working_space, space, space.name()).clicked() {
+                                ui.close_menu();
+                            }
+                        }
+                    });
                 });
                 
                 ui.menu_button("Help", |ui| {
@@ -946,12 +1679,22 @@ impl eframe::App for FlowNodeApp {
                 // Run button
                 ui.separator();
                 if ui.button("▶ Run").clicked() {
-                    self.run_graph(ctx);
+                    self.run_graph();
+                }
+                if ui.toggle_value(&mut self.live_preview, "⚡ Live")
+                    .on_hover_text("Re-run automatically when properties change")
+                    .changed() && self.live_preview
+                {
+                    // Force a run on the next frame
+                    self.live_fingerprint = 0;
                 }
                 
                 // Right-aligned status
                 ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
-                    if self.output_image.is_some() {
+                    if self.output_is_proxy {
+                        ui.label("⏳ Proxy preview");
+                        ui.separator();
+                    } else if !self.output_images.is_empty() {
                         ui.label("✅ Output ready");
                         ui.separator();
                     }
@@ -996,6 +1739,12 @@ impl eframe::App for FlowNodeApp {
                             if ui.button("Effects (E)").clicked() {
                                 self.graph.add_node(crate::nodes::NodeType::Effects);
                             }
+                            if ui.button("LUT").clicked() {
+                                self.graph.add_node(crate::nodes::NodeType::Lut);
+                            }
+                            if ui.button("Mask").clicked() {
+                                self.graph.add_node(crate::nodes::NodeType::Mask);
+                            }
                             if ui.button("Compare (C)").clicked() {
                                 self.graph.add_node(crate::nodes::NodeType::Compare);
                             }
@@ -1035,6 +1784,14 @@ impl eframe::App for FlowNodeApp {
                             }
                         });
                     
+                    egui::CollapsingHeader::new("📤 Output")
+                        .default_open(true)
+                        .show(ui, |ui| {
+                            if ui.button("Output (X)").clicked() {
+                                self.graph.add_node(crate::nodes::NodeType::Output);
+                            }
+                        });
+                    
                     egui::CollapsingHeader::new("🤖 AI Generation")
                         .default_open(false)
                         .show(ui, |ui| {
@@ -1064,37 +1821,162 @@ impl eframe::App for FlowNodeApp {
                     ui.heading("Properties");
                     ui.separator();
                     
+                    let selected_count = self.graph.selected_nodes().len();
+                    if selected_count > 1 {
+                        ui.label(egui::RichText::new(format!("{} nodes selected", selected_count)).weak());
+                    }
+                    
                     if let Some(node_id) = self.graph.selected_node() {
                         self.graph.show_node_properties(ui, node_id);
+                        
+                        let bakeable = self.graph.nodes.get(&node_id).is_some_and(|n| {
+                            matches!(n.node_type, crate::nodes::NodeType::Adjust | crate::nodes::NodeType::Lut)
+                        });
+                        if bakeable {
+                            ui.separator();
+                            if ui.button("🎞 Export as LUT...")
+                                .on_hover_text("Bake this node and the Adjust/LUT nodes feeding it into a 33³ .cube")
+                                .clicked()
+                            {
+                                self.export_lut();
+                            }
+                        }
                     } else {
                         ui.label("Select a node to view properties");
                     }
                     
                     // Output Preview
-                    if let Some(texture) = &self.output_texture {
+                    if !self.output_images.is_empty() {
                         ui.separator();
                         ui.heading("Output Preview");
                         
+                        // Pick which Output node to preview
+                        let outputs = self.sorted_outputs();
+                        let mut selected = self.preview_output;
+                        let selected_name = selected
+                            .map(|id| self.output_settings(id).0)
+                            .unwrap_or_default();
+                        egui::ComboBox::from_id_salt("preview_output")
+                            .selected_text(selected_name)
+                            .show_ui(ui, |ui| {
+                                for id in &outputs {
+                                    ui.selectable_value(&mut selected, Some(*id), self.output_settings(*id).0);
+                                }
+                            });
+                        if selected != self.preview_output {
+                            self.preview_output = selected;
+                            self.preview_item = 0;
+                            self.refresh_output_texture(ctx);
+                        }
+                        
+                        // Step through the items of a batch
+                        let items = selected
+                            .and_then(|id| self.output_images.get(&id))
+                            .map_or(0, Vec::len);
+                        if items > 1 {
+                            let mut item = self.preview_item.min(items - 1);
+                            ui.horizontal(|ui| {
+                                if ui.small_button("◀").clicked() {
+                                    item = item.saturating_sub(1);
+                                }
+                                ui.add(egui::DragValue::new(&mut item)
+                                    .range(0..=items - 1)
+                                    .custom_formatter(|n, _| format!("{}", n as usize + 1))
+                                    .custom_parser(|s| s.parse::<f64>().ok().map(|n| n - 1.0)));
+                                ui.label(format!("of {}", items));
+                                if ui.small_button("▶").clicked() {
+                                    item = (item + 1).min(items - 1);
+                                }
+                            });
+                            if item != self.preview_item {
+                                self.preview_item = item;
+                                self.refresh_output_texture(ctx);
+                            }
+                        }
+                    }
+                    
+                    if let Some((texture_id, size)) = self.output_texture.as_ref().map(|t| (t.handle.id(), t.size)) {
+                        
                         // Calculate scaled size to fit panel
                         let max_size = 250.0;
-                        let aspect = texture.size[0] as f32 / texture.size[1] as f32;
+                        let aspect = size[0] as f32 / size[1] as f32;
                         let (w, h) = if aspect > 1.0 {
                             (max_size, max_size / aspect)
                         } else {
                             (max_size * aspect, max_size)
                         };
                         
-                        ui.image(egui::ImageSource::Texture(egui::load::SizedTexture {
-                            id: texture.handle.id(),
+                        let compare = self.preview_compare_split();
+                        let image = egui::Image::new(egui::load::SizedTexture {
+                            id: texture_id,
                             size: egui::vec2(w, h),
-                        }));
+                        });
+                        let sense = if compare.is_some() { egui::Sense::drag() } else { egui::Sense::hover() };
+                        let response = ui.add(image.sense(sense));
                         
-                        ui.label(format!("{}×{}", texture.size[0], texture.size[1]));
+                        // Drag a Compare node's divider on the preview
+                        if let Some((compare_id, mode, split)) = compare {
+                            let rect = response.rect;
+                            let vertical = mode == CompareMode::SplitVertical;
+                            let mut new_split = split;
+                            if let Some(pos) = response.interact_pointer_pos() {
+                                new_split = if vertical {
+                                    (pos.x - rect.left()) / rect.width()
+                                } else {
+                                    (pos.y - rect.top()) / rect.height()
+                                }.clamp(0.0, 1.0);
+                            }
+                            
+                            let stroke = egui::Stroke::new(2.0, egui::Color32::WHITE);
+                            if vertical {
+                                let x = rect.left() + rect.width() * new_split;
+                                ui.painter().vline(x, rect.y_range(), stroke);
+                            } else {
+                                let y = rect.top() + rect.height() * new_split;
+                                ui.painter().hline(rect.x_range(), y, stroke);
+                            }
+                            if response.hovered() || response.dragged() {
+                                ui.ctx().set_cursor_icon(if vertical {
+                                    egui::CursorIcon::ResizeHorizontal
+                                } else {
+                                    egui::CursorIcon::ResizeVertical
+                                });
+                            }
+                            
+                            if new_split != split {
+                                if let Some(NodeProperties::Compare { split, .. }) = self.graph.nodes.get_mut(&compare_id).map(|n| &mut n.properties) {
+                                    *split = new_split;
+                                }
+                                // Proxy runs keep up with the drag; a full run follows on release
+                                if !self.live_preview {
+                                    self.run_graph_proxy();
+                                }
+                            }
+                            if response.drag_stopped() && !self.live_preview {
+                                self.run_graph();
+                            }
+                        }
                         
-                        // Export button
-                        if ui.button("💾 Export PNG").clicked() {
-                            self.export_output();
+                        if self.output_is_proxy {
+                            ui.label(format!("{}×{} (proxy)", size[0], size[1]));
+                        } else {
+                            ui.label(format!("{}×{}", size[0], size[1]));
                         }
+                        
+                        // Export buttons
+                        ui.horizontal(|ui| {
+                            if ui.button("💾 Export").clicked() {
+                                self.export_output();
+                            }
+                            if self.output_count() > 1 {
+                                if ui.button("💾 Export All").clicked() {
+                                    self.export_all_outputs();
+                                }
+                                if ui.button("📦 Zip").on_hover_text("Export every output in one .zip").clicked() {
+                                    self.export_zip();
+                                }
+                            }
+                        });
                     }
                 });
         }
@@ -1102,21 +1984,58 @@ impl eframe::App for FlowNodeApp {
         // Central panel: The node graph canvas
         egui::CentralPanel::default().show(ctx, |ui| {
             // This is where the magic happens - the entire graph is drawn here
+            self.graph.set_show_wire_previews(self.show_wire_previews);
             self.graph.show(ui);
         });
         
         // Handle dropped files
-        ctx.input(|i| {
-            for file in &i.raw.dropped_files {
-                if let Some(bytes) = &file.bytes {
-                    self.load_image_bytes(ctx, bytes);
-                }
+        let dropped = ctx.input(|i| i.raw.dropped_files.clone());
+        for file in dropped {
+            // The web gives us bytes, native gives us a path
+            let bytes = match (&file.bytes, &file.path) {
+                (Some(bytes), _) => bytes.to_vec(),
+                #[cfg(not(target_arch = "wasm32"))]
+                (None, Some(path)) => match std::fs::read(path) {
+                    Ok(bytes) => bytes,
+                    Err(e) => {
+                        log::error!("Failed to read {}: {}", path.display(), e);
+                        continue;
+                    }
+                },
+                _ => continue,
+            };
+            
+            let name = match &file.path {
+                Some(path) => path.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default(),
+                None => file.name.clone(),
+            };
+            if name.to_lowercase().ends_with(".cube") {
+                self.load_lut_text(&name, &String::from_utf8_lossy(&bytes));
+            } else {
+                self.load_image_bytes(ctx, &bytes);
             }
-        });
+        }
         
         // Keyboard shortcuts - matching React app exactly
         self.handle_keyboard_shortcuts(ctx);
         
+        // Undo history panel
+        let mut show_history = self.show_history;
+        egui::Window::new("History")
+            .open(&mut show_history)
+            .default_width(200.0)
+            .show(ctx, |ui| {
+                self.history.show(ui, &mut self.graph);
+            });
+        self.show_history = show_history;
+        
+        // Record this frame's edits once drags have finished
+        let (pointer_down, now) = ctx.input(|i| (i.pointer.any_down(), i.time));
+        self.history.record(&self.graph, pointer_down, now);
+        
+        // Live preview re-runs after property edits
+        self.update_live_preview(ctx);
+        
         // Bottom status bar
         egui::TopBottomPanel::bottom("status_bar").show(ctx, |ui| {
             ui.horizontal(|ui| {
@@ -1127,8 +2046,23 @@ impl eframe::App for FlowNodeApp {
                     }
                 }
                 
+                // Run progress, with a way out
+                if let Some(running) = &self.running {
+                    let p = running.progress;
+                    let label = if p.items > 1 {
+                        format!("Running node {}/{}, item {}/{}", (p.node + 1).min(p.nodes), p.nodes, p.item + 1, p.items)
+                    } else {
+                        format!("Running node {}/{}", (p.node + 1).min(p.nodes), p.nodes)
+                    };
+                    ui.add(egui::ProgressBar::new(p.fraction()).desired_width(160.0).text(label));
+                    if ui.small_button("✕").on_hover_text("Cancel the run").clicked() {
+                        self.cancel_run();
+                        self.set_status("Run cancelled");
+                    }
+                }
+                
                 ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
-                    ui.label("A=Adjust E=Effects | Ctrl+C/V=Copy/Paste | Ctrl+G=Run");
+                    ui.label("A=Adjust E=Effects | Ctrl+C/V=Copy/Paste | Ctrl+Z=Undo | Ctrl+G=Run");
                 });
             });
         });
@@ -0,0 +1,18 @@
+//! Web Worker that runs node graphs for the canvas
+//!
+//! Trunk builds this alongside the app as `flownode-worker.js`; see `worker.rs`.
+
+#![warn(clippy::all)]
+
+#[cfg(target_arch = "wasm32")]
+fn main() {
+    console_error_panic_hook::set_once();
+    console_log::init_with_level(log::Level::Info).expect("Failed to init logger");
+    flownode_canvas::worker::serve();
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn main() {
+    eprintln!("flownode-worker only runs in the browser; native builds use a thread");
+    std::process::exit(1);
+}
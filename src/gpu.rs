@@ -1,26 +1,25 @@
-//! GPU acceleration scaffolding (disabled for WASM compatibility)
+//! Picks where the editor runs node operations
 //!
-//! WebGPU support will be added later when threading issues are resolved.
-//! For now, all image processing is done on CPU.
+//! Built with the `gpu` feature, graphs run as wgpu compute shaders when a GPU
+//! can be opened; otherwise, and always in the browser, they run on the CPU.
 
-use crate::image_data::ImageData;
+use std::sync::Arc;
 
-/// Placeholder for GPU context - not used yet
-pub struct GpuContext;
+use crate::backend::ProcessingBackend;
+use crate::cpu::CpuBackend;
 
-/// CPU fallback for brightness/contrast
-pub fn brightness_contrast(input: &ImageData, brightness: f32, contrast: f32) -> ImageData {
-    let mut pixels = (*input.pixels).clone();
-    let factor = (259.0 * (contrast + 255.0)) / (255.0 * (259.0 - contrast));
-    
-    for chunk in pixels.chunks_mut(4) {
-        for i in 0..3 {
-            let val = chunk[i] as f32;
-            let val = val + brightness;
-            let val = factor * (val - 128.0) + 128.0;
-            chunk[i] = val.clamp(0.0, 255.0) as u8;
+#[cfg(feature = "gpu")]
+pub use flownode_core::gpu::GpuContext;
+
+/// The GPU if the feature is on and one is available, else the CPU
+pub fn default_backend() -> Arc<dyn ProcessingBackend> {
+    #[cfg(feature = "gpu")]
+    match GpuContext::new() {
+        Ok(gpu) => {
+            log::info!("Processing on {}", gpu.name());
+            return Arc::new(gpu);
         }
+        Err(e) => log::warn!("{}; processing on the CPU", e),
     }
-    
-    ImageData::new(pixels, input.width, input.height)
+    Arc::new(CpuBackend)
 }
@@ -1,4 +1,4 @@
-//! Node graph state and rendering
+//! Node graph editor: view state and rendering around the engine's graph
 
 use eframe::egui::{self, Pos2, Rect, Vec2};
 
@@ -19,42 +19,106 @@ fn hsv_to_rgb(h: f32, s: f32, v: f32) -> (f32, f32, f32) {
     
     (r + m, g + m, b + m)
 }
-use serde::{Deserialize, Serialize};
+
+/// The first `max_lines` lines of a text value, each cut to `max_chars`,
+/// with an ellipsis wherever something was left out
+fn text_snippet(text: &str, max_lines: usize, max_chars: usize) -> String {
+    let mut lines: Vec<String> = text.lines()
+        .take(max_lines)
+        .map(|line| if line.chars().count() > max_chars {
+            format!("{}…", line.chars().take(max_chars).collect::<String>())
+        } else {
+            line.to_string()
+        })
+        .collect();
+    if text.lines().count() > max_lines {
+        if let Some(last) = lines.last_mut() {
+            if !last.ends_with('…') {
+                last.push('…');
+            }
+        }
+    }
+    lines.join("\n")
+}
+
+/// Point at `t` (0-1) along the bezier curve drawn for a wire
+fn bezier_point(from: Pos2, to: Pos2, t: f32) -> Pos2 {
+    let control_distance = ((to.x - from.x).abs() * 0.5).max(50.0);
+    let control1 = Pos2::new(from.x + control_distance, from.y);
+    let control2 = Pos2::new(to.x - control_distance, to.y);
+    
+    let t2 = t * t;
+    let t3 = t2 * t;
+    let mt = 1.0 - t;
+    let mt2 = mt * mt;
+    let mt3 = mt2 * mt;
+    
+    Pos2::new(
+        mt3 * from.x + 3.0 * mt2 * t * control1.x + 3.0 * mt * t2 * control2.x + t3 * to.x,
+        mt3 * from.y + 3.0 * mt2 * t * control1.y + 3.0 * mt * t2 * control2.y + t3 * to.y,
+    )
+}
 use uuid::Uuid;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
+use std::ops::{Deref, DerefMut};
 
-use crate::nodes::{Node, NodeType, NodeProperties, SlotType, BlurDirection, BlendMode};
+use crate::color::{ColorSpace, WorkingSpace};
+use crate::lut::{Lut, LutKind};
+use crate::nodes::{Node, NodeType, NodeProperties, SlotType, BlurDirection, BlendMode, CompareMode, Curve, EdgeMode, ExportDepth, ExportFormat, LutInterpolation, MaskSource};
+use crate::texture::TextureHandle;
 use crate::ui_components::{style, colors};
 
-/// A connection between two nodes
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct Connection {
-    pub from_node: Uuid,
-    pub from_slot: usize,
-    pub to_node: Uuid,
-    pub to_slot: usize,
+pub use flownode_core::graph::{Connection, NodeGraph, Subgraph};
+
+/// How long a refused connection's reason stays on screen
+const REJECTION_SECONDS: f64 = 2.0;
+
+/// A node's value from the last run, shown inside the node and on its wires
+pub enum OutputPreview {
+    Image(TextureHandle),
+    Text(String),
 }
 
-/// The entire node graph
-#[derive(Debug, Serialize, Deserialize)]
-pub struct NodeGraph {
-    pub nodes: HashMap<Uuid, Node>,
-    pub connections: Vec<Connection>,
+impl std::fmt::Debug for OutputPreview {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Image(texture) => write!(f, "Image({}x{})", texture.size[0], texture.size[1]),
+            Self::Text(text) => write!(f, "Text({:?})", text),
+        }
+    }
+}
+
+/// The graph being edited, with its selection, view and interaction state.
+/// Derefs to the engine's [`NodeGraph`] for everything saved or executed.
+#[derive(Debug)]
+pub struct GraphEditor {
+    graph: NodeGraph,
     
-    #[serde(skip)]
-    selected_node: Option<Uuid>,
+    /// Selected nodes; the last one is shown in the properties panel
+    selection: Vec<Uuid>,
     
-    #[serde(skip)]
     dragging_node: Option<Uuid>,
     
-    #[serde(skip)]
+    /// Screen position where a box selection started
+    box_select: Option<Pos2>,
+    
     pan_offset: Vec2,
     
-    #[serde(skip)]
     zoom: f32,
     
-    #[serde(skip)]
     pending_connection: Option<PendingConnection>,
+    
+    /// Why the last connection drop was refused, where, and when
+    rejected_drop: Option<(String, Pos2, f64)>,
+    
+    /// Values from the last run, keyed by the node that produced them
+    output_previews: HashMap<Uuid, OutputPreview>,
+    
+    /// Also show each value at the middle of its wires
+    show_wire_previews: bool,
+    
+    /// Nodes whose preview is enlarged
+    expanded_previews: HashSet<Uuid>,
 }
 
 #[derive(Debug)]
@@ -64,137 +128,146 @@ struct PendingConnection {
     is_output: bool,
 }
 
-impl Default for NodeGraph {
+impl Default for GraphEditor {
     fn default() -> Self {
         Self::new()
     }
 }
 
-impl NodeGraph {
-    pub fn new() -> Self {
+impl Deref for GraphEditor {
+    type Target = NodeGraph;
+    
+    fn deref(&self) -> &NodeGraph {
+        &self.graph
+    }
+}
+
+impl DerefMut for GraphEditor {
+    fn deref_mut(&mut self) -> &mut NodeGraph {
+        &mut self.graph
+    }
+}
+
+impl From<NodeGraph> for GraphEditor {
+    /// Edit a loaded graph, with nothing selected and the default view
+    fn from(graph: NodeGraph) -> Self {
         Self {
-            nodes: HashMap::new(),
-            connections: Vec::new(),
-            selected_node: None,
+            graph,
+            selection: Vec::new(),
             dragging_node: None,
+            box_select: None,
             pan_offset: Vec2::ZERO,
             zoom: 1.0,
             pending_connection: None,
+            rejected_drop: None,
+            output_previews: HashMap::new(),
+            show_wire_previews: false,
+            expanded_previews: HashSet::new(),
         }
     }
-    
-    pub fn node_count(&self) -> usize {
-        self.nodes.len()
+}
+
+impl GraphEditor {
+    pub fn new() -> Self {
+        Self::from(NodeGraph::new())
     }
     
+    /// The node shown in the properties panel (the last one selected)
     pub fn selected_node(&self) -> Option<Uuid> {
-        self.selected_node
+        self.selection.last().copied()
+    }
+    
+    /// All selected nodes
+    pub fn selected_nodes(&self) -> &[Uuid] {
+        &self.selection
+    }
+    
+    pub fn is_selected(&self, node_id: Uuid) -> bool {
+        self.selection.contains(&node_id)
+    }
+    
+    /// Select only this node
+    pub fn select(&mut self, node_id: Uuid) {
+        self.selection = vec![node_id];
+    }
+    
+    /// Add a node to the selection, or remove it if already selected
+    pub fn toggle_selection(&mut self, node_id: Uuid) {
+        if let Some(index) = self.selection.iter().position(|id| *id == node_id) {
+            self.selection.remove(index);
+        } else {
+            self.selection.push(node_id);
+        }
     }
     
     /// Delete a node and all its connections
     pub fn delete_node(&mut self, node_id: Uuid) {
-        // Remove all connections involving this node
-        self.connections.retain(|c| c.from_node != node_id && c.to_node != node_id);
-        
-        // Remove the node
-        self.nodes.remove(&node_id);
-        
-        // Clear selection if this was the selected node
-        if self.selected_node == Some(node_id) {
-            self.selected_node = None;
-        }
-        
-        log::info!("Deleted node {:?}", node_id);
+        self.graph.delete_node(node_id);
+        self.selection.retain(|id| *id != node_id);
     }
     
     /// Deselect all nodes
     pub fn deselect_all(&mut self) {
-        self.selected_node = None;
+        self.selection.clear();
         self.pending_connection = None;
     }
     
-    /// Delete the currently selected node
+    /// Delete all selected nodes
     pub fn delete_selected(&mut self) {
-        if let Some(node_id) = self.selected_node {
+        for node_id in std::mem::take(&mut self.selection) {
             self.delete_node(node_id);
         }
     }
     
-    /// Get a clone of the selected node (for copy)
-    pub fn get_selected_node_clone(&self) -> Option<Node> {
-        self.selected_node.and_then(|id| self.nodes.get(&id).cloned())
-    }
-    
-    /// Paste a node (create a copy with new ID and offset position)
-    pub fn paste_node(&mut self, node: &Node) -> Uuid {
-        let mut new_node = node.clone();
-        new_node.id = Uuid::new_v4();
-        new_node.position.x += 30.0;
-        new_node.position.y += 30.0;
+    /// Copy the selected nodes and the connections between them
+    pub fn copy_selection(&self) -> Option<Subgraph> {
+        if self.selection.is_empty() {
+            return None;
+        }
         
-        let node_id = new_node.id;
-        self.nodes.insert(node_id, new_node);
-        self.selected_node = Some(node_id);
+        let nodes: Vec<Node> = self.selection.iter()
+            .filter_map(|id| self.nodes.get(id).cloned())
+            .collect();
+        let connections = self.connections.iter()
+            .filter(|c| self.is_selected(c.from_node) && self.is_selected(c.to_node))
+            .cloned()
+            .collect();
+        let luts = self.luts.subset(nodes.iter().filter_map(|n| match &n.properties {
+            NodeProperties::Lut { lut, .. } => lut.as_ref(),
+            _ => None,
+        }));
         
-        log::info!("Pasted node {:?}", node_id);
-        node_id
+        Some(Subgraph { nodes, connections, luts })
     }
     
-    /// Insert a node directly (for cloud loading)
-    pub fn insert_node(&mut self, node: Node) {
-        let node_id = node.id;
-        self.nodes.insert(node_id, node);
-    }
-    
-    /// Add a connection between nodes
-    pub fn add_connection(&mut self, from_node: Uuid, from_slot: usize, to_node: Uuid, to_slot: usize) {
-        // Check if connection already exists
-        let exists = self.connections.iter().any(|c| 
-            c.from_node == from_node && c.from_slot == from_slot &&
-            c.to_node == to_node && c.to_slot == to_slot
-        );
+    /// Paste a copied subgraph with new IDs, offset from the originals, and
+    /// select it. Returns the new node IDs.
+    pub fn paste_subgraph(&mut self, subgraph: &Subgraph) -> Vec<Uuid> {
+        let mut id_map = HashMap::new();
+        self.selection.clear();
+        self.luts.extend(&subgraph.luts);
         
-        if !exists {
-            self.connections.push(Connection {
-                from_node,
-                from_slot,
-                to_node,
-                to_slot,
-            });
+        for node in &subgraph.nodes {
+            let mut new_node = node.clone();
+            new_node.id = Uuid::new_v4();
+            new_node.position.x += 30.0;
+            new_node.position.y += 30.0;
+            
+            id_map.insert(node.id, new_node.id);
+            self.selection.push(new_node.id);
+            self.nodes.insert(new_node.id, new_node);
         }
-    }
-    
-    /// Delete a specific connection
-    pub fn delete_connection(&mut self, from_node: Uuid, from_slot: usize, to_node: Uuid, to_slot: usize) {
-        self.connections.retain(|c| {
-            !(c.from_node == from_node && c.from_slot == from_slot && 
-              c.to_node == to_node && c.to_slot == to_slot)
-        });
-    }
-    
-    /// Serialize the graph to JSON
-    pub fn to_json(&self) -> Result<String, serde_json::Error> {
-        serde_json::to_string_pretty(self)
-    }
-    
-    /// Deserialize the graph from JSON
-    pub fn from_json(json: &str) -> Result<Self, serde_json::Error> {
-        serde_json::from_str(json)
-    }
-    
-    /// Check if graph has unsaved changes (always true for now, could track dirty state)
-    pub fn is_dirty(&self) -> bool {
-        !self.nodes.is_empty()
-    }
-    
-    /// Get iterator over nodes (for compatibility layer)
-    pub fn nodes_iter(&self) -> impl Iterator<Item = (&Uuid, &Node)> {
-        self.nodes.iter()
-    }
-    
-    /// Get iterator over connections (for compatibility layer)
-    pub fn connections_iter(&self) -> impl Iterator<Item = &Connection> {
-        self.connections.iter()
+        
+        for conn in &subgraph.connections {
+            if let (Some(from_node), Some(to_node)) = (id_map.get(&conn.from_node), id_map.get(&conn.to_node)) {
+                if let Err(e) = self.add_connection(*from_node, conn.from_slot, *to_node, conn.to_slot) {
+                    log::warn!("Skipped pasted connection: {}", e);
+                }
+            }
+        }
+        
+        log::info!("Pasted {} nodes", self.selection.len());
+        self.selection.clone()
     }
     
     /// Get current pan offset
@@ -207,17 +280,14 @@ impl NodeGraph {
         self.zoom
     }
     
-    /// Create graph from parts (for loading)
-    pub fn from_parts(nodes: HashMap<Uuid, Node>, connections: Vec<Connection>) -> Self {
-        Self {
-            nodes,
-            connections,
-            selected_node: None,
-            dragging_node: None,
-            pan_offset: Vec2::ZERO,
-            zoom: 1.0,
-            pending_connection: None,
-        }
+    /// Replace the nodes and connections (for undo/redo), keeping the view
+    pub fn restore(&mut self, nodes: HashMap<Uuid, Node>, connections: Vec<Connection>, working_space: WorkingSpace) {
+        self.nodes = nodes;
+        self.connections = connections;
+        self.working_space = working_space;
+        self.pending_connection = None;
+        self.dragging_node = None;
+        self.selection.retain(|id| self.graph.nodes.contains_key(id));
     }
     
     /// Set pan and zoom (for loading viewport)
@@ -226,27 +296,13 @@ impl NodeGraph {
         self.zoom = zoom;
     }
     
-    /// Set the image ID for an Image node
-    /// Returns true if the node was an Image node and was updated
-    pub fn set_node_image(&mut self, node_id: Uuid, image_id: u64) -> bool {
-        if let Some(node) = self.nodes.get_mut(&node_id) {
-            if let NodeProperties::Image { texture_id, .. } = &mut node.properties {
-                *texture_id = Some(image_id);
-                return true;
-            }
-        }
-        false
+    /// Replace the values from the last run shown in nodes and on wires
+    pub fn set_output_previews(&mut self, previews: HashMap<Uuid, OutputPreview>) {
+        self.output_previews = previews;
     }
     
-    /// Get the image ID for a node (if it has one)
-    pub fn get_node_image(&self, node_id: Uuid) -> Option<u64> {
-        self.nodes.get(&node_id).and_then(|node| {
-            if let NodeProperties::Image { texture_id, .. } = &node.properties {
-                *texture_id
-            } else {
-                None
-            }
-        })
+    pub fn set_show_wire_previews(&mut self, show: bool) {
+        self.show_wire_previews = show;
     }
     
     pub fn add_node(&mut self, node_type: NodeType) -> Uuid {
@@ -254,14 +310,14 @@ impl NodeGraph {
         let node_count = self.nodes.len() as f32;
         let offset_x = (node_count * 30.0) % 200.0;
         let offset_y = (node_count * 20.0) % 150.0;
-        let position = Vec2::new(
+        let position = crate::nodes::Vec2::new(
             100.0 + offset_x,
             100.0 + offset_y,
         );
         log::info!("Creating node {:?} at {:?}", node_type, position);
         let node = Node::new(node_type, position);
         let node_id = node.id;
-        self.selected_node = Some(node_id);
+        self.select(node_id);
         self.nodes.insert(node_id, node);
         log::info!("Total nodes: {}", self.nodes.len());
         node_id
@@ -275,9 +331,15 @@ impl NodeGraph {
         );
         
         let canvas_rect = response.rect;
+        let shift = ui.input(|i| i.modifiers.shift);
+        
+        // Shift+drag on empty space starts a box selection
+        if response.drag_started() && shift {
+            self.box_select = response.interact_pointer_pos();
+        }
         
         // Handle panning (drag on empty space)
-        if response.dragged() && self.dragging_node.is_none() && self.pending_connection.is_none() {
+        if response.dragged() && self.box_select.is_none() && self.dragging_node.is_none() && self.pending_connection.is_none() {
             self.pan_offset += response.drag_delta();
         }
         
@@ -294,15 +356,38 @@ impl NodeGraph {
         // Draw grid background
         self.draw_grid(&painter, canvas_rect);
         
-        // Draw connections
-        for conn in &self.connections {
-            if let (Some(from_node), Some(to_node)) = (
-                self.nodes.get(&conn.from_node),
-                self.nodes.get(&conn.to_node),
-            ) {
-                let from_pos = self.get_slot_position(from_node, conn.from_slot, false, canvas_rect);
-                let to_pos = self.get_slot_position(to_node, conn.to_slot, true, canvas_rect);
-                self.draw_connection(&painter, from_pos, to_pos, SlotType::Content);
+        // Draw connections, coloured by their source slot. Wires touching the
+        // selection go last so they stay on top.
+        let mut wires: Vec<(Pos2, Pos2, SlotType, bool, Uuid)> = self.connections.iter()
+            .filter_map(|conn| {
+                let from_node = self.nodes.get(&conn.from_node)?;
+                let to_node = self.nodes.get(&conn.to_node)?;
+                let slot_type = from_node.node_type.outputs().get(conn.from_slot)
+                    .map(|s| s.slot_type)
+                    .unwrap_or(SlotType::Content);
+                let highlighted = self.is_selected(conn.from_node) || self.is_selected(conn.to_node);
+                Some((
+                    self.get_slot_position(from_node, conn.from_slot, false, canvas_rect),
+                    self.get_slot_position(to_node, conn.to_slot, true, canvas_rect),
+                    slot_type,
+                    highlighted,
+                    conn.from_node,
+                ))
+            })
+            .collect();
+        wires.sort_by_key(|wire| wire.3);
+        for (from_pos, to_pos, slot_type, highlighted, _) in &wires {
+            if *highlighted {
+                self.draw_highlighted_connection(&painter, *from_pos, *to_pos, *slot_type);
+            } else {
+                self.draw_connection(&painter, *from_pos, *to_pos, *slot_type);
+            }
+        }
+        if self.show_wire_previews {
+            for (from_pos, to_pos, _, _, source) in &wires {
+                if let Some(preview) = self.output_previews.get(source) {
+                    self.draw_wire_preview(&painter, bezier_point(*from_pos, *to_pos, 0.5), preview);
+                }
             }
         }
         
@@ -332,22 +417,59 @@ impl NodeGraph {
             self.draw_node(ui, &painter, canvas_rect, node_id);
         }
         
-        // Handle click to deselect
-        if response.clicked() && self.selected_node.is_some() {
-            self.selected_node = None;
+        // Briefly show why a dropped connection was refused
+        if let Some((message, pos, at)) = &self.rejected_drop {
+            let age = ui.input(|i| i.time) - at;
+            if age < REJECTION_SECONDS {
+                let alpha = (1.0 - age / REJECTION_SECONDS) as f32;
+                painter.text(
+                    *pos + Vec2::new(0.0, -14.0),
+                    egui::Align2::CENTER_BOTTOM,
+                    message,
+                    egui::FontId::proportional(12.0),
+                    colors::CONNECTION_REJECTED.gamma_multiply(alpha),
+                );
+                ui.ctx().request_repaint();
+            } else {
+                self.rejected_drop = None;
+            }
         }
         
-        // Keyboard shortcuts
-        if ui.input(|i| i.key_pressed(egui::Key::Delete) || i.key_pressed(egui::Key::Backspace)) {
-            if let Some(node_id) = self.selected_node {
-                self.delete_node(node_id);
+        // Box selection: add every node touching the box
+        if let Some(start) = self.box_select {
+            let end = ui.input(|i| i.pointer.hover_pos()).unwrap_or(start);
+            let box_rect = Rect::from_two_pos(start, end);
+            painter.rect(box_rect, 0.0, colors::SELECTION_BOX, egui::Stroke::new(1.0, colors::NODE_SELECTED_OUTLINE));
+            
+            if !ui.input(|i| i.pointer.any_down()) {
+                let inside: Vec<Uuid> = self.nodes.values()
+                    .filter(|node| box_rect.intersects(self.node_rect(node, canvas_rect)))
+                    .map(|node| node.id)
+                    .collect();
+                for node_id in inside {
+                    if !self.is_selected(node_id) {
+                        self.selection.push(node_id);
+                    }
+                }
+                self.box_select = None;
             }
         }
         
+        // Handle click to deselect
+        if response.clicked() && !shift {
+            self.selection.clear();
+        }
+        
+        // Keyboard shortcuts (not while typing in a text field)
+        let typing = ui.ctx().wants_keyboard_input();
+        if !typing && ui.input(|i| i.key_pressed(egui::Key::Delete) || i.key_pressed(egui::Key::Backspace)) {
+            self.delete_selected();
+        }
+        
         // Escape to deselect
         if ui.input(|i| i.key_pressed(egui::Key::Escape)) {
-            self.selected_node = None;
-            self.pending_connection = None;
+            self.deselect_all();
+            self.box_select = None;
         }
         
         // Zoom with scroll wheel
@@ -404,30 +526,44 @@ impl NodeGraph {
     }
     
     fn draw_connection(&self, painter: &egui::Painter, from: Pos2, to: Pos2, slot_type: SlotType) {
-        let color = slot_type.color();
-        
-        // Draw a bezier curve
-        let control_distance = ((to.x - from.x).abs() * 0.5).max(50.0);
-        let control1 = Pos2::new(from.x + control_distance, from.y);
-        let control2 = Pos2::new(to.x - control_distance, to.y);
-        
-        let points: Vec<Pos2> = (0..=32)
-            .map(|i| {
-                let t = i as f32 / 32.0;
-                let t2 = t * t;
-                let t3 = t2 * t;
-                let mt = 1.0 - t;
-                let mt2 = mt * mt;
-                let mt3 = mt2 * mt;
-                
-                Pos2::new(
-                    mt3 * from.x + 3.0 * mt2 * t * control1.x + 3.0 * mt * t2 * control2.x + t3 * to.x,
-                    mt3 * from.y + 3.0 * mt2 * t * control1.y + 3.0 * mt * t2 * control2.y + t3 * to.y,
-                )
-            })
-            .collect();
-        
-        painter.add(egui::Shape::line(points, egui::Stroke::new(style::CONNECTION_WIDTH, color)));
+        let points: Vec<Pos2> = (0..=32).map(|i| bezier_point(from, to, i as f32 / 32.0)).collect();
+        painter.add(egui::Shape::line(points, egui::Stroke::new(style::CONNECTION_WIDTH, colors::rgb(slot_type.color()))));
+    }
+    
+    /// Draw a wire of a selected node: wider, with a light outline
+    fn draw_highlighted_connection(&self, painter: &egui::Painter, from: Pos2, to: Pos2, slot_type: SlotType) {
+        let points: Vec<Pos2> = (0..=32).map(|i| bezier_point(from, to, i as f32 / 32.0)).collect();
+        let glow = egui::Stroke::new(style::CONNECTION_HIGHLIGHT_WIDTH + 2.0, colors::NODE_SELECTED_OUTLINE.gamma_multiply(0.6));
+        painter.add(egui::Shape::line(points.clone(), glow));
+        painter.add(egui::Shape::line(points, egui::Stroke::new(style::CONNECTION_HIGHLIGHT_WIDTH, colors::rgb(slot_type.color()))));
+    }
+    
+    /// Draw a thumbnail or text snippet of a wire's value centred on `pos`
+    fn draw_wire_preview(&self, painter: &egui::Painter, pos: Pos2, preview: &OutputPreview) {
+        match preview {
+            OutputPreview::Image(texture) => {
+                let [width, height] = texture.handle.size();
+                let scale = style::WIRE_PREVIEW_SIZE * self.zoom / width.max(height).max(1) as f32;
+                let rect = Rect::from_center_size(pos, Vec2::new(width as f32, height as f32) * scale);
+                painter.rect_filled(rect.expand(2.0), 3.0, colors::NODE_BG);
+                painter.image(
+                    texture.id(),
+                    rect,
+                    Rect::from_min_max(Pos2::ZERO, Pos2::new(1.0, 1.0)),
+                    egui::Color32::WHITE,
+                );
+            }
+            OutputPreview::Text(text) => {
+                let galley = painter.layout_no_wrap(
+                    text_snippet(text, 1, 24),
+                    egui::FontId::proportional(11.0 * self.zoom),
+                    egui::Color32::LIGHT_GRAY,
+                );
+                let rect = Rect::from_center_size(pos, galley.size()).expand(4.0);
+                painter.rect_filled(rect, 3.0, colors::NODE_BG);
+                painter.galley(rect.min + Vec2::splat(4.0), galley, egui::Color32::LIGHT_GRAY);
+            }
+        }
     }
     
     fn draw_node(&mut self, ui: &mut egui::Ui, painter: &egui::Painter, canvas_rect: Rect, node_id: Uuid) {
@@ -436,29 +572,23 @@ impl NodeGraph {
             None => return,
         };
         
-        let node_width = style::NODE_WIDTH * self.zoom;
+        let node_width = self.node_width(&node) * self.zoom;
         let header_height = style::NODE_HEADER_HEIGHT * self.zoom;
         let slot_height = style::NODE_SLOT_HEIGHT * self.zoom;
         let padding = style::NODE_PADDING * self.zoom;
         
         let inputs = node.node_type.inputs();
         let outputs = node.node_type.outputs();
-        let content_height = (inputs.len().max(outputs.len()) as f32) * slot_height + padding * 2.0;
-        let node_height = header_height + content_height;
         
-        let node_pos = Pos2::new(
-            canvas_rect.left() + node.position.x * self.zoom + self.pan_offset.x,
-            canvas_rect.top() + node.position.y * self.zoom + self.pan_offset.y,
-        );
-        
-        let node_rect = Rect::from_min_size(node_pos, Vec2::new(node_width, node_height));
+        let node_rect = self.node_rect(&node, canvas_rect);
+        let node_pos = node_rect.min;
         
         // Skip if outside visible area
         if !canvas_rect.intersects(node_rect) {
             return;
         }
         
-        let is_selected = self.selected_node == Some(node_id);
+        let is_selected = self.is_selected(node_id);
         
         // Node background
         let bg_color = if is_selected {
@@ -489,7 +619,7 @@ impl NodeGraph {
                 sw: 0.0,
                 se: 0.0,
             },
-            node.node_type.color(),
+            colors::rgb(node.node_type.color()),
         );
         
         // Header text
@@ -516,19 +646,35 @@ impl NodeGraph {
             // Check if this input has a connection
             let has_connection = self.connections.iter().any(|c| c.to_node == node_id && c.to_slot == i);
             
+            // While a wire is dragged over this input, check whether it may connect.
+            // The dragged output slot owns the pointer, so test the rect directly.
+            let drop_check = self.pending_connection.as_ref()
+                .filter(|pending| pending.is_output && ui.rect_contains_pointer(slot_rect))
+                .map(|pending| (pending.from_node, pending.from_slot))
+                .map(|(from_node, from_slot)| (from_node, from_slot, self.can_connect(from_node, from_slot, node_id, i)));
+            
             // Highlight on hover
-            let is_hovered = slot_response.hovered();
+            let is_hovered = slot_response.hovered() || drop_check.is_some();
             let radius = if is_hovered { slot_radius * 1.3 } else { slot_radius };
             
             // Slot circle - filled if connected, hollow if not
             if has_connection {
-                painter.circle_filled(slot_pos, radius, input.slot_type.color());
+                painter.circle_filled(slot_pos, radius, colors::rgb(input.slot_type.color()));
             } else {
-                painter.circle_stroke(slot_pos, radius, egui::Stroke::new(2.0, input.slot_type.color()));
+                painter.circle_stroke(slot_pos, radius, egui::Stroke::new(2.0, colors::rgb(input.slot_type.color())));
                 // Small dot in center
-                painter.circle_filled(slot_pos, radius * 0.3, input.slot_type.color());
+                painter.circle_filled(slot_pos, radius * 0.3, colors::rgb(input.slot_type.color()));
             }
-            if is_hovered {
+            if let Some((_, _, Err(message))) = &drop_check {
+                let stroke = egui::Stroke::new(2.0, colors::CONNECTION_REJECTED);
+                let arm = radius * 0.7;
+                painter.circle_stroke(slot_pos, radius + 2.0, stroke);
+                painter.line_segment([slot_pos - Vec2::splat(arm), slot_pos + Vec2::splat(arm)], stroke);
+                painter.line_segment([slot_pos + Vec2::new(arm, -arm), slot_pos + Vec2::new(-arm, arm)], stroke);
+                egui::show_tooltip_at_pointer(ui.ctx(), ui.layer_id(), egui::Id::new("connection_rejected"), |ui| {
+                    ui.colored_label(colors::CONNECTION_REJECTED, message);
+                });
+            } else if is_hovered {
                 painter.circle_stroke(slot_pos, radius + 2.0, egui::Stroke::new(2.0, egui::Color32::WHITE));
             }
             
@@ -541,22 +687,15 @@ impl NodeGraph {
                 egui::Color32::GRAY,
             );
             
-            // Handle connection drop on input slot
-            if slot_response.hovered() && ui.input(|i| i.pointer.any_released()) {
-                if let Some(pending) = &self.pending_connection {
-                    if pending.is_output && pending.from_node != node_id {
-                        // Remove existing connection to this input (only one connection per input)
-                        self.connections.retain(|c| !(c.to_node == node_id && c.to_slot == i));
-                        
-                        // Complete the connection
-                        let new_conn = Connection {
-                            from_node: pending.from_node,
-                            from_slot: pending.from_slot,
-                            to_node: node_id,
-                            to_slot: i,
-                        };
-                        self.connections.push(new_conn);
-                        log::info!("Connection created: {:?} -> {:?}", pending.from_node, node_id);
+            // Handle connection drop on input slot (replaces any existing wire)
+            if let Some((from_node, from_slot, check)) = drop_check {
+                if ui.input(|i| i.pointer.any_released()) {
+                    match check.and_then(|_| self.add_connection(from_node, from_slot, node_id, i)) {
+                        Ok(()) => log::info!("Connection created: {:?} -> {:?}", from_node, node_id),
+                        Err(message) => {
+                            log::info!("Connection rejected: {}", message);
+                            self.rejected_drop = Some((message, slot_pos, ui.input(|i| i.time)));
+                        }
                     }
                 }
             }
@@ -589,10 +728,10 @@ impl NodeGraph {
             
             // Slot circle - filled if connected, hollow if not
             if has_connection {
-                painter.circle_filled(slot_pos, radius, output.slot_type.color());
+                painter.circle_filled(slot_pos, radius, colors::rgb(output.slot_type.color()));
             } else {
-                painter.circle_stroke(slot_pos, radius, egui::Stroke::new(2.0, output.slot_type.color()));
-                painter.circle_filled(slot_pos, radius * 0.3, output.slot_type.color());
+                painter.circle_stroke(slot_pos, radius, egui::Stroke::new(2.0, colors::rgb(output.slot_type.color())));
+                painter.circle_filled(slot_pos, radius * 0.3, colors::rgb(output.slot_type.color()));
             }
             if is_hovered {
                 painter.circle_stroke(slot_pos, radius + 2.0, egui::Stroke::new(2.0, egui::Color32::WHITE));
@@ -618,30 +757,153 @@ impl NodeGraph {
             }
         }
         
+        // Output from the last run, below the slots
+        let preview_rect = self.preview_size(&node).map(|size| {
+            let slots = inputs.len().max(outputs.len()) as f32;
+            let top = node_pos.y + header_height + slots * slot_height + padding * 2.0;
+            Rect::from_min_size(
+                Pos2::new(node_pos.x + (node_width - size.x * self.zoom) / 2.0, top),
+                size * self.zoom,
+            )
+        });
+        if let (Some(rect), Some(preview)) = (preview_rect, self.output_previews.get(&node_id)) {
+            match preview {
+                OutputPreview::Image(texture) => {
+                    painter.image(
+                        texture.id(),
+                        rect,
+                        Rect::from_min_max(Pos2::ZERO, Pos2::new(1.0, 1.0)),
+                        egui::Color32::WHITE,
+                    );
+                }
+                OutputPreview::Text(text) => {
+                    let (lines, chars) = if self.expanded_previews.contains(&node_id) {
+                        (style::NODE_PREVIEW_LINES_EXPANDED, 56)
+                    } else {
+                        (style::NODE_PREVIEW_LINES, 24)
+                    };
+                    painter.rect_filled(rect, 3.0 * self.zoom, colors::CANVAS_BG);
+                    painter.text(
+                        rect.min + Vec2::splat(4.0 * self.zoom),
+                        egui::Align2::LEFT_TOP,
+                        text_snippet(text, lines, chars),
+                        egui::FontId::monospace(11.0 * self.zoom),
+                        egui::Color32::LIGHT_GRAY,
+                    );
+                }
+            }
+        }
+        
         // Handle node interaction
         let node_response = ui.interact(node_rect, egui::Id::new(node_id), egui::Sense::click_and_drag());
         
+        let shift = ui.input(|i| i.modifiers.shift);
+        
         if node_response.clicked() {
-            self.selected_node = Some(node_id);
+            if shift {
+                self.toggle_selection(node_id);
+            } else {
+                self.select(node_id);
+            }
         }
         
         if node_response.drag_started() {
+            // Dragging an unselected node picks it up on its own
+            if !self.is_selected(node_id) {
+                if shift {
+                    self.selection.push(node_id);
+                } else {
+                    self.select(node_id);
+                }
+            }
             self.dragging_node = Some(node_id);
         }
         
+        // Move the whole selection together
         if node_response.dragged() && self.dragging_node == Some(node_id) {
-            if let Some(n) = self.nodes.get_mut(&node_id) {
-                n.position += node_response.drag_delta() / self.zoom;
+            let delta = node_response.drag_delta() / self.zoom;
+            for id in &self.selection {
+                if let Some(n) = self.graph.nodes.get_mut(id) {
+                    n.position += crate::nodes::Vec2::new(delta.x, delta.y);
+                }
             }
         }
         
         if node_response.drag_stopped() {
             self.dragging_node = None;
         }
+        
+        // Enlarge/shrink toggle in the preview's corner (after the node so it's on top)
+        if let Some(rect) = preview_rect {
+            let expanded = self.expanded_previews.contains(&node_id);
+            let button_rect = Rect::from_min_size(
+                Pos2::new(node_rect.right() - padding - 16.0 * self.zoom, rect.top()),
+                Vec2::splat(16.0 * self.zoom),
+            );
+            let button = ui.interact(button_rect, egui::Id::new((node_id, "preview_toggle")), egui::Sense::click());
+            let color = if button.hovered() { egui::Color32::WHITE } else { egui::Color32::GRAY };
+            painter.rect_filled(button_rect, 3.0 * self.zoom, colors::NODE_BG.gamma_multiply(0.8));
+            painter.text(
+                button_rect.center(),
+                egui::Align2::CENTER_CENTER,
+                if expanded { "⊟" } else { "⊞" },
+                egui::FontId::proportional(12.0 * self.zoom),
+                color,
+            );
+            if button.on_hover_text(if expanded { "Shrink preview" } else { "Enlarge preview" }).clicked() {
+                if expanded {
+                    self.expanded_previews.remove(&node_id);
+                } else {
+                    self.expanded_previews.insert(node_id);
+                }
+            }
+        }
+    }
+    
+    /// Unscaled width of a node; enlarged previews make it twice as wide
+    fn node_width(&self, node: &Node) -> f32 {
+        if self.expanded_previews.contains(&node.id) && self.output_previews.contains_key(&node.id) {
+            style::NODE_WIDTH * 2.0
+        } else {
+            style::NODE_WIDTH
+        }
+    }
+    
+    /// Unscaled size of a node's output preview, if it has one
+    fn preview_size(&self, node: &Node) -> Option<Vec2> {
+        let expanded = self.expanded_previews.contains(&node.id);
+        let width = self.node_width(node) - style::NODE_PADDING * 2.0;
+        match self.output_previews.get(&node.id)? {
+            OutputPreview::Image(texture) => {
+                let [w, h] = texture.handle.size();
+                let max_height = if expanded { style::NODE_PREVIEW_HEIGHT * 2.0 } else { style::NODE_PREVIEW_HEIGHT };
+                let scale = (width / w.max(1) as f32).min(max_height / h.max(1) as f32);
+                Some(Vec2::new(w as f32, h as f32) * scale)
+            }
+            OutputPreview::Text(_) => {
+                let lines = if expanded { style::NODE_PREVIEW_LINES_EXPANDED } else { style::NODE_PREVIEW_LINES };
+                Some(Vec2::new(width, lines as f32 * style::NODE_PREVIEW_LINE_HEIGHT + 8.0))
+            }
+        }
+    }
+    
+    /// Screen rectangle of a node
+    fn node_rect(&self, node: &Node, canvas_rect: Rect) -> Rect {
+        let slots = node.node_type.inputs().len().max(node.node_type.outputs().len());
+        let content_height = slots as f32 * style::NODE_SLOT_HEIGHT + style::NODE_PADDING * 2.0;
+        let preview_height = self.preview_size(node).map_or(0.0, |size| size.y + style::NODE_PADDING);
+        let node_height = style::NODE_HEADER_HEIGHT + content_height + preview_height;
+        
+        let node_pos = Pos2::new(
+            canvas_rect.left() + node.position.x * self.zoom + self.pan_offset.x,
+            canvas_rect.top() + node.position.y * self.zoom + self.pan_offset.y,
+        );
+        
+        Rect::from_min_size(node_pos, Vec2::new(self.node_width(node), node_height) * self.zoom)
     }
     
     fn get_slot_position(&self, node: &Node, slot_index: usize, is_input: bool, canvas_rect: Rect) -> Pos2 {
-        let node_width = style::NODE_WIDTH * self.zoom;
+        let node_width = self.node_width(node) * self.zoom;
         let header_height = style::NODE_HEADER_HEIGHT * self.zoom;
         let slot_height = style::NODE_SLOT_HEIGHT * self.zoom;
         let padding = style::NODE_PADDING * self.zoom;
@@ -741,12 +1003,117 @@ impl NodeGraph {
         });
     }
     
+    /// Interactive curve editor: click to add, drag to move, double/right-click to delete
+    fn curve_editor(ui: &mut egui::Ui, curve: &mut Curve, color: egui::Color32) {
+        let size = ui.available_width().min(220.0);
+        let (response, painter) = ui.allocate_painter(
+            egui::vec2(size, size),
+            egui::Sense::click_and_drag(),
+        );
+        let rect = response.rect;
+        
+        let to_screen = |p: [f32; 2]| egui::pos2(
+            rect.left() + p[0] * rect.width(),
+            rect.bottom() - p[1] * rect.height(),
+        );
+        let from_screen = |pos: Pos2| [
+            ((pos.x - rect.left()) / rect.width()).clamp(0.0, 1.0),
+            ((rect.bottom() - pos.y) / rect.height()).clamp(0.0, 1.0),
+        ];
+        
+        // Background and quarter grid
+        painter.rect_filled(rect, 2.0, colors::SLIDER_BG);
+        let grid = egui::Stroke::new(1.0, colors::GRID_LINE);
+        for i in 1..4 {
+            let t = i as f32 / 4.0;
+            painter.line_segment([to_screen([t, 0.0]), to_screen([t, 1.0])], grid);
+            painter.line_segment([to_screen([0.0, t]), to_screen([1.0, t])], grid);
+        }
+        painter.line_segment([to_screen([0.0, 0.0]), to_screen([1.0, 1.0])], grid);
+        
+        // Point under the pointer
+        let hit_radius = 8.0;
+        let hovered_point = response.hover_pos().and_then(|pos| {
+            curve.points.iter()
+                .enumerate()
+                .map(|(i, p)| (i, to_screen(*p).distance(pos)))
+                .filter(|(_, d)| *d <= hit_radius)
+                .min_by(|a, b| a.1.total_cmp(&b.1))
+                .map(|(i, _)| i)
+        });
+        
+        let drag_id = response.id.with("drag_point");
+        if response.drag_started() {
+            let index = hovered_point.or_else(|| {
+                // Start dragging a new point where the pointer went down
+                let pos = response.interact_pointer_pos()?;
+                let p = from_screen(pos);
+                let index = curve.points.partition_point(|q| q[0] < p[0]);
+                curve.points.insert(index, p);
+                Some(index)
+            });
+            ui.data_mut(|d| d.insert_temp(drag_id, index));
+        }
+        
+        let dragging: Option<usize> = ui.data(|d| d.get_temp(drag_id)).flatten();
+        if let (Some(index), true) = (dragging, response.dragged()) {
+            if let (Some(pos), true) = (response.interact_pointer_pos(), index < curve.points.len()) {
+                let p = from_screen(pos);
+                
+                // Keep x strictly between neighbours so points stay sorted
+                let min_x = if index == 0 { 0.0 } else { curve.points[index - 1][0] + 0.01 };
+                let max_x = if index + 1 == curve.points.len() { 1.0 } else { curve.points[index + 1][0] - 0.01 };
+                curve.points[index] = [p[0].clamp(min_x, max_x.max(min_x)), p[1]];
+            }
+        }
+        if response.drag_stopped() {
+            ui.data_mut(|d| d.remove::<Option<usize>>(drag_id));
+        }
+        
+        if response.clicked() && hovered_point.is_none() {
+            if let Some(pos) = response.interact_pointer_pos() {
+                let p = from_screen(pos);
+                let index = curve.points.partition_point(|q| q[0] < p[0]);
+                curve.points.insert(index, p);
+            }
+        }
+        
+        // Delete, keeping at least two points
+        if (response.double_clicked() || response.secondary_clicked()) && curve.points.len() > 2 {
+            if let Some(index) = hovered_point {
+                curve.points.remove(index);
+            }
+        }
+        
+        // Curve
+        let lut = curve.to_lut();
+        let line: Vec<Pos2> = lut.iter()
+            .enumerate()
+            .map(|(i, y)| to_screen([i as f32 / 255.0, *y]))
+            .collect();
+        painter.add(egui::Shape::line(line, egui::Stroke::new(2.0, color)));
+        
+        // Control points
+        for (i, p) in curve.points.iter().enumerate() {
+            let pos = to_screen(*p);
+            let radius = if Some(i) == hovered_point || Some(i) == dragging { 5.0 } else { 4.0 };
+            painter.circle_filled(pos, radius, egui::Color32::WHITE);
+            painter.circle_stroke(pos, radius, egui::Stroke::new(1.0, egui::Color32::BLACK));
+        }
+    }
+    
     /// Show properties panel for a node
     pub fn show_node_properties(&mut self, ui: &mut egui::Ui, node_id: Uuid) {
-        let node = match self.nodes.get_mut(&node_id) {
+        let connected_inputs: Vec<usize> = self.connections.iter()
+            .filter(|c| c.to_node == node_id)
+            .map(|c| c.to_slot)
+            .collect();
+        
+        let node = match self.graph.nodes.get_mut(&node_id) {
             Some(n) => n,
             None => return,
         };
+        let luts = &mut self.graph.luts;
         
         ui.label(egui::RichText::new(node.node_type.name()).heading());
         ui.separator();
@@ -769,8 +1136,12 @@ impl NodeGraph {
                 }
             }
             
-            NodeProperties::Bucket { images } => {
-                ui.label(format!("{} images", images.len()));
+            NodeProperties::Bucket { image_ids, .. } => {
+                ui.label(format!("{} images", image_ids.len()));
+                ui.label(egui::RichText::new("Drop images while the Bucket is selected to add them").small().weak());
+                if !image_ids.is_empty() && ui.button("Clear").clicked() {
+                    image_ids.clear();
+                }
             }
             
             // === Adjust Node (full color grading) ===
@@ -779,7 +1150,8 @@ impl NodeGraph {
                 highlights, shadows, temperature, tint,
                 vibrance, gamma, 
                 lift, gamma_wheel, gain, offset,
-                color_boost, hue_rotation, luminance_mix, ..
+                color_boost, hue_rotation, luminance_mix,
+                curves_enabled, curves,
             } => {
                 egui::CollapsingHeader::new("Basic Adjustments")
                     .default_open(true)
@@ -827,11 +1199,43 @@ impl NodeGraph {
                             Self::color_wheel(ui, "Offset", offset);
                         });
                     });
+                
+                egui::CollapsingHeader::new("📈 Curves")
+                    .default_open(false)
+                    .show(ui, |ui| {
+                        ui.checkbox(curves_enabled, "Enabled");
+                        
+                        // Channel being edited is UI state only
+                        let channel_id = ui.id().with("curve_channel");
+                        let mut channel: usize = ui.data(|d| d.get_temp(channel_id)).unwrap_or(0);
+                        ui.horizontal(|ui| {
+                            for (i, name) in ["Master", "R", "G", "B"].iter().enumerate() {
+                                ui.selectable_value(&mut channel, i, *name);
+                            }
+                        });
+                        ui.data_mut(|d| d.insert_temp(channel_id, channel));
+                        
+                        let (curve, color) = match channel {
+                            1 => (&mut curves.red, egui::Color32::from_rgb(239, 83, 80)),
+                            2 => (&mut curves.green, egui::Color32::from_rgb(102, 187, 106)),
+                            3 => (&mut curves.blue, egui::Color32::from_rgb(66, 165, 245)),
+                            _ => (&mut curves.master, egui::Color32::WHITE),
+                        };
+                        ui.add_enabled_ui(*curves_enabled, |ui| {
+                            Self::curve_editor(ui, curve, color);
+                        });
+                        ui.horizontal(|ui| {
+                            ui.label(egui::RichText::new("Click to add, right-click to remove").small());
+                            if ui.small_button("Reset").clicked() {
+                                *curve = Curve::default();
+                            }
+                        });
+                    });
             }
             
             // === Effects Node ===
             NodeProperties::Effects {
-                gaussian_blur, directional_blur, directional_blur_angle,
+                gaussian_blur, blur_edge_mode, directional_blur, directional_blur_angle,
                 progressive_blur, progressive_blur_direction, progressive_blur_falloff,
                 glass_blinds, glass_blinds_frequency, glass_blinds_angle, glass_blinds_phase,
                 grain, grain_size, grain_monochrome, grain_seed,
@@ -860,6 +1264,18 @@ impl NodeGraph {
                                     });
                             });
                         }
+                        if *gaussian_blur > 0.0 || *directional_blur > 0.0 || *progressive_blur > 0.0 {
+                            ui.horizontal(|ui| {
+                                ui.label("Edges:");
+                                egui::ComboBox::from_id_salt("blur_edge_mode")
+                                    .selected_text(blur_edge_mode.name())
+                                    .show_ui(ui, |ui| {
+                                        for mode in EdgeMode::ALL {
+                                            ui.selectable_value(blur_edge_mode, mode, mode.name());
+                                        }
+                                    });
+                            });
+                        }
                     });
                 
                 egui::CollapsingHeader::new("Glass Blinds")
@@ -893,6 +1309,82 @@ impl NodeGraph {
                     });
             }
             
+            // === LUT Node ===
+            NodeProperties::Lut { file_name, lut, interpolation, intensity } => {
+                let table = lut.as_ref().and_then(|r| luts.get(r.hash)).cloned();
+                match &table {
+                    Some(l) => {
+                        ui.label(if file_name.is_empty() { "Untitled LUT" } else { file_name.as_str() });
+                        let kind = match l.kind {
+                            LutKind::OneD => format!("1D, {} entries", l.size),
+                            LutKind::ThreeD => format!("3D, {}³", l.size),
+                        };
+                        ui.label(egui::RichText::new(kind).small().weak());
+                    }
+                    None => {
+                        ui.label("Drop a .cube file to load it");
+                    }
+                }
+                
+                #[cfg(not(target_arch = "wasm32"))]
+                if ui.button("📂 Load .cube...").clicked() {
+                    if let Some(path) = rfd::FileDialog::new().add_filter("Cube LUT", &["cube"]).pick_file() {
+                        match std::fs::read_to_string(&path).map_err(|e| e.to_string()).and_then(|t| Lut::parse_cube(&t)) {
+                            Ok(parsed) => {
+                                *file_name = path.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default();
+                                *lut = Some(luts.insert(parsed));
+                            }
+                            Err(e) => log::error!("Failed to load LUT: {}", e),
+                        }
+                    }
+                }
+                
+                ui.add_enabled_ui(table.is_some_and(|l| l.kind == LutKind::ThreeD), |ui| {
+                    ui.horizontal(|ui| {
+                        ui.label("Interpolation:");
+                        egui::ComboBox::from_id_salt("lut_interpolation")
+                            .selected_text(interpolation.name())
+                            .show_ui(ui, |ui| {
+                                for mode in LutInterpolation::ALL {
+                                    ui.selectable_value(interpolation, mode, mode.name());
+                                }
+                            });
+                    });
+                });
+                ui.add(egui::Slider::new(intensity, 0.0..=100.0).text("Intensity").suffix("%"));
+            }
+            
+            // === Mask Node ===
+            NodeProperties::Mask { source, center, size, angle, invert, feather, density } => {
+                ui.horizontal(|ui| {
+                    ui.label("Source:");
+                    egui::ComboBox::from_id_salt("mask_source")
+                        .selected_text(source.name())
+                        .show_ui(ui, |ui| {
+                            for s in MaskSource::ALL {
+                                ui.selectable_value(source, s, s.name());
+                            }
+                        });
+                });
+                
+                if source.is_shape() {
+                    ui.add(egui::Slider::new(&mut center[0], 0.0..=1.0).text("Center X"));
+                    ui.add(egui::Slider::new(&mut center[1], 0.0..=1.0).text("Center Y"));
+                    if *source == MaskSource::Gradient {
+                        ui.add(egui::Slider::new(&mut size[0], 0.0..=1.0).text("Width"));
+                    } else {
+                        ui.add(egui::Slider::new(&mut size[0], 0.0..=2.0).text("Width"));
+                        ui.add(egui::Slider::new(&mut size[1], 0.0..=2.0).text("Height"));
+                    }
+                    ui.add(egui::Slider::new(angle, 0.0..=360.0).text("Angle").suffix("°"));
+                }
+                
+                ui.separator();
+                ui.checkbox(invert, "Invert");
+                ui.add(egui::Slider::new(feather, 0.0..=100.0).text("Feather"));
+                ui.add(egui::Slider::new(density, 0.0..=100.0).text("Density").suffix("%"));
+            }
+            
             // === Text Nodes ===
             NodeProperties::Text { text } => {
                 ui.text_edit_multiline(text);
@@ -921,12 +1413,80 @@ impl NodeGraph {
             }
             
             // === Utility Nodes ===
-            NodeProperties::Compare {} => {
-                ui.label("Drop 2 images to compare");
+            NodeProperties::Compare { mode, split, metrics } => {
+                ui.horizontal(|ui| {
+                    ui.label("Mode:");
+                    egui::ComboBox::from_id_salt("compare_mode")
+                        .selected_text(mode.name())
+                        .show_ui(ui, |ui| {
+                            for m in CompareMode::ALL {
+                                ui.selectable_value(mode, m, m.name());
+                            }
+                        });
+                });
+                
+                if matches!(mode, CompareMode::SplitVertical | CompareMode::SplitHorizontal) {
+                    ui.add(egui::Slider::new(split, 0.0..=1.0).show_value(false).text("Split"));
+                }
+                
+                ui.separator();
+                match metrics {
+                    Some(m) => {
+                        if m.psnr.is_finite() {
+                            ui.label(format!("PSNR: {:.2} dB", m.psnr));
+                        } else {
+                            ui.label("PSNR: ∞ (identical)");
+                        }
+                        ui.label(format!("SSIM: {:.4}", m.ssim));
+                    }
+                    None => {
+                        ui.label("Connect 2 images and run to compare");
+                    }
+                }
             }
             
             NodeProperties::Composition { layers } => {
-                ui.label(format!("{} layers", layers.len()));
+                let mut slots = connected_inputs;
+                slots.sort_unstable();
+                slots.dedup();
+                
+                if slots.is_empty() {
+                    ui.label("Connect images to add layers");
+                }
+                
+                // One layer per input slot, created on demand
+                if let Some(&last) = slots.last() {
+                    if layers.len() <= last {
+                        layers.resize_with(last + 1, Default::default);
+                    }
+                }
+                
+                // Top layer first, like a layers panel
+                for &slot in slots.iter().rev() {
+                    let layer = &mut layers[slot];
+                    egui::CollapsingHeader::new(format!("Layer {}", slot + 1))
+                        .id_salt(("composition_layer", slot))
+                        .default_open(true)
+                        .show(ui, |ui| {
+                            ui.horizontal(|ui| {
+                                ui.label("Blend:");
+                                egui::ComboBox::from_id_salt(("blend_mode", slot))
+                                    .selected_text(layer.blend_mode.name())
+                                    .show_ui(ui, |ui| {
+                                        for mode in BlendMode::ALL {
+                                            ui.selectable_value(&mut layer.blend_mode, mode, mode.name());
+                                        }
+                                    });
+                            });
+                            ui.add(egui::Slider::new(&mut layer.opacity, 0.0..=1.0).text("Opacity"));
+                            ui.add(egui::Slider::new(&mut layer.scale, 0.05..=4.0).logarithmic(true).text("Scale"));
+                            ui.horizontal(|ui| {
+                                ui.label("Position:");
+                                ui.add(egui::DragValue::new(&mut layer.position.0).prefix("x: ").suffix(" px"));
+                                ui.add(egui::DragValue::new(&mut layer.position.1).prefix("y: ").suffix(" px"));
+                            });
+                        });
+                }
             }
             
             NodeProperties::Router { active_output } => {
@@ -935,6 +1495,11 @@ impl NodeGraph {
             
             NodeProperties::Batch { items } => {
                 ui.label(format!("{} items in batch", items.len()));
+                ui.label(egui::RichText::new("One item per line").small().weak());
+                let mut text = items.join("\n");
+                if ui.text_edit_multiline(&mut text).changed() {
+                    *items = text.lines().map(String::from).collect();
+                }
             }
             
             NodeProperties::Title { text } => {
@@ -945,6 +1510,47 @@ impl NodeGraph {
                 ui.label("No properties");
             }
             
+            // === Output Nodes ===
+            NodeProperties::Output { name, format, max_size, bit_depth, color_space } => {
+                ui.horizontal(|ui| {
+                    ui.label("Name:");
+                    ui.text_edit_singleline(name);
+                });
+                ui.horizontal(|ui| {
+                    ui.label("Format:");
+                    egui::ComboBox::from_id_salt("output_format")
+                        .selected_text(format.as_str().to_uppercase())
+                        .show_ui(ui, |ui| {
+                            ui.selectable_value(format, ExportFormat::Png, "PNG");
+                            ui.selectable_value(format, ExportFormat::Jpeg, "JPEG");
+                            ui.selectable_value(format, ExportFormat::Webp, "WEBP");
+                            ui.selectable_value(format, ExportFormat::Tiff, "TIFF");
+                        });
+                });
+                ui.add_enabled_ui(format.supports_16_bit(), |ui| {
+                    ui.horizontal(|ui| {
+                        ui.label("Depth:");
+                        ui.selectable_value(bit_depth, ExportDepth::Eight, "8-bit");
+                        ui.selectable_value(bit_depth, ExportDepth::Sixteen, "16-bit");
+                    });
+                });
+                ui.horizontal(|ui| {
+                    ui.label("Color:");
+                    egui::ComboBox::from_id_salt("output_color_space")
+                        .selected_text(color_space.name())
+                        .show_ui(ui, |ui| {
+                            for space in ColorSpace::OUTPUT {
+                                ui.selectable_value(color_space, space, space.name());
+                            }
+                        });
+                });
+                ui.horizontal(|ui| {
+                    ui.label("Max size:");
+                    ui.add(egui::DragValue::new(max_size).range(0..=16384).suffix(" px"));
+                });
+                ui.label(egui::RichText::new("0 keeps the original size").small().weak());
+            }
+            
             // === AI Nodes ===
             NodeProperties::Omni { model, prompt, negative_prompt, seed } => {
                 ui.horizontal(|ui| {
@@ -0,0 +1,257 @@
+//! Undo/redo history for the node graph
+//!
+//! The history keeps snapshots of the graph's nodes and connections. Instead of
+//! instrumenting every mutation, the app calls [`History::record`] once per frame;
+//! a new step is recorded whenever the graph differs from the last snapshot and
+//! no pointer button is held, so a whole slider or node drag becomes one step.
+
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
+
+use uuid::Uuid;
+
+use crate::color::WorkingSpace;
+use crate::graph::{Connection, GraphEditor, NodeGraph};
+use crate::nodes::Node;
+
+/// Steps kept before the oldest are dropped
+const MAX_STEPS: usize = 100;
+
+/// Consecutive edits to the same node within this many seconds are merged
+const COALESCE_SECONDS: f64 = 1.0;
+
+/// Everything in the graph that can be undone
+#[derive(Clone)]
+struct GraphState {
+    nodes: HashMap<Uuid, Node>,
+    connections: Vec<Connection>,
+    working_space: WorkingSpace,
+}
+
+impl GraphState {
+    fn capture(graph: &NodeGraph) -> Self {
+        Self {
+            nodes: graph.nodes.clone(),
+            connections: graph.connections.clone(),
+            working_space: graph.working_space,
+        }
+    }
+}
+
+/// One undoable change: the state before it and a description
+struct Step {
+    label: String,
+    before: GraphState,
+}
+
+/// Undo and redo stacks for one graph
+pub struct History {
+    undo: Vec<Step>,
+    redo: Vec<Step>,
+    /// Snapshot of the graph as of the last recorded step
+    current: GraphState,
+    current_hash: u64,
+    /// Node edited by the last step and when, for merging edits
+    last_edit: Option<(Uuid, f64)>,
+}
+
+impl History {
+    pub fn new(graph: &NodeGraph) -> Self {
+        Self {
+            undo: Vec::new(),
+            redo: Vec::new(),
+            current: GraphState::capture(graph),
+            current_hash: state_hash(graph),
+            last_edit: None,
+        }
+    }
+
+    /// Forget all steps and start again from the graph as it is now
+    pub fn reset(&mut self, graph: &NodeGraph) {
+        *self = Self::new(graph);
+    }
+
+    pub fn can_undo(&self) -> bool {
+        !self.undo.is_empty()
+    }
+
+    pub fn can_redo(&self) -> bool {
+        !self.redo.is_empty()
+    }
+
+    /// Label of the step Undo would revert
+    pub fn undo_label(&self) -> Option<&str> {
+        self.undo.last().map(|s| s.label.as_str())
+    }
+
+    /// Label of the step Redo would reapply
+    pub fn redo_label(&self) -> Option<&str> {
+        self.redo.last().map(|s| s.label.as_str())
+    }
+
+    /// Record a step if the graph changed since the last one. Call once per
+    /// frame with `pointer_down` set while any mouse button is held.
+    pub fn record(&mut self, graph: &NodeGraph, pointer_down: bool, now: f64) {
+        if pointer_down {
+            return;
+        }
+
+        let hash = state_hash(graph);
+        if hash == self.current_hash {
+            return;
+        }
+
+        let state = GraphState::capture(graph);
+        let (label, edited) = describe(&self.current, &state);
+
+        let merge = match (edited, self.last_edit) {
+            (Some(id), Some((last_id, at))) => id == last_id && now - at < COALESCE_SECONDS && !self.undo.is_empty(),
+            _ => false,
+        };
+        if merge {
+            self.current = state;
+        } else {
+            let before = std::mem::replace(&mut self.current, state);
+            self.undo.push(Step { label, before });
+            if self.undo.len() > MAX_STEPS {
+                self.undo.remove(0);
+            }
+        }
+        self.redo.clear();
+        self.current_hash = hash;
+        self.last_edit = edited.map(|id| (id, now));
+    }
+
+    /// Revert the last step. Returns false if there was nothing to undo.
+    pub fn undo(&mut self, graph: &mut GraphEditor) -> bool {
+        let Some(step) = self.undo.pop() else {
+            return false;
+        };
+        let after = std::mem::replace(&mut self.current, step.before);
+        self.redo.push(Step { label: step.label, before: after });
+        self.apply(graph);
+        true
+    }
+
+    /// Reapply the last undone step. Returns false if there was nothing to redo.
+    pub fn redo(&mut self, graph: &mut GraphEditor) -> bool {
+        let Some(step) = self.redo.pop() else {
+            return false;
+        };
+        let before = std::mem::replace(&mut self.current, step.before);
+        self.undo.push(Step { label: step.label, before });
+        self.apply(graph);
+        true
+    }
+
+    fn apply(&mut self, graph: &mut GraphEditor) {
+        let state = self.current.clone();
+        graph.restore(state.nodes, state.connections, state.working_space);
+        self.current_hash = state_hash(graph);
+        self.last_edit = None;
+    }
+
+    /// List the history in a panel, oldest first. Clicking a step moves the
+    /// graph to the state right after it.
+    pub fn show(&mut self, ui: &mut egui::Ui, graph: &mut GraphEditor) {
+        let done = self.undo.len();
+        let mut target: Option<usize> = None;
+
+        egui::ScrollArea::vertical().show(ui, |ui| {
+            if ui.selectable_label(done == 0, "Initial state").clicked() {
+                target = Some(0);
+            }
+            for (i, step) in self.undo.iter().enumerate() {
+                if ui.selectable_label(i + 1 == done, &step.label).clicked() {
+                    target = Some(i + 1);
+                }
+            }
+            for (i, step) in self.redo.iter().rev().enumerate() {
+                let text = egui::RichText::new(&step.label).weak();
+                if ui.selectable_label(false, text).clicked() {
+                    target = Some(done + i + 1);
+                }
+            }
+        });
+
+        if let Some(target) = target {
+            while self.undo.len() > target && self.undo(graph) {}
+            while self.undo.len() < target && self.redo(graph) {}
+        }
+    }
+}
+
+/// Hash of everything a step restores, including node positions
+fn state_hash(graph: &NodeGraph) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    graph.fingerprint().hash(&mut hasher);
+
+    let mut positions: Vec<(Uuid, u32, u32)> = graph.nodes.values()
+        .map(|n| (n.id, n.position.x.to_bits(), n.position.y.to_bits()))
+        .collect();
+    positions.sort_unstable();
+    positions.hash(&mut hasher);
+
+    let mut labels: Vec<(Uuid, &Option<String>)> = graph.nodes.values()
+        .map(|n| (n.id, &n.label))
+        .collect();
+    labels.sort_unstable();
+    labels.hash(&mut hasher);
+
+    hasher.finish()
+}
+
+/// Describe the change between two states. Also returns the node whose
+/// properties were edited, if that was the only change.
+fn describe(before: &GraphState, after: &GraphState) -> (String, Option<Uuid>) {
+    let type_name = |state: &GraphState, id: &Uuid| state.nodes[id].node_type.name();
+    let count_label = |verb: &str, ids: Vec<&Uuid>, state: &GraphState| match ids.as_slice() {
+        [id] => format!("{} {}", verb, type_name(state, id)),
+        _ => format!("{} {} nodes", verb, ids.len()),
+    };
+
+    let added: Vec<&Uuid> = after.nodes.keys().filter(|id| !before.nodes.contains_key(id)).collect();
+    if !added.is_empty() {
+        return (count_label("Add", added, after), None);
+    }
+
+    let removed: Vec<&Uuid> = before.nodes.keys().filter(|id| !after.nodes.contains_key(id)).collect();
+    if !removed.is_empty() {
+        return (count_label("Delete", removed, before), None);
+    }
+
+    let key = |c: &Connection| (c.from_node, c.from_slot, c.to_node, c.to_slot);
+    let connections = |state: &GraphState| {
+        let mut keys: Vec<_> = state.connections.iter().map(key).collect();
+        keys.sort_unstable();
+        keys
+    };
+    let (before_connections, after_connections) = (connections(before), connections(after));
+    if before_connections != after_connections {
+        let label = if after_connections.len() >= before_connections.len() { "Connect" } else { "Disconnect" };
+        return (label.to_string(), None);
+    }
+
+    let moved: Vec<&Uuid> = after.nodes.iter()
+        .filter(|(id, node)| before.nodes[id].position != node.position)
+        .map(|(id, _)| id)
+        .collect();
+    if !moved.is_empty() {
+        return (count_label("Move", moved, after), None);
+    }
+
+    let edited: Vec<&Uuid> = after.nodes.iter()
+        .filter(|(id, node)| {
+            let old = &before.nodes[id];
+            old.label != node.label
+                || serde_json::to_string(&old.properties).ok() != serde_json::to_string(&node.properties).ok()
+        })
+        .map(|(id, _)| id)
+        .collect();
+    match edited.as_slice() {
+        [id] => (format!("Edit {}", type_name(after, id)), Some(**id)),
+        [] if before.working_space != after.working_space => ("Change working space".to_string(), None),
+        ids => (format!("Edit {} nodes", ids.len()), None),
+    }
+}
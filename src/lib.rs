@@ -0,0 +1,19 @@
+//! FlowNode Canvas - node editor built on the flownode-core engine
+//!
+//! The engine modules are re-exported so the editor and anything embedding it
+//! can keep using `nodes`, `executor` and friends through this crate.
+
+#![warn(clippy::all)]
+
+pub use flownode_core::{backend, color, compat, cpu, executor, image_data, lut, mask, nodes, zip};
+
+pub mod app;
+pub mod graph;
+pub mod ui_components;
+pub mod texture;
+pub mod gpu;
+pub mod history;
+pub mod cloud;
+pub mod worker;
+#[cfg(all(target_arch = "wasm32", feature = "wasm-threads"))]
+pub mod threads;
@@ -5,17 +5,7 @@
 
 #![warn(clippy::all)]
 
-mod app;
-mod nodes;
-mod graph;
-mod ui_components;
-mod compat;
-mod image_data;
-mod gpu;
-mod executor;
-mod cloud;
-
-use app::FlowNodeApp;
+use flownode_canvas::app::FlowNodeApp;
 
 // Native entry point
 #[cfg(not(target_arch = "wasm32"))]
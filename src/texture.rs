@@ -0,0 +1,46 @@
+//! egui textures for displaying images
+//!
+//! The engine's image buffers know nothing about the GUI; this module makes
+//! 8-bit sRGB textures from them for the canvas.
+
+use eframe::egui;
+
+use crate::color::{self, ColorSpace};
+use crate::image_data::{downscale, ImageData};
+
+/// Convert ImageData to egui ColorImage for display (always 8-bit sRGB)
+pub fn to_color_image(data: &ImageData) -> egui::ColorImage {
+    egui::ColorImage::from_rgba_unmultiplied(
+        [data.width as usize, data.height as usize],
+        &color::convert(data, ColorSpace::Srgb).to_rgba8(),
+    )
+}
+
+/// Texture handle for caching in egui
+#[derive(Clone)]
+pub struct TextureHandle {
+    pub handle: egui::TextureHandle,
+    pub size: [u32; 2],
+}
+
+impl TextureHandle {
+    /// Create a new texture from image data, downscaled so its longest side is
+    /// at most `max_dimension`. `size` keeps the full-resolution dimensions.
+    pub fn from_image_data(ctx: &egui::Context, name: &str, data: &ImageData, max_dimension: u32) -> Self {
+        let color_image = to_color_image(&downscale(data, max_dimension));
+        let handle = ctx.load_texture(
+            name,
+            color_image,
+            egui::TextureOptions::LINEAR,
+        );
+        Self {
+            handle,
+            size: [data.width, data.height],
+        }
+    }
+    
+    /// Get the texture ID for rendering
+    pub fn id(&self) -> egui::TextureId {
+        self.handle.id()
+    }
+}
@@ -0,0 +1,169 @@
+//! Rayon thread pool for the graph Web Worker (`wasm-threads` feature)
+//!
+//! Browsers can't spawn threads from wasm, so each pool thread is a Web
+//! Worker started on the same module and the same shared memory. That needs a
+//! build with atomics (see the README) and a cross-origin isolated page,
+//! which is what makes `SharedArrayBuffer` available. Without either, the
+//! pool isn't started and the kernels run on the worker's own thread as in
+//! other builds.
+
+use std::cell::{Cell, RefCell};
+use std::rc::Rc;
+use std::sync::mpsc::{self, Receiver};
+use std::sync::{Arc, Mutex};
+
+use rayon::ThreadBuilder;
+use wasm_bindgen::closure::Closure;
+use wasm_bindgen::prelude::*;
+use wasm_bindgen::JsCast;
+
+/// wasm-bindgen glue for the worker binary, without the loader that starts it
+const GLUE_SCRIPT: &str = "flownode-worker.js";
+
+/// Pool thread: load the module on the memory it's sent, report in, then
+/// wait to be handed a rayon thread
+const THREAD_SCRIPT: &str = "
+self.onmessage = async (event) => {
+    const [glue, module, memory, receiver] = event.data;
+    importScripts(glue);
+    await wasm_bindgen({ module_or_path: module, memory });
+    postMessage('ready');
+    wasm_bindgen.flownode_thread_run(receiver);
+};
+";
+
+type Threads = Mutex<Receiver<ThreadBuilder>>;
+
+/// Called once, when the pool is up or has failed to start
+type OnReady = Rc<RefCell<Option<Box<dyn FnOnce()>>>>;
+
+fn finish(on_ready: &OnReady) {
+    if let Some(on_ready) = on_ready.borrow_mut().take() {
+        on_ready();
+    }
+}
+
+/// Entry point of a pool thread's Web Worker
+#[wasm_bindgen]
+pub fn flownode_thread_run(receiver: u32) {
+    // SAFETY: `start` leaked one reference to the receiver for each thread
+    let receiver = unsafe { Arc::from_raw(receiver as *const Threads) };
+    let thread = receiver.lock().map_err(|e| e.to_string()).and_then(|r| r.recv().map_err(|e| e.to_string()));
+    drop(receiver);
+    match thread {
+        Ok(thread) => thread.run(),
+        Err(e) => log::error!("Pool thread wasn't given any work: {}", e),
+    }
+}
+
+/// Start one pool thread per core, then call `on_ready`. If the pool can't be
+/// started `on_ready` is called anyway and rayon stays on the current thread.
+pub fn start(on_ready: impl FnOnce() + 'static) {
+    let on_ready: OnReady = Rc::new(RefCell::new(Some(Box::new(on_ready))));
+    if let Err(e) = spawn_threads(&on_ready) {
+        log::info!("Running kernels on one thread: {}", e);
+        finish(&on_ready);
+    }
+}
+
+fn spawn_threads(on_ready: &OnReady) -> Result<(), String> {
+    let scope: web_sys::DedicatedWorkerGlobalScope = js_sys::global().unchecked_into();
+    let isolated = js_sys::Reflect::get(&scope, &"crossOriginIsolated".into())
+        .ok()
+        .and_then(|v| v.as_bool())
+        .unwrap_or(false);
+    if !isolated {
+        return Err("the page isn't cross-origin isolated, so there's no SharedArrayBuffer".to_string());
+    }
+    let memory: js_sys::WebAssembly::Memory = wasm_bindgen::memory().unchecked_into();
+    if !memory.buffer().is_instance_of::<js_sys::SharedArrayBuffer>() {
+        return Err("this build doesn't use shared memory".to_string());
+    }
+
+    let threads = (scope.navigator().hardware_concurrency() as usize).max(1);
+    let js_error = |e: JsValue| format!("{:?}", e);
+    let glue = web_sys::Url::new_with_base(GLUE_SCRIPT, &scope.location().href()).map_err(js_error)?.href();
+    let options = web_sys::BlobPropertyBag::new();
+    options.set_type("application/javascript");
+    let blob = web_sys::Blob::new_with_str_sequence_and_options(&js_sys::Array::of1(&THREAD_SCRIPT.into()), &options)
+        .map_err(js_error)?;
+    let script = web_sys::Url::create_object_url_with_blob(&blob).map_err(js_error)?;
+
+    let (sender, receiver) = mpsc::channel::<ThreadBuilder>();
+    let receiver = Arc::new(Mutex::new(receiver));
+    // Taken when the pool is built or given up on, so that happens once
+    let sender = Rc::new(RefCell::new(Some(sender)));
+    let ready = Rc::new(Cell::new(0));
+    let workers = Rc::new(RefCell::new(Vec::<web_sys::Worker>::new()));
+    let give_up = {
+        let (sender, workers) = (sender.clone(), workers.clone());
+        move || {
+            let stopped = sender.borrow_mut().take().is_some();
+            if stopped {
+                workers.borrow().iter().for_each(web_sys::Worker::terminate);
+            }
+            stopped
+        }
+    };
+
+    for _ in 0..threads {
+        let worker = match web_sys::Worker::new(&script) {
+            Ok(worker) => worker,
+            Err(e) => {
+                give_up();
+                return Err(js_error(e));
+            }
+        };
+
+        // Build the pool once every thread is waiting for work
+        let on_message = Closure::<dyn FnMut(web_sys::MessageEvent)>::new({
+            let (on_ready, sender, ready) = (on_ready.clone(), sender.clone(), ready.clone());
+            move |_: web_sys::MessageEvent| {
+                ready.set(ready.get() + 1);
+                if ready.get() < threads {
+                    return;
+                }
+                let Some(sender) = sender.borrow_mut().take() else {
+                    return;
+                };
+                let built = rayon::ThreadPoolBuilder::new()
+                    .num_threads(threads)
+                    .spawn_handler(move |thread| sender.send(thread).map_err(|e| std::io::Error::other(e.to_string())))
+                    .build_global();
+                match built {
+                    Ok(()) => log::info!("Running kernels on {} threads", threads),
+                    Err(e) => log::error!("Couldn't start the thread pool: {}", e),
+                }
+                finish(&on_ready);
+            }
+        });
+        worker.set_onmessage(Some(on_message.as_ref().unchecked_ref()));
+        on_message.forget();
+
+        // A thread that fails to load would leave the others waiting
+        let on_error = Closure::<dyn FnMut(web_sys::Event)>::new({
+            let (on_ready, give_up) = (on_ready.clone(), give_up.clone());
+            move |_: web_sys::Event| {
+                if give_up() {
+                    log::error!("A pool thread failed to start; running kernels on one thread");
+                    finish(&on_ready);
+                }
+            }
+        });
+        worker.set_onerror(Some(on_error.as_ref().unchecked_ref()));
+        on_error.forget();
+
+        let message = js_sys::Array::of4(
+            &glue.clone().into(),
+            &wasm_bindgen::module(),
+            &memory,
+            &(Arc::into_raw(receiver.clone()) as u32).into(),
+        );
+        workers.borrow_mut().push(worker.clone());
+        if let Err(e) = worker.post_message(&message) {
+            give_up();
+            return Err(js_error(e));
+        }
+    }
+    Ok(())
+}
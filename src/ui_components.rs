@@ -19,6 +19,20 @@ pub mod style {
     
     /// Connection line width
     pub const CONNECTION_WIDTH: f32 = 3.0;
+    pub const CONNECTION_HIGHLIGHT_WIDTH: f32 = 5.0;
+    
+    /// Longest side of the thumbnails shown on wires
+    pub const WIRE_PREVIEW_SIZE: f32 = 48.0;
+    
+    /// Output previews inside nodes: image height (doubled when enlarged,
+    /// along with the node width) and lines of text shown
+    pub const NODE_PREVIEW_HEIGHT: f32 = 96.0;
+    pub const NODE_PREVIEW_LINES: usize = 2;
+    pub const NODE_PREVIEW_LINES_EXPANDED: usize = 8;
+    pub const NODE_PREVIEW_LINE_HEIGHT: f32 = 14.0;
+    
+    /// Longest side of the preview textures, enough for an enlarged node
+    pub const PREVIEW_TEXTURE_SIZE: u32 = 384;
     
     /// Grid
     pub const GRID_SIZE: f32 = 20.0;
@@ -32,6 +46,11 @@ pub mod style {
 pub mod colors {
     use super::Color32;
     
+    /// A node or slot colour from the engine's sRGB triple
+    pub fn rgb([r, g, b]: [u8; 3]) -> Color32 {
+        Color32::from_rgb(r, g, b)
+    }
+    
     // Canvas
     pub const CANVAS_BG: Color32 = Color32::from_rgb(26, 26, 46);
     pub const GRID_LINE: Color32 = Color32::from_rgba_premultiplied(255, 255, 255, 15);
@@ -40,6 +59,8 @@ pub mod colors {
     pub const NODE_BG: Color32 = Color32::from_rgb(40, 40, 55);
     pub const NODE_BG_SELECTED: Color32 = Color32::from_rgb(50, 50, 70);
     pub const NODE_SELECTED_OUTLINE: Color32 = Color32::from_rgb(100, 149, 237);
+    pub const SELECTION_BOX: Color32 = Color32::from_rgba_premultiplied(12, 18, 28, 30);
+    pub const CONNECTION_REJECTED: Color32 = Color32::from_rgb(244, 67, 54);
     
     // Node categories
     pub const CAT_INPUT: Color32 = Color32::from_rgb(76, 175, 80);      // Green
@@ -67,7 +88,7 @@ pub mod colors {
 /// Unified slider component
 /// 
 /// Usage:
-/// ```
+/// ```ignore
 /// let response = FlowSlider::new(&mut value, 0.0..=1.0)
 ///     .label("Brightness")
 ///     .show(ui);
@@ -0,0 +1,848 @@
+//! Runs the executor off the UI thread
+//!
+//! On native the executors live on a background thread; in the browser they
+//! live in a dedicated Web Worker (the `flownode-worker` binary). The app sends
+//! input images and [`Job`]s and polls for [`Response`]s once per frame.
+//!
+//! Starting a run cancels the one before it, and the running job stops at its
+//! next node. The Web Worker runs one node per timer tick so a `Cancel` or a
+//! newer run can be read between nodes.
+
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+
+use eframe::egui;
+use uuid::Uuid;
+
+use crate::executor::{ExecutionStats, Executor, NodeOutput, Progress, Run};
+use crate::gpu;
+use crate::graph::NodeGraph;
+use crate::image_data::{ImageData, ImageLimits};
+use crate::lut::{Lut, LutStore};
+use crate::nodes::CompareMetrics;
+
+/// A graph to run and how
+pub struct Job {
+    pub graph: NodeGraph,
+    /// Run on the low-resolution proxies instead of the full images
+    pub proxy: bool,
+    /// Size of the images run on relative to the full ones, see
+    /// [`Executor::set_pixel_scale`]
+    pub pixel_scale: f32,
+    /// Longest side of the node previews sent back
+    pub preview_size: u32,
+}
+
+/// Everything the app needs from a finished run
+pub struct RunResult {
+    pub job: u64,
+    pub proxy: bool,
+    /// Images of every Output node, one per batch item
+    pub outputs: HashMap<Uuid, Vec<ImageData>>,
+    /// Each node's value (first item for batches), images downscaled for previews
+    pub previews: HashMap<Uuid, NodeOutput>,
+    pub compare_metrics: HashMap<Uuid, CompareMetrics>,
+    pub stats: ExecutionStats,
+}
+
+/// Messages from the worker
+pub enum Response {
+    Progress { job: u64, progress: Progress },
+    Finished(Box<RunResult>),
+    Failed { job: u64, error: String },
+}
+
+/// Messages to the worker
+pub enum Request {
+    /// Make an input image available to runs
+    AddImage { id: u64, proxy: bool, image: ImageData },
+    /// Make a LUT table available to runs under its content hash
+    AddLut { hash: u64, lut: Arc<Lut> },
+    Run { job: u64, job_data: Job },
+    /// Stop the run in progress
+    Cancel,
+    /// Drop cached outputs so the next run recomputes everything
+    Invalidate,
+    SetLimits(ImageLimits),
+}
+
+/// Executors and input images, on whichever side of the channel runs them
+struct WorkerState {
+    executor: Executor,
+    /// Separate executor for proxy runs so they don't evict full-resolution results
+    proxy_executor: Executor,
+    images: HashMap<u64, ImageData>,
+    proxy_images: HashMap<u64, ImageData>,
+    /// Tables sent so far; jobs arrive without theirs
+    luts: LutStore,
+    /// Run being stepped through, see [`WorkerState::step`]
+    active: Option<ActiveRun>,
+}
+
+struct ActiveRun {
+    job: u64,
+    job_data: Job,
+    run: Run,
+}
+
+impl WorkerState {
+    fn new() -> Self {
+        let backend = gpu::default_backend();
+        let mut executor = Executor::new();
+        executor.set_backend(backend.clone());
+        let mut proxy_executor = Executor::new();
+        proxy_executor.set_backend(backend);
+        Self {
+            executor,
+            proxy_executor,
+            images: HashMap::new(),
+            proxy_images: HashMap::new(),
+            luts: LutStore::default(),
+            active: None,
+        }
+    }
+
+    /// Handle one request. `is_current` says whether a job is still wanted.
+    fn handle(&mut self, request: Request, is_current: &dyn Fn(u64) -> bool, send: &mut dyn FnMut(Response)) {
+        match request {
+            Request::AddImage { id, proxy: false, image } => {
+                self.images.insert(id, image);
+            }
+            Request::AddImage { id, proxy: true, image } => {
+                self.proxy_images.insert(id, image);
+            }
+            Request::AddLut { hash, lut } => self.luts.insert_shared(hash, lut),
+            Request::Run { job, job_data } => {
+                self.start(job, job_data, is_current, send);
+                while self.step(is_current, send) {}
+            }
+            Request::Cancel => self.active = None,
+            Request::Invalidate => {
+                self.executor.invalidate();
+                self.proxy_executor.invalidate();
+                // A run part way through would mix old and new outputs
+                if let Some(active) = self.active.take() {
+                    self.start(active.job, active.job_data, is_current, send);
+                }
+            }
+            Request::SetLimits(limits) => {
+                self.executor.set_limits(limits);
+                self.proxy_executor.set_limits(limits);
+            }
+        }
+    }
+
+    /// Begin running a job, replacing any run in progress. Nothing is
+    /// computed until [`WorkerState::step`].
+    fn start(&mut self, job: u64, mut job_data: Job, is_current: &dyn Fn(u64) -> bool, send: &mut dyn FnMut(Response)) {
+        self.active = None;
+        if !is_current(job) {
+            return;
+        }
+        job_data.graph.luts = self.luts.clone();
+        log::info!("Running node graph{}...", if job_data.proxy { " (proxy)" } else { "" });
+
+        let executor = if job_data.proxy { &mut self.proxy_executor } else { &mut self.executor };
+        executor.set_pixel_scale(job_data.pixel_scale);
+        match executor.start(&job_data.graph) {
+            Ok(run) => self.active = Some(ActiveRun { job, job_data, run }),
+            Err(error) => send(Response::Failed { job, error }),
+        }
+    }
+
+    /// Compute the next node of the run in progress, sending the result once
+    /// it's done. Returns whether the run has more to do.
+    fn step(&mut self, is_current: &dyn Fn(u64) -> bool, send: &mut dyn FnMut(Response)) -> bool {
+        let Some(ActiveRun { job, job_data, run }) = self.active.as_mut() else {
+            return false;
+        };
+        let job = *job;
+        let (executor, images) = if job_data.proxy {
+            (&mut self.proxy_executor, &self.proxy_images)
+        } else {
+            (&mut self.executor, &self.images)
+        };
+
+        let mut on_progress = |progress: Progress| {
+            if !is_current(job) {
+                return false;
+            }
+            send(Response::Progress { job, progress });
+            true
+        };
+        match executor.step(run, &job_data.graph, images, &mut on_progress) {
+            Ok(true) => return true,
+            Ok(false) => {}
+            Err(_) if !is_current(job) => {
+                log::info!("Run {} cancelled", job);
+                self.active = None;
+                return false;
+            }
+            Err(error) => {
+                send(Response::Failed { job, error });
+                self.active = None;
+                return false;
+            }
+        }
+
+        let Some(ActiveRun { job_data, run, .. }) = self.active.take() else {
+            return false;
+        };
+        let executor = if job_data.proxy { &mut self.proxy_executor } else { &mut self.executor };
+        let outputs = executor.finish(run, &job_data.graph);
+
+        let previews = job_data.graph.nodes_iter()
+            .filter_map(|(id, _)| {
+                // Batches preview their first item
+                let output = match executor.output(*id)? {
+                    NodeOutput::Batch(items) => items.first()?,
+                    output => output,
+                };
+                let preview = match output {
+                    NodeOutput::Image(image) => NodeOutput::Image(crate::image_data::downscale(image, job_data.preview_size)),
+                    NodeOutput::Text(text) => NodeOutput::Text(text.clone()),
+                    // Masks show as a tint over the image they were made from
+                    NodeOutput::Mask(mask) => {
+                        let source = job_data.graph.connections_iter()
+                            .find(|c| c.to_node == *id && c.to_slot == 0)
+                            .and_then(|c| match executor.output(c.from_node)? {
+                                NodeOutput::Batch(items) => items.first(),
+                                output => Some(output),
+                            });
+                        let image = match source {
+                            Some(NodeOutput::Image(image)) => {
+                                let image = crate::image_data::downscale(image, job_data.preview_size);
+                                crate::mask::overlay(&image, mask)
+                            }
+                            _ => crate::image_data::downscale(&mask.to_image(), job_data.preview_size),
+                        };
+                        NodeOutput::Image(image)
+                    }
+                    NodeOutput::Batch(_) | NodeOutput::None => return None,
+                };
+                Some((*id, preview))
+            })
+            .collect();
+
+        send(Response::Finished(Box::new(RunResult {
+            job,
+            proxy: job_data.proxy,
+            outputs,
+            previews,
+            compare_metrics: executor.compare_metrics().clone(),
+            stats: executor.stats(),
+        })));
+        false
+    }
+}
+
+/// Handle to the background executor
+pub struct Worker {
+    backend: Backend,
+    /// ID of the last job started
+    last_job: u64,
+    /// Hashes of the LUT tables already sent
+    sent_luts: HashSet<u64>,
+}
+
+enum Backend {
+    #[cfg(not(target_arch = "wasm32"))]
+    Thread(thread::ThreadBackend),
+    #[cfg(target_arch = "wasm32")]
+    Web(web::WebBackend),
+    /// No worker could be started; runs block the UI as they used to
+    Inline {
+        state: Box<WorkerState>,
+        responses: Vec<Response>,
+    },
+}
+
+impl Worker {
+    /// Start the worker. Falls back to running on the UI thread if it can't.
+    /// `ctx` is repainted whenever a response arrives.
+    pub fn spawn(ctx: &egui::Context) -> Self {
+        #[cfg(not(target_arch = "wasm32"))]
+        let backend = thread::ThreadBackend::spawn(ctx.clone()).map(Backend::Thread);
+        #[cfg(target_arch = "wasm32")]
+        let backend = web::WebBackend::spawn(ctx.clone()).map(Backend::Web);
+
+        let backend = backend.unwrap_or_else(|e| {
+            log::error!("Couldn't start the graph worker, running on the UI thread: {}", e);
+            Backend::Inline { state: Box::new(WorkerState::new()), responses: Vec::new() }
+        });
+        Self { backend, last_job: 0, sent_luts: HashSet::new() }
+    }
+
+    /// Whether `job` is the last one started and hasn't been cancelled
+    pub fn is_current(&self, job: u64) -> bool {
+        job != 0 && job == self.last_job
+    }
+
+    /// Make an image available to full-resolution or proxy runs under `id`
+    pub fn add_image(&mut self, id: u64, proxy: bool, image: ImageData) {
+        self.send(Request::AddImage { id, proxy, image });
+    }
+
+    /// Start running a graph, cancelling any run in progress. Returns the job ID.
+    /// LUT tables are sent the first time a job uses them rather than with
+    /// every job.
+    pub fn run(&mut self, mut job: Job) -> u64 {
+        for (hash, lut) in std::mem::take(&mut job.graph.luts).iter() {
+            if self.sent_luts.insert(hash) {
+                self.send(Request::AddLut { hash, lut: lut.clone() });
+            }
+        }
+        self.last_job += 1;
+        let job_id = self.last_job;
+        #[cfg(not(target_arch = "wasm32"))]
+        if let Backend::Thread(backend) = &self.backend {
+            backend.set_current(job_id);
+        }
+        self.send(Request::Run { job: job_id, job_data: job });
+        job_id
+    }
+
+    /// Cancel the run in progress, if any
+    pub fn cancel(&mut self) {
+        self.last_job += 1;
+        #[cfg(not(target_arch = "wasm32"))]
+        if let Backend::Thread(backend) = &self.backend {
+            backend.set_current(0);
+        }
+        self.send(Request::Cancel);
+    }
+
+    /// Drop cached outputs so the next run recomputes every node
+    pub fn invalidate(&mut self) {
+        self.send(Request::Invalidate);
+    }
+
+    pub fn set_limits(&mut self, limits: ImageLimits) {
+        self.send(Request::SetLimits(limits));
+    }
+
+    /// Responses received since the last call, oldest first
+    pub fn poll(&mut self) -> Vec<Response> {
+        match &mut self.backend {
+            #[cfg(not(target_arch = "wasm32"))]
+            Backend::Thread(backend) => backend.poll(),
+            #[cfg(target_arch = "wasm32")]
+            Backend::Web(backend) => backend.poll(),
+            Backend::Inline { responses, .. } => std::mem::take(responses),
+        }
+    }
+
+    fn send(&mut self, request: Request) {
+        let last_job = self.last_job;
+        match &mut self.backend {
+            #[cfg(not(target_arch = "wasm32"))]
+            Backend::Thread(backend) => backend.send(request),
+            #[cfg(target_arch = "wasm32")]
+            Backend::Web(backend) => backend.send(request),
+            Backend::Inline { state, responses } => {
+                state.handle(request, &|job| job == last_job, &mut |r| responses.push(r));
+            }
+        }
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+mod thread {
+    use std::sync::atomic::{AtomicU64, Ordering};
+    use std::sync::{mpsc, Arc};
+
+    use eframe::egui;
+
+    use super::{Request, Response, WorkerState};
+
+    /// Executors on a background thread, fed through channels
+    pub struct ThreadBackend {
+        requests: mpsc::Sender<Request>,
+        responses: mpsc::Receiver<Response>,
+        /// Job the thread should be working on; anything else is cancelled
+        current: Arc<AtomicU64>,
+    }
+
+    impl ThreadBackend {
+        pub fn spawn(ctx: egui::Context) -> Result<Self, String> {
+            let (requests, request_rx) = mpsc::channel::<Request>();
+            let (response_tx, responses) = mpsc::channel();
+            let current = Arc::new(AtomicU64::new(0));
+
+            let thread_current = current.clone();
+            std::thread::Builder::new()
+                .name("flownode-executor".to_string())
+                .spawn(move || {
+                    let mut state = WorkerState::new();
+                    let is_current = |job| thread_current.load(Ordering::Relaxed) == job;
+                    let mut send = |response| {
+                        // The app is gone if the channel is closed; the loop ends with it
+                        let _ = response_tx.send(response);
+                        ctx.request_repaint();
+                    };
+                    for request in request_rx {
+                        state.handle(request, &is_current, &mut send);
+                    }
+                })
+                .map_err(|e| e.to_string())?;
+
+            Ok(Self { requests, responses, current })
+        }
+
+        pub fn set_current(&self, job: u64) {
+            self.current.store(job, Ordering::Relaxed);
+        }
+
+        pub fn send(&self, request: Request) {
+            if self.requests.send(request).is_err() {
+                log::error!("Graph worker thread has stopped");
+            }
+        }
+
+        pub fn poll(&self) -> Vec<Response> {
+            self.responses.try_iter().collect()
+        }
+    }
+}
+
+#[cfg(target_arch = "wasm32")]
+mod web {
+    use std::cell::{Cell, RefCell};
+    use std::rc::Rc;
+
+    use eframe::egui;
+    use wasm_bindgen::closure::Closure;
+    use wasm_bindgen::JsCast;
+
+    use super::{codec, Request, Response, WorkerState};
+
+    /// Loader trunk writes for the `flownode-worker` binary; the binary's own
+    /// script is only the wasm-bindgen glue, which pool threads load too
+    const WORKER_SCRIPT: &str = "./flownode-worker_loader.js";
+
+    /// Executors in a dedicated Web Worker, talked to with transferred buffers
+    pub struct WebBackend {
+        worker: web_sys::Worker,
+        inbox: Rc<RefCell<Vec<Response>>>,
+        _on_message: Closure<dyn FnMut(web_sys::MessageEvent)>,
+    }
+
+    impl WebBackend {
+        pub fn spawn(ctx: egui::Context) -> Result<Self, String> {
+            let worker = web_sys::Worker::new(WORKER_SCRIPT).map_err(|e| format!("{:?}", e))?;
+            let inbox = Rc::new(RefCell::new(Vec::new()));
+
+            let received = inbox.clone();
+            let on_message = Closure::<dyn FnMut(web_sys::MessageEvent)>::new(move |event: web_sys::MessageEvent| {
+                let bytes = js_sys::Uint8Array::new(&event.data()).to_vec();
+                match codec::decode_response(&bytes) {
+                    Ok(response) => received.borrow_mut().push(response),
+                    Err(e) => log::error!("Bad message from graph worker: {}", e),
+                }
+                ctx.request_repaint();
+            });
+            worker.set_onmessage(Some(on_message.as_ref().unchecked_ref()));
+
+            Ok(Self { worker, inbox, _on_message: on_message })
+        }
+
+        pub fn send(&self, request: Request) {
+            let (buffer, transfer) = transferable(&codec::encode_request(&request));
+            if let Err(e) = self.worker.post_message_with_transfer(&buffer, &transfer) {
+                log::error!("Couldn't send to graph worker: {:?}", e);
+            }
+        }
+
+        pub fn poll(&self) -> Vec<Response> {
+            std::mem::take(&mut *self.inbox.borrow_mut())
+        }
+    }
+
+    /// Copy bytes into a buffer that `postMessage` can transfer rather than clone
+    fn transferable(bytes: &[u8]) -> (js_sys::ArrayBuffer, js_sys::Array) {
+        let buffer = js_sys::Uint8Array::from(bytes).buffer();
+        let transfer = js_sys::Array::of1(&buffer);
+        (buffer, transfer)
+    }
+
+    /// Entry point inside the Web Worker: serve requests until the page closes
+    pub fn serve() {
+        let scope: web_sys::DedicatedWorkerGlobalScope = js_sys::global().unchecked_into();
+        let state = Rc::new(RefCell::new(WorkerState::new()));
+        // Whether a step is waiting on the timer
+        let scheduled = Rc::new(Cell::new(false));
+        // Steps wait for the thread pool, which starts asynchronously
+        let pool_ready = Rc::new(Cell::new(!cfg!(feature = "wasm-threads")));
+
+        let send = {
+            let scope = scope.clone();
+            move |response: Response| {
+                let (buffer, transfer) = transferable(&codec::encode_response(&response));
+                if let Err(e) = scope.post_message_with_transfer(&buffer, &transfer) {
+                    log::error!("Couldn't send result to the page: {:?}", e);
+                }
+            }
+        };
+
+        // One node per tick; messages that arrived meanwhile are handled
+        // before the next, so a Cancel or a newer run stops this one there
+        let step: Rc<RefCell<Option<Closure<dyn FnMut()>>>> = Rc::new(RefCell::new(None));
+        let schedule = {
+            let (scope, scheduled, pool_ready, step) = (scope.clone(), scheduled.clone(), pool_ready.clone(), step.clone());
+            move || {
+                if !pool_ready.get() || scheduled.replace(true) {
+                    return;
+                }
+                if let Some(step) = step.borrow().as_ref() {
+                    let _ = scope.set_timeout_with_callback_and_timeout_and_arguments_0(step.as_ref().unchecked_ref(), 0);
+                }
+            }
+        };
+        *step.borrow_mut() = Some(Closure::<dyn FnMut()>::new({
+            let (state, scheduled, schedule, mut send) = (state.clone(), scheduled.clone(), schedule.clone(), send.clone());
+            move || {
+                scheduled.set(false);
+                if state.borrow_mut().step(&|_| true, &mut send) {
+                    schedule();
+                }
+            }
+        }));
+
+        #[cfg(feature = "wasm-threads")]
+        crate::threads::start({
+            let schedule = schedule.clone();
+            move || {
+                pool_ready.set(true);
+                schedule();
+            }
+        });
+
+        let on_message = Closure::<dyn FnMut(web_sys::MessageEvent)>::new(move |event: web_sys::MessageEvent| {
+            let bytes = js_sys::Uint8Array::new(&event.data()).to_vec();
+            match codec::decode_request(&bytes) {
+                Ok(Request::Run { job, job_data }) => {
+                    state.borrow_mut().start(job, job_data, &|_| true, &mut send.clone());
+                    schedule();
+                }
+                Ok(request) => state.borrow_mut().handle(request, &|_| true, &mut send.clone()),
+                Err(e) => log::error!("Bad message from the page: {}", e),
+            }
+        });
+        scope.set_onmessage(Some(on_message.as_ref().unchecked_ref()));
+        on_message.forget();
+        // Lives as long as the worker
+        std::mem::forget(step);
+    }
+}
+
+#[cfg(target_arch = "wasm32")]
+pub use web::serve;
+
+/// Byte encoding of requests and responses for `postMessage`: a JSON header
+/// followed by the raw pixels of every image it mentions, so one buffer can be
+/// transferred instead of structured-cloning each image. Only the Web Worker
+/// backend sends messages this way, but it builds everywhere so it can be
+/// tested natively.
+pub mod codec {
+    use std::collections::HashMap;
+
+    use serde_json::{json, Value};
+    use uuid::Uuid;
+
+    use super::{ExecutionStats, Job, NodeGraph, NodeOutput, Progress, Request, Response, RunResult};
+    use crate::image_data::{ImageData, ImageLimits, PixelBuffer};
+    use crate::nodes::{CompareMetrics, NodeProperties};
+
+    /// Builds the pixel section while the header is written
+    #[derive(Default)]
+    struct Writer {
+        blobs: Vec<u8>,
+    }
+
+    impl Writer {
+        fn image(&mut self, image: &ImageData) -> Value {
+            let offset = self.blobs.len();
+            let format = match image.pixels.as_ref() {
+                PixelBuffer::Rgba8(p) => {
+                    self.blobs.extend_from_slice(p);
+                    "rgba8"
+                }
+                PixelBuffer::Rgba16(p) => {
+                    self.blobs.extend(p.iter().flat_map(|v| v.to_le_bytes()));
+                    "rgba16"
+                }
+                PixelBuffer::Rgba32F(p) => {
+                    self.blobs.extend(p.iter().flat_map(|v| v.to_le_bytes()));
+                    "rgba32f"
+                }
+            };
+            json!({
+                "width": image.width,
+                "height": image.height,
+                "color_space": image.color_space,
+                "format": format,
+                "offset": offset,
+                "len": self.blobs.len() - offset,
+            })
+        }
+
+        fn output(&mut self, output: &NodeOutput) -> Value {
+            match output {
+                NodeOutput::Image(image) => json!({ "image": self.image(image) }),
+                NodeOutput::Text(text) => json!({ "text": text }),
+                // Previews carry masks as overlay images
+                NodeOutput::Mask(_) => Value::Null,
+                NodeOutput::Batch(items) => json!({ "batch": items.iter().map(|i| self.output(i)).collect::<Vec<_>>() }),
+                NodeOutput::None => Value::Null,
+            }
+        }
+
+        fn finish(self, header: Value) -> Vec<u8> {
+            let header = header.to_string();
+            let mut bytes = Vec::with_capacity(4 + header.len() + self.blobs.len());
+            bytes.extend_from_slice(&(header.len() as u32).to_le_bytes());
+            bytes.extend_from_slice(header.as_bytes());
+            bytes.extend_from_slice(&self.blobs);
+            bytes
+        }
+    }
+
+    struct Reader<'a> {
+        header: Value,
+        blobs: &'a [u8],
+    }
+
+    impl<'a> Reader<'a> {
+        fn new(bytes: &'a [u8]) -> Result<Self, String> {
+            let len = bytes.get(..4)
+                .map(|b| u32::from_le_bytes([b[0], b[1], b[2], b[3]]) as usize)
+                .ok_or("Message too short")?;
+            let end = len.checked_add(4).filter(|end| *end <= bytes.len()).ok_or("Message header truncated")?;
+            let header = serde_json::from_slice(&bytes[4..end]).map_err(|e| e.to_string())?;
+            Ok(Self { header, blobs: &bytes[end..] })
+        }
+
+        fn image(&self, value: &Value) -> Result<ImageData, String> {
+            let number = |key: &str| value[key].as_u64().ok_or_else(|| format!("Image has no {}", key));
+            let (width, height) = (number("width")? as u32, number("height")? as u32);
+            let (offset, len) = (number("offset")? as usize, number("len")? as usize);
+            let bytes = offset.checked_add(len)
+                .and_then(|end| self.blobs.get(offset..end))
+                .ok_or("Image pixels truncated")?;
+
+            let channel_bytes = match value["format"].as_str() {
+                Some("rgba8") => 1,
+                Some("rgba16") => 2,
+                Some("rgba32f") => 4,
+                other => return Err(format!("Unknown pixel format {:?}", other)),
+            };
+            if width as u64 * height as u64 * 4 * channel_bytes != len as u64 {
+                return Err(format!("Image pixels don't fill {}×{}", width, height));
+            }
+
+            let image = match channel_bytes {
+                1 => ImageData::new(bytes.to_vec(), width, height),
+                2 => ImageData::new_rgba16(
+                    bytes.chunks_exact(2).map(|b| u16::from_le_bytes([b[0], b[1]])).collect(),
+                    width,
+                    height,
+                ),
+                _ => ImageData::from_f32(
+                    bytes.chunks_exact(4).map(|b| f32::from_le_bytes([b[0], b[1], b[2], b[3]])).collect(),
+                    width,
+                    height,
+                ),
+            };
+            let color_space = serde_json::from_value(value["color_space"].clone()).map_err(|e| e.to_string())?;
+            Ok(image.with_color_space(color_space))
+        }
+
+        fn output(&self, value: &Value) -> Result<NodeOutput, String> {
+            if let Some(image) = value.get("image") {
+                Ok(NodeOutput::Image(self.image(image)?))
+            } else if let Some(text) = value.get("text").and_then(Value::as_str) {
+                Ok(NodeOutput::Text(text.to_string()))
+            } else if let Some(items) = value.get("batch").and_then(Value::as_array) {
+                Ok(NodeOutput::Batch(items.iter().map(|i| self.output(i)).collect::<Result<_, _>>()?))
+            } else {
+                Ok(NodeOutput::None)
+            }
+        }
+    }
+
+    fn job_id(header: &Value) -> Result<u64, String> {
+        header["job"].as_u64().ok_or_else(|| "Message has no job".to_string())
+    }
+
+    fn node_id(key: &str) -> Result<Uuid, String> {
+        Uuid::parse_str(key).map_err(|e| e.to_string())
+    }
+
+    fn uuid_map<T>(map: &HashMap<Uuid, T>, mut value: impl FnMut(&T) -> Value) -> Value {
+        Value::Object(map.iter().map(|(id, v)| (id.to_string(), value(v))).collect())
+    }
+
+    pub fn encode_request(request: &Request) -> Vec<u8> {
+        let mut writer = Writer::default();
+        let header = match request {
+            Request::AddImage { id, proxy, image } => json!({
+                "type": "add_image", "id": id, "proxy": proxy, "image": writer.image(image),
+            }),
+            Request::AddLut { hash, lut } => json!({ "type": "add_lut", "hash": hash, "lut": lut }),
+            Request::Run { job, job_data } => {
+                // Loaded image IDs aren't saved with the graph, so they travel separately
+                let image_ids: serde_json::Map<String, Value> = job_data.graph.nodes_iter()
+                    .filter_map(|(id, node)| match &node.properties {
+                        NodeProperties::Image { texture_id: Some(image), .. } => Some((id.to_string(), json!([image]))),
+                        NodeProperties::Bucket { image_ids, .. } => Some((id.to_string(), json!(image_ids))),
+                        _ => None,
+                    })
+                    .collect();
+                json!({
+                    "type": "run",
+                    "job": job,
+                    "graph": job_data.graph,
+                    "image_ids": image_ids,
+                    "proxy": job_data.proxy,
+                    "pixel_scale": job_data.pixel_scale,
+                    "preview_size": job_data.preview_size,
+                })
+            }
+            Request::Cancel => json!({ "type": "cancel" }),
+            Request::Invalidate => json!({ "type": "invalidate" }),
+            Request::SetLimits(limits) => json!({
+                "type": "set_limits",
+                "max_megapixels": limits.max_megapixels,
+                "max_dimension": limits.max_dimension,
+                "display_max_dimension": limits.display_max_dimension,
+                "tile_size": limits.tile_size,
+            }),
+        };
+        writer.finish(header)
+    }
+
+    pub fn decode_request(bytes: &[u8]) -> Result<Request, String> {
+        let reader = Reader::new(bytes)?;
+        let header = &reader.header;
+        match header["type"].as_str() {
+            Some("add_image") => Ok(Request::AddImage {
+                id: header["id"].as_u64().ok_or("Image has no ID")?,
+                proxy: header["proxy"].as_bool().unwrap_or(false),
+                image: reader.image(&header["image"])?,
+            }),
+            Some("add_lut") => Ok(Request::AddLut {
+                hash: header["hash"].as_u64().ok_or("LUT has no hash")?,
+                lut: serde_json::from_value(header["lut"].clone()).map_err(|e| e.to_string())?,
+            }),
+            Some("run") => {
+                let mut graph: NodeGraph = serde_json::from_value(header["graph"].clone()).map_err(|e| e.to_string())?;
+                if let Some(image_ids) = header["image_ids"].as_object() {
+                    for (id, images) in image_ids {
+                        let id = node_id(id)?;
+                        for image in images.as_array().into_iter().flatten().filter_map(Value::as_u64) {
+                            graph.set_node_image(id, image);
+                        }
+                    }
+                }
+                Ok(Request::Run {
+                    job: job_id(header)?,
+                    job_data: Job {
+                        graph,
+                        proxy: header["proxy"].as_bool().unwrap_or(false),
+                        pixel_scale: header["pixel_scale"].as_f64().unwrap_or(1.0) as f32,
+                        preview_size: header["preview_size"].as_u64().unwrap_or(256) as u32,
+                    },
+                })
+            }
+            Some("cancel") => Ok(Request::Cancel),
+            Some("invalidate") => Ok(Request::Invalidate),
+            Some("set_limits") => {
+                let defaults = ImageLimits::default();
+                Ok(Request::SetLimits(ImageLimits {
+                    max_megapixels: header["max_megapixels"].as_f64().map_or(defaults.max_megapixels, |v| v as _),
+                    max_dimension: header["max_dimension"].as_u64().map_or(defaults.max_dimension, |v| v as _),
+                    display_max_dimension: header["display_max_dimension"].as_u64().map_or(defaults.display_max_dimension, |v| v as _),
+                    tile_size: header["tile_size"].as_u64().map_or(defaults.tile_size, |v| v as _),
+                }))
+            }
+            other => Err(format!("Unknown request {:?}", other)),
+        }
+    }
+
+    pub fn encode_response(response: &Response) -> Vec<u8> {
+        let mut writer = Writer::default();
+        let header = match response {
+            Response::Progress { job, progress } => json!({
+                "type": "progress", "job": job,
+                "node": progress.node, "nodes": progress.nodes,
+                "item": progress.item, "items": progress.items,
+            }),
+            Response::Finished(result) => json!({
+                "type": "finished",
+                "job": result.job,
+                "proxy": result.proxy,
+                "outputs": uuid_map(&result.outputs, |images| {
+                    Value::Array(images.iter().map(|i| writer.image(i)).collect())
+                }),
+                "previews": uuid_map(&result.previews, |output| writer.output(output)),
+                // PSNR is infinite for identical images, which JSON can't hold
+                "compare_metrics": uuid_map(&result.compare_metrics, |m| json!({
+                    "psnr": m.psnr.is_finite().then_some(m.psnr),
+                    "ssim": m.ssim,
+                })),
+                "computed": result.stats.computed,
+                "cached": result.stats.cached,
+                "items": result.stats.items,
+            }),
+            Response::Failed { job, error } => json!({ "type": "failed", "job": job, "error": error }),
+        };
+        writer.finish(header)
+    }
+
+    pub fn decode_response(bytes: &[u8]) -> Result<Response, String> {
+        let reader = Reader::new(bytes)?;
+        let header = &reader.header;
+        let count = |key: &str| header[key].as_u64().unwrap_or(0) as usize;
+        let entries = |key: &str| header[key].as_object().into_iter().flatten();
+
+        match header["type"].as_str() {
+            Some("progress") => Ok(Response::Progress {
+                job: job_id(header)?,
+                progress: Progress { node: count("node"), nodes: count("nodes"), item: count("item"), items: count("items") },
+            }),
+            Some("finished") => {
+                let mut outputs = HashMap::new();
+                for (id, images) in entries("outputs") {
+                    let images = images.as_array().into_iter().flatten()
+                        .map(|i| reader.image(i))
+                        .collect::<Result<_, _>>()?;
+                    outputs.insert(node_id(id)?, images);
+                }
+                let mut previews = HashMap::new();
+                for (id, output) in entries("previews") {
+                    previews.insert(node_id(id)?, reader.output(output)?);
+                }
+                let mut compare_metrics = HashMap::new();
+                for (id, metrics) in entries("compare_metrics") {
+                    compare_metrics.insert(node_id(id)?, CompareMetrics {
+                        psnr: metrics["psnr"].as_f64().map_or(f32::INFINITY, |v| v as f32),
+                        ssim: metrics["ssim"].as_f64().unwrap_or(0.0) as f32,
+                    });
+                }
+                Ok(Response::Finished(Box::new(RunResult {
+                    job: job_id(header)?,
+                    proxy: header["proxy"].as_bool().unwrap_or(false),
+                    outputs,
+                    previews,
+                    compare_metrics,
+                    stats: ExecutionStats { computed: count("computed"), cached: count("cached"), items: count("items") },
+                })))
+            }
+            Some("failed") => Ok(Response::Failed {
+                job: job_id(header)?,
+                error: header["error"].as_str().unwrap_or("Unknown error").to_string(),
+            }),
+            other => Err(format!("Unknown response {:?}", other)),
+        }
+    }
+}
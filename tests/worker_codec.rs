@@ -0,0 +1,215 @@
+//! Messages between the app and the Web Worker survive the byte encoding
+//! used for `postMessage`, and damaged messages are rejected without panicking
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use flownode_canvas::color::ColorSpace;
+use flownode_canvas::executor::{ExecutionStats, NodeOutput, Progress};
+use flownode_canvas::graph::NodeGraph;
+use flownode_canvas::image_data::{ImageData, ImageLimits, PixelBuffer};
+use flownode_canvas::lut::{Lut, LutKind};
+use flownode_canvas::nodes::{CompareMetrics, Node, NodeProperties, NodeType, Vec2};
+use flownode_canvas::worker::codec::{decode_request, decode_response, encode_request, encode_response};
+use flownode_canvas::worker::{Job, Request, Response, RunResult};
+use uuid::Uuid;
+
+/// 2×1 images in each pixel format, with values that show byte order and
+/// out-of-range floats
+fn rgba8() -> ImageData {
+    ImageData::new(vec![1, 2, 3, 255, 250, 128, 0, 7], 2, 1)
+}
+
+fn rgba16() -> ImageData {
+    ImageData::new_rgba16(vec![1, 258, 65535, 0, 4660, 22136, 39612, 65534], 2, 1).with_color_space(ColorSpace::DisplayP3)
+}
+
+fn rgba32f() -> ImageData {
+    ImageData::from_f32(vec![-0.25, 0.5, 1.75, 1.0, f32::MIN_POSITIVE, 0.1, 3.0e5, 0.5], 2, 1)
+        .with_color_space(ColorSpace::LinearSrgb)
+}
+
+fn assert_same_image(actual: &ImageData, expected: &ImageData) {
+    assert_eq!((actual.width, actual.height, actual.color_space), (expected.width, expected.height, expected.color_space));
+    match (actual.pixels.as_ref(), expected.pixels.as_ref()) {
+        (PixelBuffer::Rgba8(a), PixelBuffer::Rgba8(e)) => assert_eq!(a, e),
+        (PixelBuffer::Rgba16(a), PixelBuffer::Rgba16(e)) => assert_eq!(a, e),
+        (PixelBuffer::Rgba32F(a), PixelBuffer::Rgba32F(e)) => {
+            let bits = |p: &[f32]| p.iter().map(|v| v.to_bits()).collect::<Vec<_>>();
+            assert_eq!(bits(a), bits(e));
+        }
+        (a, e) => panic!("pixel format changed from {:?} to {:?}", e, a),
+    }
+}
+
+fn assert_same_output(actual: &NodeOutput, expected: &NodeOutput) {
+    match (actual, expected) {
+        (NodeOutput::Image(a), NodeOutput::Image(e)) => assert_same_image(a, e),
+        (NodeOutput::Text(a), NodeOutput::Text(e)) => assert_eq!(a, e),
+        (NodeOutput::Batch(a), NodeOutput::Batch(e)) => {
+            assert_eq!(a.len(), e.len());
+            a.iter().zip(e).for_each(|(a, e)| assert_same_output(a, e));
+        }
+        (NodeOutput::None, NodeOutput::None) => {}
+        _ => panic!("output kind changed"),
+    }
+}
+
+fn request_round_trip(request: &Request) -> Request {
+    decode_request(&encode_request(request)).unwrap()
+}
+
+fn response_round_trip(response: &Response) -> Response {
+    decode_response(&encode_response(response)).unwrap()
+}
+
+#[test]
+fn images_round_trip_in_every_format() {
+    for (proxy, image) in [(false, rgba8()), (true, rgba16()), (false, rgba32f())] {
+        let Request::AddImage { id, proxy: decoded_proxy, image: decoded } =
+            request_round_trip(&Request::AddImage { id: 42, proxy, image: image.clone() })
+        else {
+            panic!("not an AddImage");
+        };
+        assert_eq!((id, decoded_proxy), (42, proxy));
+        assert_same_image(&decoded, &image);
+    }
+}
+
+#[test]
+fn luts_round_trip() {
+    let lut = Lut {
+        title: "Ramp".to_string(),
+        kind: LutKind::OneD,
+        size: 2,
+        domain_min: [0.0; 3],
+        domain_max: [2.0; 3],
+        table: vec![[0.0, 0.1, 0.2], [1.0, 0.9, 0.8]],
+    };
+    let hash = lut.content_hash();
+    let Request::AddLut { hash: decoded_hash, lut: decoded } = request_round_trip(&Request::AddLut { hash, lut: Arc::new(lut.clone()) }) else {
+        panic!("not an AddLut");
+    };
+    assert_eq!(decoded_hash, hash);
+    assert_eq!(*decoded, lut);
+}
+
+#[test]
+fn runs_keep_their_graph_and_loaded_images() {
+    let mut graph = NodeGraph::new();
+    let image = Node::new(NodeType::Image, Vec2::ZERO);
+    let bucket = Node::new(NodeType::Bucket, Vec2::new(0.0, 200.0));
+    let (image_id, bucket_id) = (image.id, bucket.id);
+    graph.insert_node(image);
+    graph.insert_node(bucket);
+    graph.set_node_image(image_id, 7);
+    graph.set_node_image(bucket_id, 8);
+    graph.set_node_image(bucket_id, 9);
+
+    let job = Job { graph: graph.clone(), proxy: true, pixel_scale: 0.25, preview_size: 128 };
+    let Request::Run { job: id, job_data } = request_round_trip(&Request::Run { job: 3, job_data: job }) else {
+        panic!("not a Run");
+    };
+    assert_eq!(id, 3);
+    assert_eq!((job_data.proxy, job_data.pixel_scale, job_data.preview_size), (true, 0.25, 128));
+    assert_eq!(job_data.graph.fingerprint(), graph.fingerprint());
+    assert!(matches!(job_data.graph.nodes[&image_id].properties, NodeProperties::Image { texture_id: Some(7), .. }));
+    let NodeProperties::Bucket { image_ids, .. } = &job_data.graph.nodes[&bucket_id].properties else {
+        panic!("not a Bucket");
+    };
+    assert_eq!(image_ids, &[8, 9]);
+}
+
+#[test]
+fn control_requests_round_trip() {
+    assert!(matches!(request_round_trip(&Request::Cancel), Request::Cancel));
+    assert!(matches!(request_round_trip(&Request::Invalidate), Request::Invalidate));
+
+    let limits = ImageLimits { max_dimension: 1234, max_megapixels: 12.5, display_max_dimension: 999, tile_size: 256 };
+    let Request::SetLimits(decoded) = request_round_trip(&Request::SetLimits(limits)) else {
+        panic!("not SetLimits");
+    };
+    assert_eq!(decoded, limits);
+}
+
+#[test]
+fn progress_and_failures_round_trip() {
+    let progress = Progress { node: 2, nodes: 5, item: 3, items: 10 };
+    let Response::Progress { job, progress: decoded } = response_round_trip(&Response::Progress { job: 9, progress }) else {
+        panic!("not Progress");
+    };
+    assert_eq!(job, 9);
+    assert_eq!((decoded.node, decoded.nodes, decoded.item, decoded.items), (2, 5, 3, 10));
+
+    let Response::Failed { job, error } = response_round_trip(&Response::Failed { job: 4, error: "Out of memory".to_string() }) else {
+        panic!("not Failed");
+    };
+    assert_eq!((job, error.as_str()), (4, "Out of memory"));
+}
+
+/// A run with a single output, a batch, every kind of preview and both
+/// finite and infinite PSNR, for the node IDs given
+fn finished([single, batch, identical, different]: [Uuid; 4]) -> RunResult {
+    RunResult {
+        job: 12,
+        proxy: false,
+        outputs: HashMap::from([
+            (single, vec![rgba32f()]),
+            // One image per batch item
+            (batch, vec![rgba8(), rgba16(), rgba32f()]),
+        ]),
+        previews: HashMap::from([
+            (single, NodeOutput::Image(rgba8())),
+            (batch, NodeOutput::Batch(vec![NodeOutput::Image(rgba16()), NodeOutput::Text("caption".to_string())])),
+            (identical, NodeOutput::Text("prompt".to_string())),
+            (different, NodeOutput::None),
+        ]),
+        compare_metrics: HashMap::from([
+            // Identical images have infinite PSNR
+            (identical, CompareMetrics { psnr: f32::INFINITY, ssim: 1.0 }),
+            (different, CompareMetrics { psnr: 31.5, ssim: 0.875 }),
+        ]),
+        stats: ExecutionStats { computed: 4, cached: 2, items: 3 },
+    }
+}
+
+#[test]
+fn finished_runs_round_trip() {
+    let ids = [Uuid::new_v4(), Uuid::new_v4(), Uuid::new_v4(), Uuid::new_v4()];
+    let expected = finished(ids);
+    let Response::Finished(decoded) = response_round_trip(&Response::Finished(Box::new(finished(ids)))) else {
+        panic!("not Finished");
+    };
+    assert_eq!((decoded.job, decoded.proxy), (12, false));
+    assert_eq!(decoded.outputs.len(), expected.outputs.len());
+    for (id, images) in &expected.outputs {
+        assert_eq!(decoded.outputs[id].len(), images.len());
+        decoded.outputs[id].iter().zip(images).for_each(|(a, e)| assert_same_image(a, e));
+    }
+    assert_eq!(decoded.previews.len(), expected.previews.len());
+    for (id, preview) in &expected.previews {
+        assert_same_output(&decoded.previews[id], preview);
+    }
+    assert_eq!(decoded.compare_metrics, expected.compare_metrics);
+    assert_eq!((decoded.stats.computed, decoded.stats.cached, decoded.stats.items), (4, 2, 3));
+}
+
+#[test]
+fn truncated_messages_are_rejected() {
+    let messages = [
+        encode_request(&Request::AddImage { id: 1, proxy: false, image: rgba16() }),
+        encode_response(&Response::Finished(Box::new(finished([Uuid::new_v4(); 4])))),
+    ];
+    for (i, bytes) in messages.iter().enumerate() {
+        for len in 0..bytes.len() {
+            let truncated = &bytes[..len];
+            let rejected = if i == 0 { decode_request(truncated).is_err() } else { decode_response(truncated).is_err() };
+            assert!(rejected, "message {} cut to {} of {} bytes was accepted", i, len, bytes.len());
+        }
+    }
+
+    // A header length past the end of the message
+    let mut bytes = encode_request(&Request::Cancel);
+    bytes[..4].copy_from_slice(&u32::MAX.to_le_bytes());
+    assert!(decode_request(&bytes).is_err());
+}